@@ -0,0 +1,345 @@
+use crate::library::ast::types::Type;
+use crate::library::lexeme::definition::TokenType;
+use crate::library::lexeme::literal::Prefix;
+use crate::library::lexeme::number::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiteralKind {
+    Integer(Number),
+    Float(Number),
+    Char(Prefix),
+    /// adjacent string literals are kept in one, `"a" "b"`
+    Str(Prefix),
+    Bool,
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    And,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignOp {
+    Assign,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Plus,
+    Minus,
+    Not,
+    BitNot,
+    Deref,
+    AddressOf,
+    PreIncrement,
+    PreDecrement,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostfixOp {
+    Increment,
+    Decrement,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SizeOfArg {
+    Type(Type),
+    Expr(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal {
+        kind: LiteralKind,
+        value: String,
+    },
+    Ident(String),
+    /// `this`, the receiver of a method
+    This,
+    /// qualified name, `std::cout`, `A::count`
+    Path(Vec<String>),
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expr>,
+    },
+    Postfix {
+        op: PostfixOp,
+        operand: Box<Expr>,
+    },
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Assign {
+        op: AssignOp,
+        target: Box<Expr>,
+        value: Box<Expr>,
+    },
+    Call {
+        callee: Box<Expr>,
+        args: Vec<Expr>,
+    },
+    Index {
+        base: Box<Expr>,
+        index: Box<Expr>,
+    },
+    /// `base.field` or `base->field` when `arrow` is set
+    Member {
+        base: Box<Expr>,
+        field: String,
+        arrow: bool,
+    },
+    SizeOf(SizeOfArg),
+    /// `alignof(type)`
+    AlignOf(Type),
+    /// `(type) expr` and the functional form `type(expr)`
+    Cast {
+        ty: Type,
+        expr: Box<Expr>,
+    },
+    /// `cond ? then : otherwise`
+    Conditional {
+        cond: Box<Expr>,
+        then: Box<Expr>,
+        otherwise: Box<Expr>,
+    },
+    /// `a, b`
+    Comma(Vec<Expr>),
+    /// `#x` in the body of a macro, the argument as a string
+    Stringize(String),
+    /// token the parser could not place in an expression, kept as is
+    Verbatim(String),
+}
+
+impl BinaryOp {
+    pub fn from_token(typ: TokenType) -> Option<BinaryOp> {
+        let op = match typ {
+            TokenType::Plus => BinaryOp::Add,
+            TokenType::Minus => BinaryOp::Sub,
+            TokenType::Multiplication => BinaryOp::Mul,
+            TokenType::Divide => BinaryOp::Div,
+            TokenType::Module => BinaryOp::Rem,
+            TokenType::BitwiseAnd => BinaryOp::BitAnd,
+            TokenType::BitwiseOr => BinaryOp::BitOr,
+            TokenType::BitwiseXor => BinaryOp::BitXor,
+            TokenType::BitwiseLeftShift => BinaryOp::Shl,
+            TokenType::BitwiseRightShift => BinaryOp::Shr,
+            TokenType::LogicalAnd => BinaryOp::And,
+            TokenType::LogicalOr => BinaryOp::Or,
+            TokenType::Equal => BinaryOp::Eq,
+            TokenType::NotEqual => BinaryOp::Ne,
+            TokenType::LessThan => BinaryOp::Lt,
+            TokenType::LessThanOrEqual => BinaryOp::Le,
+            TokenType::GreaterThan => BinaryOp::Gt,
+            TokenType::GreaterThanOrEqual => BinaryOp::Ge,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// c/c++ precedence of the operator, higher binds tighter
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::BitOr => 3,
+            BinaryOp::BitXor => 4,
+            BinaryOp::BitAnd => 5,
+            BinaryOp::Eq | BinaryOp::Ne => 6,
+            BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => 7,
+            BinaryOp::Shl | BinaryOp::Shr => 8,
+            BinaryOp::Add | BinaryOp::Sub => 9,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 10,
+        }
+    }
+
+    pub fn is_comparison(self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge
+        )
+    }
+
+    /// the operator as written in both c/c++ and rust
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::BitAnd => "&",
+            BinaryOp::BitOr => "|",
+            BinaryOp::BitXor => "^",
+            BinaryOp::Shl => "<<",
+            BinaryOp::Shr => ">>",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+        }
+    }
+}
+
+impl AssignOp {
+    pub fn from_token(typ: TokenType) -> Option<AssignOp> {
+        let op = match typ {
+            TokenType::Assignment => AssignOp::Assign,
+            TokenType::PlusEqual => AssignOp::Add,
+            TokenType::MinusEqual => AssignOp::Sub,
+            TokenType::MultiplyEqual => AssignOp::Mul,
+            TokenType::DivideEqual => AssignOp::Div,
+            TokenType::ModuleEqual => AssignOp::Rem,
+            TokenType::AndEqual => AssignOp::BitAnd,
+            TokenType::OrEqual => AssignOp::BitOr,
+            TokenType::XorEqual => AssignOp::BitXor,
+            TokenType::LeftShiftEqual => AssignOp::Shl,
+            TokenType::RightShiftEqual => AssignOp::Shr,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AssignOp::Assign => "=",
+            AssignOp::Add => "+=",
+            AssignOp::Sub => "-=",
+            AssignOp::Mul => "*=",
+            AssignOp::Div => "/=",
+            AssignOp::Rem => "%=",
+            AssignOp::BitAnd => "&=",
+            AssignOp::BitOr => "|=",
+            AssignOp::BitXor => "^=",
+            AssignOp::Shl => "<<=",
+            AssignOp::Shr => ">>=",
+        }
+    }
+}
+
+impl UnaryOp {
+    pub fn from_token(typ: TokenType) -> Option<UnaryOp> {
+        let op = match typ {
+            TokenType::Plus => UnaryOp::Plus,
+            TokenType::Minus => UnaryOp::Minus,
+            TokenType::LogicalNot => UnaryOp::Not,
+            TokenType::BitwiseNegate => UnaryOp::BitNot,
+            TokenType::Multiplication => UnaryOp::Deref,
+            TokenType::BitwiseAnd | TokenType::AddressOf => UnaryOp::AddressOf,
+            TokenType::Increment => UnaryOp::PreIncrement,
+            TokenType::Decrement => UnaryOp::PreDecrement,
+            _ => return None,
+        };
+        Some(op)
+    }
+}
+
+impl Expr {
+    pub fn ident(name: &str) -> Expr {
+        Expr::Ident(name.to_string())
+    }
+
+    /// true for `1` and `true`, used to spot infinite loops
+    pub fn is_always_true(&self) -> bool {
+        match self {
+            Expr::Literal { kind, value } => {
+                (matches!(kind, LiteralKind::Integer(_)) && value == "1")
+                    || (*kind == LiteralKind::Bool && value == "true")
+            }
+            _ => false,
+        }
+    }
+
+    /**
+     * visit:
+     * calls `f` on the expression and then on each of its sub expressions,
+     * the array lengths of the types it names included
+     */
+    pub fn visit<F: FnMut(&Expr)>(&self, f: &mut F) {
+        f(self);
+        match self {
+            Expr::Unary { operand, .. } | Expr::Postfix { operand, .. } => operand.visit(f),
+            Expr::Binary { lhs, rhs, .. } => {
+                lhs.visit(f);
+                rhs.visit(f);
+            }
+            Expr::Assign { target, value, .. } => {
+                target.visit(f);
+                value.visit(f);
+            }
+            Expr::Call { callee, args } => {
+                callee.visit(f);
+                args.iter().for_each(|arg| arg.visit(f));
+            }
+            Expr::Index { base, index } => {
+                base.visit(f);
+                index.visit(f);
+            }
+            Expr::Member { base, .. } => base.visit(f),
+            Expr::SizeOf(SizeOfArg::Expr(value)) => value.visit(f),
+            Expr::SizeOf(SizeOfArg::Type(ty)) | Expr::AlignOf(ty) => ty.visit(f),
+            Expr::Cast { ty, expr } => {
+                ty.visit(f);
+                expr.visit(f);
+            }
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                cond.visit(f);
+                then.visit(f);
+                otherwise.visit(f);
+            }
+            Expr::Comma(list) => list.iter().for_each(|expr| expr.visit(f)),
+            _ => {}
+        }
+    }
+
+    /// true if the expression is a string or character literal
+    pub fn is_text_literal(&self) -> bool {
+        matches!(
+            self,
+            Expr::Literal {
+                kind: LiteralKind::Str(_),
+                ..
+            } | Expr::Literal {
+                kind: LiteralKind::Char(_),
+                ..
+            }
+        )
+    }
+}
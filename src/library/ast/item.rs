@@ -0,0 +1,255 @@
+use crate::library::ast::expr::Expr;
+use crate::library::ast::stmt::{Block, Comment, Declaration, Declarator, Stmt};
+use crate::library::ast::types::Type;
+use crate::library::doc::DocType;
+use crate::library::lexeme::definition::TokenType;
+use crate::library::lexeme::span::Span;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TranslationUnit {
+    pub items: Vec<Item>,
+}
+
+impl TranslationUnit {
+    /// paths of the headers translated into modules, in #include order
+    pub fn headers(&self) -> Vec<String> {
+        fn collect(items: &[Item], headers: &mut Vec<String>) {
+            for item in items {
+                match item {
+                    Item::Include { header, .. } if !headers.contains(header) => {
+                        headers.push(header.clone())
+                    }
+                    Item::Cfg { items, .. } => collect(items, headers),
+                    _ => {}
+                }
+            }
+        }
+        let mut headers = Vec::new();
+        collect(&self.items, &mut headers);
+        headers
+    }
+
+    /// names of the free functions the unit defines, `main` aside
+    pub fn functions(&self) -> Vec<String> {
+        fn collect(items: &[Item], functions: &mut Vec<String>) {
+            for item in items {
+                match item {
+                    Item::Function(Function {
+                        owner: None,
+                        name: FunctionName::Ident(name),
+                        body: Some(_),
+                        ..
+                    }) if name != "main" => functions.push(name.clone()),
+                    Item::Cfg { items, .. } => collect(items, functions),
+                    _ => {}
+                }
+            }
+        }
+        let mut functions = Vec::new();
+        collect(&self.items, &mut functions);
+        functions
+    }
+
+    /// the unit defines `main`, it is the binary of a crate
+    pub fn has_main(&self) -> bool {
+        fn find(items: &[Item]) -> bool {
+            items.iter().any(|item| match item {
+                Item::Function(function) => function.is_main() && function.body.is_some(),
+                Item::Cfg { items, .. } => find(items),
+                _ => false,
+            })
+        }
+        find(&self.items)
+    }
+}
+
+/// source text of a construct that is kept as it is
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceText {
+    pub text: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FunctionName {
+    Ident(String),
+    /// `operator==` and friends, identified by the operator token
+    Operator(TokenType),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    /// unnamed parameters are allowed in declarations
+    pub name: Option<String>,
+    pub ty: Type,
+    /// comments in the parameter list, before the parameter or after it on its line
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub ret: Type,
+    /// `A` in `int A::get()`
+    pub owner: Option<String>,
+    pub name: FunctionName,
+    pub params: Vec<Param>,
+    /// `None` for a prototype
+    pub body: Option<Block>,
+    /// method declared `const`, it does not change the object it is called on
+    pub is_const: bool,
+    /// virtual method declared `= 0`, it has no definition
+    pub is_pure: bool,
+    /// `...` ending the parameters, rust functions take no variadic arguments
+    pub variadic: Option<Span>,
+    /// comments before the function, its doc, and after it
+    pub comments: Vec<Comment>,
+    pub span: Span,
+}
+
+impl Function {
+    pub fn is_main(&self) -> bool {
+        self.owner.is_none() && self.name == FunctionName::Ident("main".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Public,
+    Private,
+    Protected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordKind {
+    Struct,
+    Class,
+    Union,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub access: Access,
+    pub decl: Declarator,
+    /// cfg predicates of the #if branches the member is in
+    pub cfg: Vec<String>,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Method {
+    pub access: Access,
+    pub function: Function,
+    /// cfg predicates of the #if branches the member is in
+    pub cfg: Vec<String>,
+}
+
+/// struct, class or union definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub kind: RecordKind,
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub methods: Vec<Method>,
+    /// constructors are not translated, their source text is kept
+    pub constructors: Vec<SourceText>,
+    /// pure virtual methods are not translated, their source text is kept
+    pub pure_methods: Vec<SourceText>,
+    /// comments before the record, and the ones of its body left after the members
+    pub comments: Vec<Comment>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enumerator {
+    pub name: String,
+    /// explicit value, `RED = 1`
+    pub value: Option<Expr>,
+    pub comments: Vec<Comment>,
+    pub span: Span,
+}
+
+/// enum or enum class definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    /// empty for an anonymous enum, whose enumerators are constants
+    pub name: String,
+    /// `enum class`, its enumerators are only named through the enum
+    pub scoped: bool,
+    /// `enum E : uint8_t`
+    pub underlying: Option<Type>,
+    pub enumerators: Vec<Enumerator>,
+    /// comments before the enum
+    pub comments: Vec<Comment>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAlias {
+    pub name: String,
+    pub ty: Type,
+    pub span: Span,
+}
+
+/// object-like macro with a constant value, `#define MAX_CONN 64`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constant {
+    pub name: String,
+    pub value: Expr,
+    pub span: Span,
+}
+
+/// what a function-like macro expands to
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroBody {
+    Expr(Expr),
+    /// statements, `do { .. } while (0)` or none at all
+    Stmts(Vec<Stmt>),
+}
+
+/// function-like macro, `#define SQUARE(x) ((x) * (x))`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Macro {
+    pub name: String,
+    pub params: Vec<String>,
+    /// the extra arguments are `__VA_ARGS__`
+    pub variadic: bool,
+    pub body: MacroBody,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Function(Function),
+    Record(Record),
+    Enum(Enum),
+    Typedef(TypeAlias),
+    Global(Declaration),
+    Constant(Constant),
+    Macro(Macro),
+    /// #if branch kept for the configurations of its cfg predicate
+    Cfg {
+        predicate: String,
+        items: Vec<Item>,
+    },
+    /// preprocessor line, `#include <stdio.h>`
+    Directive(SourceText),
+    /// #include of a header translated into its own module, with the
+    /// path the header was found at
+    Include {
+        header: String,
+        span: Span,
+    },
+    Comment(Comment),
+    /// construct crust can not translate, kept as source text next to its doc
+    Unsupported {
+        doc: DocType,
+        text: String,
+        span: Span,
+    },
+    /// construct passed through to the output as it is
+    Verbatim(String),
+    /// statement found outside of any function
+    Stmt(Stmt),
+    /// declaration the parser could not read, kept as source text
+    Error(SourceText),
+}
@@ -0,0 +1,18 @@
+//! Typed C/C++ syntax tree produced by the parser and consumed by the code generator.
+//!
+//! The tree mirrors the source program: a translation unit holds top level items,
+//! items hold statements and statements hold expressions. No Rust specific decision
+//! is taken here, so later passes can inspect or rewrite the tree before emission.
+#![allow(dead_code)]
+
+pub mod expr;
+pub mod item;
+pub mod scope;
+pub mod stmt;
+pub mod types;
+
+pub use self::expr::*;
+pub use self::item::*;
+pub use self::scope::*;
+pub use self::stmt::*;
+pub use self::types::*;
@@ -0,0 +1,178 @@
+use crate::library::ast::types::Type;
+use crate::library::lexeme::span::Span;
+
+/// The construct a scope is opened by, innermost scopes are searched first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    Global,
+    /// body of a namespace, not opened while namespaces are kept as comments
+    Namespace,
+    /// members of a struct, class or union
+    Class,
+    /// parameters of a function
+    Function,
+    Block,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    /// variable, parameter or field
+    Variable,
+    Function,
+    /// typedef, struct, class or union, `ty` is the type it names
+    Type,
+}
+
+/// A declaration seen in a scope
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// declared type with its modifiers, pointers and array dimensions
+    pub ty: Type,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    /// in declaration order, a redeclaration comes after the declaration
+    pub symbols: Vec<Symbol>,
+}
+
+/**
+ * SymbolTable:
+ * stack of the scopes open at a point of the translation unit.
+ * The global scope is always open, a scope is dropped with its
+ * symbols when the construct opening it ends
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolTable {
+    scopes: Vec<Scope>,
+}
+
+impl Default for SymbolTable {
+    fn default() -> SymbolTable {
+        SymbolTable::new()
+    }
+}
+
+impl SymbolTable {
+    pub fn new() -> SymbolTable {
+        SymbolTable {
+            scopes: vec![Scope {
+                kind: ScopeKind::Global,
+                symbols: Vec::new(),
+            }],
+        }
+    }
+
+    pub fn enter(&mut self, kind: ScopeKind) {
+        self.scopes.push(Scope {
+            kind,
+            symbols: Vec::new(),
+        });
+    }
+
+    /// closes the innermost scope, the global scope stays open
+    pub fn leave(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+
+    /// number of open scopes, the global scope included
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    pub fn current(&self) -> &Scope {
+        &self.scopes[self.scopes.len() - 1]
+    }
+
+    /// declares the symbol in the innermost scope
+    pub fn declare(&mut self, symbol: Symbol) {
+        let last = self.scopes.len() - 1;
+        self.scopes[last].symbols.push(symbol);
+    }
+
+    /// the declaration `name` refers to, inner declarations shadow outer ones
+    pub fn lookup(&self, name: &str) -> Option<&Symbol> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.symbols.iter().rev().find(|s| s.name == name))
+    }
+
+    /// the declaration of `name` in the innermost scope only
+    pub fn lookup_local(&self, name: &str) -> Option<&Symbol> {
+        self.current().symbols.iter().rev().find(|s| s.name == name)
+    }
+
+    /// `name` refers to a type where it is used, not to a variable hiding it
+    pub fn is_type(&self, name: &str) -> bool {
+        self.lookup(name)
+            .is_some_and(|symbol| symbol.kind == SymbolKind::Type)
+    }
+
+    /// the variable `name` refers to
+    pub fn variable(&self, name: &str) -> Option<&Symbol> {
+        self.lookup(name)
+            .filter(|symbol| symbol.kind == SymbolKind::Variable)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ScopeKind, Symbol, SymbolKind, SymbolTable};
+    use crate::library::ast::types::{BaseType, Type};
+    use crate::library::lexeme::definition::TokenType;
+    use crate::library::lexeme::span::Span;
+
+    fn symbol(name: &str, kind: SymbolKind, ty: Type) -> Symbol {
+        Symbol {
+            name: name.to_string(),
+            kind,
+            ty,
+            span: Span::default(),
+        }
+    }
+
+    #[test]
+    fn test_that_inner_declarations_shadow_outer_ones() {
+        let mut table = SymbolTable::new();
+        let point = Type::new(BaseType::Struct("point".to_string()));
+        table.declare(symbol("point", SymbolKind::Type, point));
+        table.enter(ScopeKind::Function);
+        let mut ptr = Type::primitive(TokenType::Integer);
+        ptr.pointer = 1;
+        table.declare(symbol("point", SymbolKind::Variable, ptr));
+        assert!(!table.is_type("point"));
+        assert!(table.variable("point").unwrap().ty.is_pointer());
+        assert!(table.lookup_local("point").is_some());
+
+        table.enter(ScopeKind::Block);
+        assert!(table.lookup_local("point").is_none());
+        table.leave();
+        table.leave();
+        assert!(table.is_type("point"));
+        assert!(table.variable("point").is_none());
+    }
+
+    #[test]
+    fn test_that_symbols_are_dropped_with_their_scope() {
+        let mut table = SymbolTable::new();
+        table.enter(ScopeKind::Function);
+        table.declare(symbol(
+            "i",
+            SymbolKind::Variable,
+            Type::primitive(TokenType::Integer),
+        ));
+        table.leave();
+        assert!(table.lookup("i").is_none());
+        // the global scope is never closed
+        table.leave();
+        assert_eq!(table.depth(), 1);
+        assert_eq!(table.current().kind, ScopeKind::Global);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::library::ast::expr::Expr;
+use crate::library::ast::item::SourceText;
+use crate::library::ast::types::Type;
+use crate::library::doc::DocType;
+use crate::library::lexeme::span::Span;
+
+/// A comment of the source, kept next to the construct it was found with
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub text: String,
+    /// found inside the construct before it or on its last line, rather
+    /// than on lines of its own
+    pub trailing: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Initializer {
+    Expr(Expr),
+    /// brace enclosed list `{1, 2, 3}`
+    List(Vec<Expr>),
+}
+
+/// One declared name, `*a = &b` in `int *a = &b, c;`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declarator {
+    pub name: String,
+    pub ty: Type,
+    pub init: Option<Initializer>,
+    pub span: Span,
+}
+
+/// A declaration statement, every declarator shares the same base type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    pub declarators: Vec<Declarator>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Block {
+    pub stmts: Vec<Stmt>,
+}
+
+/// A group of `case` labels sharing the same body, `None` label is `default`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    pub labels: Vec<Option<Expr>>,
+    pub body: Vec<Stmt>,
+}
+
+/// A statement, the span of each one runs from its first token to its `;` or body
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Decl(Declaration),
+    Expr(Expr, Span),
+    If {
+        cond: Expr,
+        then: Box<Stmt>,
+        otherwise: Option<Box<Stmt>>,
+        span: Span,
+    },
+    While {
+        cond: Expr,
+        body: Box<Stmt>,
+        span: Span,
+    },
+    DoWhile {
+        body: Box<Stmt>,
+        cond: Expr,
+        span: Span,
+    },
+    For {
+        init: Option<Box<Stmt>>,
+        cond: Option<Expr>,
+        step: Option<Expr>,
+        body: Box<Stmt>,
+        span: Span,
+    },
+    Switch {
+        scrutinee: Expr,
+        cases: Vec<Case>,
+        span: Span,
+    },
+    Return(Option<Expr>, Span),
+    Break(Span),
+    Continue(Span),
+    Block(Block),
+    /// #if branch kept for the configurations of its cfg predicate
+    Cfg {
+        predicate: String,
+        stmts: Vec<Stmt>,
+    },
+    Comment(Comment),
+    /// construct crust can not translate, kept as source text next to its doc
+    Unsupported {
+        doc: DocType,
+        text: String,
+        span: Span,
+    },
+    Empty,
+    /// statement the parser could not read, kept as source text
+    Error(SourceText),
+}
+
+impl Stmt {
+    /// statements of the body, a single statement body is treated as a block of one
+    pub fn as_slice(&self) -> &[Stmt] {
+        match self {
+            Stmt::Block(block) => &block.stmts,
+            _ => std::slice::from_ref(self),
+        }
+    }
+
+    /// calls `f` on every expression of the statement and its nested statements
+    pub fn visit<F: FnMut(&Expr)>(&self, f: &mut F) {
+        match self {
+            Stmt::Decl(decl) => {
+                for declarator in &decl.declarators {
+                    declarator.ty.visit(f);
+                    match declarator.init {
+                        Some(Initializer::Expr(ref value)) => value.visit(f),
+                        Some(Initializer::List(ref values)) => {
+                            values.iter().for_each(|value| value.visit(f))
+                        }
+                        None => {}
+                    }
+                }
+            }
+            Stmt::Expr(expr, _) | Stmt::Return(Some(expr), _) => expr.visit(f),
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                cond.visit(f);
+                then.visit(f);
+                if let Some(otherwise) = otherwise {
+                    otherwise.visit(f);
+                }
+            }
+            Stmt::While { cond, body, .. } | Stmt::DoWhile { body, cond, .. } => {
+                cond.visit(f);
+                body.visit(f);
+            }
+            Stmt::For {
+                init,
+                cond,
+                step,
+                body,
+                ..
+            } => {
+                if let Some(init) = init {
+                    init.visit(f);
+                }
+                cond.iter().chain(step).for_each(|expr| expr.visit(f));
+                body.visit(f);
+            }
+            Stmt::Switch {
+                scrutinee, cases, ..
+            } => {
+                scrutinee.visit(f);
+                for case in cases {
+                    case.labels
+                        .iter()
+                        .flatten()
+                        .for_each(|label| label.visit(f));
+                    case.body.iter().for_each(|stmt| stmt.visit(f));
+                }
+            }
+            Stmt::Block(block) => block.stmts.iter().for_each(|stmt| stmt.visit(f)),
+            Stmt::Cfg { stmts, .. } => stmts.iter().for_each(|stmt| stmt.visit(f)),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::library::ast::expr::Expr;
+use crate::library::lexeme::definition::TokenType;
+use crate::library::parser::rust_type::Modifier;
+
+/// The type a declaration starts with, before any pointer or array declarator is applied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BaseType {
+    /// builtin c/c++ type, identified by the token type of its keyword
+    Primitive(TokenType),
+    /// class, typedef or any other user defined type referred by its name
+    Named(String),
+    /// `struct name`
+    Struct(String),
+    /// `union name`
+    Union(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Type {
+    pub base: BaseType,
+    /// signedness of the primitive type
+    pub modifier: Modifier,
+    pub is_const: bool,
+    pub is_static: bool,
+    /// number of `*` in the declarator
+    pub pointer: usize,
+    /// declared as c++ reference `&`
+    pub reference: bool,
+    /// array dimensions, `None` for `[]`
+    pub array: Vec<Option<Expr>>,
+}
+
+impl Type {
+    pub fn new(base: BaseType) -> Type {
+        Type {
+            base,
+            modifier: Modifier::Default,
+            is_const: false,
+            is_static: false,
+            pointer: 0,
+            reference: false,
+            array: Vec::new(),
+        }
+    }
+
+    pub fn primitive(typ: TokenType) -> Type {
+        Type::new(BaseType::Primitive(typ))
+    }
+
+    pub fn is_void(&self) -> bool {
+        self.base == BaseType::Primitive(TokenType::Void) && self.pointer == 0
+    }
+
+    pub fn is_pointer(&self) -> bool {
+        self.pointer > 0
+    }
+
+    /// calls `f` on the expressions of the array lengths
+    pub fn visit<F: FnMut(&Expr)>(&self, f: &mut F) {
+        for len in self.array.iter().flatten() {
+            len.visit(f);
+        }
+    }
+
+    /// name of the user defined type, if the type is not a primitive
+    pub fn type_name(&self) -> Option<&str> {
+        match self.base {
+            BaseType::Named(ref name) | BaseType::Struct(ref name) | BaseType::Union(ref name) => {
+                Some(name)
+            }
+            BaseType::Primitive(_) => None,
+        }
+    }
+}
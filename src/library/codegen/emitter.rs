@@ -0,0 +1,2659 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use crate::library::ast::*;
+use crate::library::codegen::helper::*;
+use crate::library::codegen::mutability::{Callees, Mutability, Writes};
+use crate::library::diagnostics::Diagnostics;
+use crate::library::doc::DocType::*;
+use crate::library::doc::{DocType, CRUST_BANNER};
+use crate::library::lexeme::definition::TokenType;
+use crate::library::lexeme::number::{Number, Radix, Suffix};
+use crate::library::lexeme::span::Span;
+use crate::library::parser::rust_type::*;
+use crate::library::preprocessor::expression::{apply, integer_value};
+
+struct Emitter<'a> {
+    // inferred by default, strict and loose override it
+    mutability: Mutability,
+    // variables written by the function being translated
+    writes: Writes,
+    // return type of the function being translated
+    ret: Type,
+    //default false, declarations outside of blocks are globals
+    in_block_stmnt: bool,
+    //default false
+    in_switch: bool,
+    //default false
+    in_main: bool,
+    // structure book keeping, used to initialize struct variables
+    records: Vec<&'a Record>,
+    // enums, their enumerators are named through them and converted to integers
+    enums: Vec<&'a Enum>,
+    // #define constants, their type is inferred from the value
+    constants: Vec<&'a Constant>,
+    // functions, calls of them are typed from their declaration
+    functions: Vec<&'a Function>,
+    // function-like macros, invoked with `!` unless translated into functions
+    macros: Vec<&'a Macro>,
+    // macros translated into functions, with their parameter and return type
+    signatures: HashMap<String, (String, String)>,
+    // parameters of the macro_rules! being written, `$x`
+    macro_params: Vec<String>,
+    // predicates of the #if branches kept as cfg being written
+    cfg: Vec<String>,
+    // the unit is a module, its items are public
+    module: bool,
+    // module paths of the included headers, in a crate translated from a project
+    modules: Option<&'a HashMap<String, String>>,
+    // names of the modules of headers not named after the header
+    renamed: Option<&'a HashMap<String, String>>,
+    // module paths of the functions defined by other modules of the crate
+    defined: Option<&'a HashMap<String, String>>,
+    // imports of the std counterparts of system headers, written once
+    imports: Vec<&'static str>,
+    // declarations of the scopes open at the statement being written
+    symbols: SymbolTable,
+    // what could not be translated or was translated with a choice to check
+    diagnostics: Diagnostics,
+}
+
+/// Where the translation of a unit goes in the crate
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Placement<'a> {
+    /// the unit is a module of the crate, its items are public so that
+    /// the units including it can import them
+    pub module: bool,
+    /// module paths of the headers the unit includes, in a crate translated
+    /// from a whole project, an empty path for the header of the unit itself.
+    /// Without them the unit declares the modules of its headers, or imports
+    /// them from the crate root when it is a module
+    pub modules: Option<&'a HashMap<String, String>>,
+    /// names of the modules of the headers which are not named after the
+    /// header, as a translated source has that name, `util.h` -> `util_h`
+    pub renamed: Option<&'a HashMap<String, String>>,
+    /// module paths of the functions defined by the other modules of a crate
+    /// translated from a project, the prototypes of the unit import them so
+    /// that the units including it find them, `pub use crate::src::util::add;`
+    pub defined: Option<&'a HashMap<String, String>>,
+    /// units of the included headers, their structures and macros are used
+    pub headers: &'a [&'a TranslationUnit],
+}
+
+/// Walks the translation unit and generates the equivalent rust source,
+/// reporting what needs a look into `diagnostics`
+pub fn generate(
+    unit: &TranslationUnit,
+    mutability: Mutability,
+    diagnostics: &mut Diagnostics,
+) -> String {
+    generate_in(unit, mutability, Placement::default(), diagnostics)
+}
+
+/// Generates the rust source of a unit placed in the crate as `placement` says
+pub fn generate_in<'a>(
+    unit: &'a TranslationUnit,
+    mutability: Mutability,
+    placement: Placement<'a>,
+    diagnostics: &mut Diagnostics,
+) -> String {
+    let items: Vec<&Item> =
+        placement
+            .headers
+            .iter()
+            .fold(flatten(&unit.items), |mut items, header| {
+                items.append(&mut flatten(&header.items));
+                items
+            });
+    let mut emitter = Emitter {
+        mutability,
+        writes: Writes::default(),
+        ret: Type::primitive(TokenType::Void),
+        in_block_stmnt: false,
+        in_switch: false,
+        in_main: false,
+        records: items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Record(record) => Some(record),
+                _ => None,
+            })
+            .collect(),
+        enums: items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Enum(found) => Some(found),
+                _ => None,
+            })
+            .collect(),
+        constants: items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Constant(constant) => Some(constant),
+                _ => None,
+            })
+            .collect(),
+        functions: items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Function(function) => Some(function),
+                _ => None,
+            })
+            .collect(),
+        macros: items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Macro(found) => Some(found),
+                _ => None,
+            })
+            .collect(),
+        signatures: HashMap::new(),
+        macro_params: Vec::new(),
+        cfg: Vec::new(),
+        module: placement.module,
+        modules: placement.modules,
+        renamed: placement.renamed,
+        defined: placement.defined,
+        imports: Vec::new(),
+        symbols: SymbolTable::new(),
+        diagnostics: Diagnostics::new(),
+    };
+    emitter.signatures = emitter.inline_signatures(&items, placement.headers);
+    let mut stream: Vec<String> = vec![CRUST_BANNER.to_string()];
+    stream.append(&mut emitter.emit_unit(unit));
+    diagnostics.extend(emitter.diagnostics);
+    stream.join(" ")
+}
+
+/// the items with the ones of #if branches kept as cfg
+fn flatten(items: &[Item]) -> Vec<&Item> {
+    let mut all = Vec::new();
+    for item in items {
+        match item {
+            Item::Cfg { items, .. } => all.append(&mut flatten(items)),
+            item => all.push(item),
+        }
+    }
+    all
+}
+
+/// `#[cfg(..)]` of an item in the #if branches of `predicates`
+fn cfg_attribute(predicates: &[String]) -> Option<String> {
+    match predicates {
+        [] => None,
+        [predicate] => Some(format!("#[cfg({})]\n", predicate)),
+        predicates => Some(format!("#[cfg(all({}))]\n", predicates.join(", "))),
+    }
+}
+
+/// the source text as comments marked with the code of the diagnostic
+fn emit_commented(doc: DocType, text: &str) -> Vec<String> {
+    let mut stream = vec![format!("\n// FIXME({}): {}\n", doc.code(), doc.title())];
+    for line in text.lines() {
+        stream.push(format!("// {}\n", line));
+    }
+    stream
+}
+
+/// a comment on lines of its own, or after the code it trails
+fn emit_comment(comment: &Comment) -> String {
+    let text = plain_comment(&comment.text);
+    if !comment.trailing {
+        format!("\n{}\n", text)
+    } else if text.starts_with("//") {
+        text + "\n"
+    } else {
+        text
+    }
+}
+
+/**
+ * emit_doc:
+ * the comments before an item, its doc comments as `///` lines. An item
+ * left out of the translation, a prototype, keeps them as plain comments
+ */
+fn emit_doc(comments: &[Comment], documented: bool) -> Vec<String> {
+    let mut stream = Vec::new();
+    for comment in comments.iter().filter(|comment| !comment.trailing) {
+        let text = &comment.text;
+        match doc_comment(text) {
+            Some(lines) if documented => {
+                stream.extend(lines.into_iter().map(|line| format!("\n{}\n", line)))
+            }
+            _ if documented && text.starts_with("///") => stream.push(format!("\n{}\n", text)),
+            _ => stream.push(emit_comment(comment)),
+        }
+    }
+    stream
+}
+
+/// the comments after an item, found on its last line or inside it
+fn emit_trailing(comments: &[Comment]) -> Vec<String> {
+    comments
+        .iter()
+        .filter(|comment| comment.trailing)
+        .map(emit_comment)
+        .collect()
+}
+
+/// the comments of a record body left after its members, before the closing brace
+fn emit_body_comments(comments: &[Comment]) -> Vec<String> {
+    comments
+        .iter()
+        .filter(|comment| comment.trailing)
+        .map(|comment| format!("\n{}\n", plain_comment(&comment.text)))
+        .collect()
+}
+
+impl<'a> Emitter<'a> {
+    fn emit_unit(&mut self, unit: &TranslationUnit) -> Vec<String> {
+        self.emit_items(&unit.items)
+    }
+
+    fn emit_items(&mut self, items: &[Item]) -> Vec<String> {
+        let mut stream = Vec::new();
+        for item in items {
+            match item {
+                Item::Function(function) => stream.append(&mut self.emit_function(function, None)),
+                Item::Record(record) => stream.append(&mut self.emit_record(record)),
+                Item::Enum(found) => stream.append(&mut self.emit_enum(found)),
+                Item::Typedef(typedef) => {
+                    stream.extend(cfg_attribute(&self.cfg));
+                    stream.extend(self.visibility());
+                    stream.push("type".to_string());
+                    stream.push(typedef.name.clone());
+                    stream.push("=".to_string());
+                    stream.push(self.emit_type(&typedef.ty));
+                    stream.push(";".to_string());
+                }
+                Item::Global(decl) => stream.append(&mut self.emit_declaration(decl)),
+                Item::Macro(found) => {
+                    stream.extend(cfg_attribute(&self.cfg));
+                    let cfg = std::mem::take(&mut self.cfg);
+                    stream.append(&mut self.emit_macro(found));
+                    self.cfg = cfg;
+                }
+                // every item of the branch gets the attribute
+                Item::Cfg { predicate, items } => {
+                    self.cfg.push(predicate.clone());
+                    stream.append(&mut self.emit_items(items));
+                    self.cfg.pop();
+                }
+                Item::Constant(constant) => {
+                    stream.extend(cfg_attribute(&self.cfg));
+                    let value = match self.type_of(&constant.value) {
+                        Some(ty) => self.emit_value(&constant.value, &ty),
+                        None => self.emit_expr(&constant.value),
+                    };
+                    stream.push(format!(
+                        "pub const {}: {} = {};\n",
+                        constant.name,
+                        self.constant_type(&constant.value),
+                        value
+                    ));
+                }
+                Item::Directive(directive) => {
+                    stream.push(format!("// {}\n", directive.text));
+                    if let Some(header) = directive.text.strip_prefix("#include") {
+                        stream.append(&mut self.emit_system_include(header, directive.span));
+                    }
+                }
+                Item::Include { header, .. } => stream.append(&mut self.emit_include(header)),
+                Item::Comment(comment) => stream.push(emit_comment(comment)),
+                Item::Unsupported { doc, text, span } => {
+                    stream.append(&mut self.emit_unsupported(*doc, text, *span))
+                }
+                // the parser has reported the error already
+                Item::Error(error) => stream.append(&mut emit_commented(PARSE_ERROR, &error.text)),
+                Item::Verbatim(text) => stream.push(text.clone()),
+                Item::Stmt(stmt) => stream.append(&mut self.emit_stmt(stmt)),
+            }
+        }
+        stream
+    }
+
+    /// `pub` on the items of a module
+    fn visibility(&self) -> Option<String> {
+        if self.module {
+            Some("pub".to_string())
+        } else {
+            None
+        }
+    }
+
+    /**
+     * emit_include:
+     * the module of a header is declared in the source including it and
+     * its items imported, a header including another imports it from the
+     * crate root where all modules are declared
+     */
+    fn emit_include(&mut self, header: &str) -> Vec<String> {
+        let name = match self.renamed.and_then(|renamed| renamed.get(header)) {
+            Some(name) => name.clone(),
+            None => module_name(header),
+        };
+        let attribute = cfg_attribute(&self.cfg).unwrap_or_default();
+        // every module is declared by its parent in the crate of a project
+        if let Some(modules) = self.modules {
+            return match modules.get(header).map(String::as_str) {
+                Some("") => Vec::new(),
+                path => vec![format!(
+                    "{}use crate::{}::*;\n",
+                    attribute,
+                    path.unwrap_or(&name)
+                )],
+            };
+        }
+        if self.module {
+            return vec![format!("{}use crate::{}::*;\n", attribute, name)];
+        }
+        // macro_rules! of the header are visible after the declaration
+        vec![
+            format!("{}#[macro_use]\nmod {};\n", attribute, name),
+            format!("{}use {}::*;\n", attribute, name),
+        ]
+    }
+
+    /**
+     * emit_system_include:
+     * headers which are not found are taken to be system headers, those
+     * with a counterpart in std are imported from it, once
+     * ` <map>` -> use std::collections::BTreeMap;
+     */
+    fn emit_system_include(&mut self, header: &str, span: Span) -> Vec<String> {
+        let header = header.trim().trim_matches(|c| matches!(c, '<' | '>' | '"'));
+        match std_equivalent(header) {
+            Some("") => Vec::new(),
+            Some(import) if self.imports.contains(&import) => Vec::new(),
+            Some(import) => {
+                self.imports.push(import);
+                let attribute = cfg_attribute(&self.cfg).unwrap_or_default();
+                vec![format!("{}{}\n", attribute, import)]
+            }
+            None => {
+                self.diagnostics.report(INCLUDE_STMT, span);
+                Vec::new()
+            }
+        }
+    }
+
+    /**
+     * emit_unsupported:
+     * the source text is kept as a comment marked with the code of
+     * the diagnostic, so it can be found from the report
+     */
+    fn emit_unsupported(&mut self, doc: DocType, text: &str, span: Span) -> Vec<String> {
+        self.diagnostics.report(doc, span);
+        emit_commented(doc, text)
+    }
+
+    fn find_record(&self, name: &str) -> Option<&'a Record> {
+        self.records.iter().find(|r| r.name == name).copied()
+    }
+
+    fn find_enum(&self, name: &str) -> Option<&'a Enum> {
+        self.enums.iter().find(|e| e.name == name).copied()
+    }
+
+    /// the enum an unscoped enumerator is named through, `RED` of `Color::RED`
+    fn enum_of_enumerator(&self, name: &str) -> Option<&'a Enum> {
+        if self.symbols.variable(name).is_some() {
+            return None;
+        }
+        self.enums
+            .iter()
+            .find(|e| {
+                !e.scoped && !e.name.is_empty() && e.enumerators.iter().any(|v| v.name == name)
+            })
+            .copied()
+    }
+
+    fn find_constant(&self, name: &str) -> Option<&'a Constant> {
+        self.constants.iter().find(|c| c.name == name).copied()
+    }
+
+    /// the function a call names, a variable of the same name hides it
+    fn find_function(&self, name: &str) -> Option<&'a Function> {
+        if self.symbols.variable(name).is_some() {
+            return None;
+        }
+        self.functions
+            .iter()
+            .find(|f| f.owner.is_none() && f.name == FunctionName::Ident(name.to_string()))
+            .copied()
+    }
+
+    /// the function or method `callee` names, with its parameters
+    fn callee(&self, callee: &Expr) -> Option<&'a Function> {
+        match callee {
+            Expr::Ident(name) => self.find_function(name),
+            Expr::Member { base, field, .. } => {
+                let record = self.find_record(self.type_of(base)?.type_name()?)?;
+                record
+                    .methods
+                    .iter()
+                    .map(|method| &method.function)
+                    .find(|f| f.name == FunctionName::Ident(field.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// macro translated into a `macro_rules!`, rather than a function
+    fn find_macro_rules(&self, name: &str) -> Option<&'a Macro> {
+        self.macros
+            .iter()
+            .find(|m| m.name == name && !self.signatures.contains_key(name))
+            .copied()
+    }
+
+    /**
+     * emit_macro:
+     * a macro computing a value from parameters of one type, without
+     * changing them, becomes an inline function. Other macros become
+     * a `macro_rules!`, invoked with `!`
+     */
+    fn emit_macro(&mut self, found: &Macro) -> Vec<String> {
+        let mut stream: Vec<String> = Vec::new();
+        if let (Some((ty, ret)), MacroBody::Expr(body)) =
+            (self.signatures.get(&found.name).cloned(), &found.body)
+        {
+            let params: Vec<String> = found
+                .params
+                .iter()
+                .map(|param| format!("{}: {}", param, ty))
+                .collect();
+            stream.push("#[inline]\n".to_string());
+            stream.extend(self.visibility());
+            stream.push(format!(
+                "fn {}({}) -> {} {{",
+                found.name,
+                params.join(", "),
+                ret
+            ));
+            stream.push(self.emit_expr(body));
+            stream.push("}\n".to_string());
+            return stream;
+        }
+
+        let mut matchers: Vec<String> = found
+            .params
+            .iter()
+            .map(|p| format!("${}:expr", p))
+            .collect();
+        if found.variadic {
+            matchers.push("$($__VA_ARGS__:expr),*".to_string());
+        }
+        self.macro_params = found.params.clone();
+        if found.variadic {
+            self.macro_params.push("__VA_ARGS__".to_string());
+        }
+        stream.push(format!("macro_rules! {} {{", found.name));
+        stream.push(format!("({}) => {{", matchers.join(", ")));
+        match found.body {
+            MacroBody::Expr(ref body) => stream.push(self.emit_expr(body)),
+            MacroBody::Stmts(ref stmts) => {
+                let in_block = self.in_block_stmnt;
+                self.in_block_stmnt = true;
+                stream.push("{".to_string());
+                for stmt in stmts {
+                    match stmt {
+                        // do { .. } while (0) only makes a statement of the body
+                        Stmt::DoWhile {
+                            body,
+                            cond: Expr::Literal { value, .. },
+                            ..
+                        } if value == "0" => {
+                            for stmt in body.as_slice() {
+                                stream.append(&mut self.emit_stmt(stmt));
+                            }
+                        }
+                        stmt => stream.append(&mut self.emit_stmt(stmt)),
+                    }
+                }
+                stream.push("}".to_string());
+                self.in_block_stmnt = in_block;
+            }
+        }
+        stream.push("};".to_string());
+        stream.push("}\n".to_string());
+        self.macro_params.clear();
+        stream
+    }
+
+    /**
+     * inline_signatures:
+     * the macros which can be functions, by name, with their signature.
+     * A header is translated apart from the sources including it, which
+     * could not agree on a signature, its macros stay macro_rules!
+     */
+    fn inline_signatures(
+        &mut self,
+        items: &[&Item],
+        headers: &[&TranslationUnit],
+    ) -> HashMap<String, (String, String)> {
+        let in_headers: Vec<&str> = headers
+            .iter()
+            .flat_map(|header| flatten(&header.items))
+            .filter_map(|item| match item {
+                Item::Macro(found) => Some(found.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        let mut calls: HashMap<String, Vec<Option<String>>> = HashMap::new();
+        for item in items {
+            match item {
+                Item::Global(decl) => self.decl_call_sites(decl, &mut calls),
+                Item::Function(function) => self.function_call_sites(function, &mut calls),
+                Item::Record(record) => {
+                    for method in &record.methods {
+                        self.function_call_sites(&method.function, &mut calls);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.symbols = SymbolTable::new();
+        self.macros
+            .iter()
+            .filter(|found| !in_headers.contains(&found.name.as_str()))
+            .filter_map(|found| {
+                let calls = calls.get(&found.name).map_or(&[][..], Vec::as_slice);
+                Some((found.name.clone(), self.inline_signature(found, calls)?))
+            })
+            .collect()
+    }
+
+    /**
+     * inline_signature:
+     * parameter and return type of a macro which can be a function, its
+     * body is an expression reading the parameters, all of one type, and
+     * constants. The type is that of the literals and of the arguments of
+     * every invocation, `calls`, the macro stays a macro_rules! when they
+     * differ or tell none
+     */
+    fn inline_signature(
+        &self,
+        found: &Macro,
+        calls: &[Option<String>],
+    ) -> Option<(String, String)> {
+        let body = match found.body {
+            MacroBody::Expr(ref body) if !found.variadic => body,
+            _ => return None,
+        };
+        let mut types: Vec<String> = Vec::new();
+        if !self.is_uniform(body, &found.params, &mut types) {
+            return None;
+        }
+        // an argument of unknown type may only be given the type of the body
+        if types.is_empty() && calls.iter().any(Option::is_none) {
+            return None;
+        }
+        types.extend(calls.iter().flatten().cloned());
+        types.sort();
+        types.dedup();
+        let ty = match types.as_slice() {
+            [ty] => ty.clone(),
+            _ => return None,
+        };
+        let ret = self.value_type(body, &found.params, &ty)?;
+        Some((ty, ret))
+    }
+
+    fn function_call_sites(
+        &mut self,
+        function: &Function,
+        calls: &mut HashMap<String, Vec<Option<String>>>,
+    ) {
+        let body = match function.body {
+            Some(ref body) => body,
+            None => return,
+        };
+        self.symbols.enter(ScopeKind::Function);
+        for param in &function.params {
+            if let Some(ref name) = param.name {
+                self.declare(name, &param.ty, function.span);
+            }
+        }
+        self.stmts_call_sites(&body.stmts, calls);
+        self.symbols.leave();
+    }
+
+    fn decl_call_sites(
+        &mut self,
+        decl: &Declaration,
+        calls: &mut HashMap<String, Vec<Option<String>>>,
+    ) {
+        for declarator in &decl.declarators {
+            match declarator.init {
+                Some(Initializer::Expr(ref value)) => self.expr_call_sites(value, calls),
+                Some(Initializer::List(ref values)) => values
+                    .iter()
+                    .for_each(|value| self.expr_call_sites(value, calls)),
+                None => {}
+            }
+            self.declare(&declarator.name, &declarator.ty, declarator.span);
+        }
+    }
+
+    /// the statements in a scope of their own
+    fn stmts_call_sites(
+        &mut self,
+        stmts: &[Stmt],
+        calls: &mut HashMap<String, Vec<Option<String>>>,
+    ) {
+        self.symbols.enter(ScopeKind::Block);
+        for stmt in stmts {
+            self.stmt_call_sites(stmt, calls);
+        }
+        self.symbols.leave();
+    }
+
+    /**
+     * stmt_call_sites:
+     * records the types of the arguments of the function-like macros
+     * invoked in `stmt`, with the declarations in scope
+     */
+    fn stmt_call_sites(&mut self, stmt: &Stmt, calls: &mut HashMap<String, Vec<Option<String>>>) {
+        match stmt {
+            Stmt::Decl(decl) => self.decl_call_sites(decl, calls),
+            Stmt::Expr(expr, _) | Stmt::Return(Some(expr), _) => self.expr_call_sites(expr, calls),
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                self.expr_call_sites(cond, calls);
+                self.stmts_call_sites(then.as_slice(), calls);
+                if let Some(otherwise) = otherwise {
+                    self.stmts_call_sites(otherwise.as_slice(), calls);
+                }
+            }
+            Stmt::While { cond, body, .. } | Stmt::DoWhile { body, cond, .. } => {
+                self.expr_call_sites(cond, calls);
+                self.stmts_call_sites(body.as_slice(), calls);
+            }
+            Stmt::For {
+                init,
+                cond,
+                step,
+                body,
+                ..
+            } => {
+                self.symbols.enter(ScopeKind::Block);
+                if let Some(init) = init {
+                    self.stmt_call_sites(init, calls);
+                }
+                for expr in cond.iter().chain(step) {
+                    self.expr_call_sites(expr, calls);
+                }
+                self.stmts_call_sites(body.as_slice(), calls);
+                self.symbols.leave();
+            }
+            Stmt::Switch {
+                scrutinee, cases, ..
+            } => {
+                self.expr_call_sites(scrutinee, calls);
+                self.symbols.enter(ScopeKind::Block);
+                for stmt in cases.iter().flat_map(|case| &case.body) {
+                    self.stmt_call_sites(stmt, calls);
+                }
+                self.symbols.leave();
+            }
+            Stmt::Block(block) => self.stmts_call_sites(&block.stmts, calls),
+            Stmt::Cfg { stmts, .. } => {
+                for stmt in stmts {
+                    self.stmt_call_sites(stmt, calls);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn expr_call_sites(&self, expr: &Expr, calls: &mut HashMap<String, Vec<Option<String>>>) {
+        expr.visit(&mut |expr| {
+            if let Expr::Call { callee, args } = expr {
+                match &**callee {
+                    Expr::Ident(name) if self.macros.iter().any(|m| m.name == *name) => {
+                        let types = args.iter().map(|arg| self.scalar_type(arg));
+                        calls.entry(name.clone()).or_default().extend(types);
+                    }
+                    _ => {}
+                }
+            }
+        });
+    }
+
+    /// rust type of `expr` when it is a number, a bool or a character
+    fn scalar_type(&self, expr: &Expr) -> Option<String> {
+        let ty = self.type_of(expr)?;
+        if ty.is_pointer() || !ty.array.is_empty() {
+            return None;
+        }
+        let ty = Type {
+            reference: false,
+            ..ty
+        };
+        match ty.base {
+            BaseType::Primitive(TokenType::Auto | TokenType::Void) => None,
+            BaseType::Primitive(_) => Some(self.emit_type(&ty)),
+            BaseType::Named(ref name) => fixed_width_type(name).map(str::to_string),
+            _ => None,
+        }
+    }
+
+    /// `expr` has no side effect and uses the parameters as values of one type
+    fn is_uniform(&self, expr: &Expr, params: &[String], types: &mut Vec<String>) -> bool {
+        let is_param = |e: &Expr| matches!(e, Expr::Ident(name) if params.contains(name));
+        match expr {
+            Expr::Literal {
+                kind: LiteralKind::Integer(_) | LiteralKind::Float(_),
+                ..
+            } => {
+                types.push(self.constant_type(expr));
+                true
+            }
+            Expr::Ident(name) if params.contains(name) => true,
+            Expr::Ident(name) => match self.find_constant(name) {
+                Some(constant) => {
+                    types.push(self.constant_type(&constant.value));
+                    true
+                }
+                None => false,
+            },
+            // parameters used as conditions would be bool
+            Expr::Unary {
+                op: UnaryOp::Not,
+                operand,
+            } => !is_param(operand) && self.is_uniform(operand, params, types),
+            Expr::Unary {
+                op: UnaryOp::Plus | UnaryOp::Minus | UnaryOp::BitNot,
+                operand,
+            } => self.is_uniform(operand, params, types),
+            Expr::Binary {
+                op: BinaryOp::And | BinaryOp::Or,
+                lhs,
+                rhs,
+            } if is_param(lhs) || is_param(rhs) => false,
+            Expr::Binary { lhs, rhs, .. } => {
+                self.is_uniform(lhs, params, types) && self.is_uniform(rhs, params, types)
+            }
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                !is_param(cond)
+                    && self.is_uniform(cond, params, types)
+                    && self.is_uniform(then, params, types)
+                    && self.is_uniform(otherwise, params, types)
+            }
+            _ => false,
+        }
+    }
+
+    /// type of the value of a uniform expression whose parameters are `ty`
+    fn value_type(&self, expr: &Expr, params: &[String], ty: &str) -> Option<String> {
+        match expr {
+            Expr::Ident(name) if params.contains(name) => Some(ty.to_string()),
+            Expr::Binary { op, lhs, .. } => match op {
+                BinaryOp::And
+                | BinaryOp::Or
+                | BinaryOp::Eq
+                | BinaryOp::Ne
+                | BinaryOp::Lt
+                | BinaryOp::Le
+                | BinaryOp::Gt
+                | BinaryOp::Ge => parse_type(TokenType::Boolean, Modifier::Default),
+                _ => self.value_type(lhs, params, ty),
+            },
+            Expr::Unary {
+                op: UnaryOp::Not, ..
+            } => parse_type(TokenType::Boolean, Modifier::Default),
+            Expr::Unary { operand, .. } => self.value_type(operand, params, ty),
+            Expr::Conditional { then, .. } => self.value_type(then, params, ty),
+            _ => Some(self.constant_type(expr)),
+        }
+    }
+
+    /**
+     * constant_type:
+     * rust type of a #define constant, from the type of its literals
+     * 64 -> i32, 10UL -> u64, 3000000000 -> i64, 3.0f -> f32, "a" -> &str,
+     * a < b -> bool, 1 + 2.5 -> f64
+     */
+    fn constant_type(&self, value: &Expr) -> String {
+        let int = || parse_type(TokenType::Integer, Modifier::Default).unwrap_or_default();
+        match value {
+            // a multicharacter literal is an int
+            Expr::Literal {
+                kind: LiteralKind::Char(_),
+                ..
+            } if !self.emit_expr(value).starts_with('\'') => int(),
+            // bytes which are not ascii are written in a byte string
+            Expr::Literal {
+                kind: LiteralKind::Str(_),
+                ..
+            } if self.emit_expr(value).starts_with('b') => "&[u8]".to_string(),
+            Expr::Literal {
+                kind: LiteralKind::Str(_),
+                ..
+            } => "&str".to_string(),
+            Expr::Ident(name) if self.find_constant(name).is_some() => self
+                .find_constant(name)
+                .map_or_else(int, |constant| self.constant_type(&constant.value)),
+            // the usual arithmetic conversions unify the types of the operands
+            value => self
+                .type_of(value)
+                .map_or_else(int, |ty| self.emit_type(&ty)),
+        }
+    }
+
+    /**
+     * type_of:
+     * type of the value of `expr`, from its literals and the declarations
+     * in scope. `None` when it can not be told, as for macro parameters
+     * and calls of functions the unit does not declare
+     */
+    fn type_of(&self, expr: &Expr) -> Option<Type> {
+        let named = |found: &Enum| Type::new(BaseType::Named(found.name.clone()));
+        match expr {
+            Expr::Literal { kind, value } => literal_type(kind, value),
+            Expr::Ident(name) if self.macro_params.contains(name) => None,
+            Expr::Ident(name) => match self.symbols.variable(name) {
+                Some(symbol) => Some(symbol.ty.clone()),
+                None => match self.find_constant(name) {
+                    Some(constant) => self.type_of(&constant.value),
+                    None => self.enum_of_enumerator(name).map(named),
+                },
+            },
+            Expr::Path(path) if path.len() == 2 => self.find_enum(&path[0]).map(named),
+            Expr::Unary {
+                op: UnaryOp::Not, ..
+            } => Some(Type::primitive(TokenType::Boolean)),
+            Expr::Unary {
+                op: UnaryOp::Deref,
+                operand,
+            } => element_type(self.type_of(operand)?),
+            Expr::Unary {
+                op: UnaryOp::AddressOf,
+                operand,
+            } => {
+                let mut ty = self.type_of(operand)?;
+                ty.pointer += 1;
+                Some(ty)
+            }
+            Expr::Unary { operand, .. } | Expr::Postfix { operand, .. } => self.type_of(operand),
+            Expr::Binary { op, lhs, rhs } => match op {
+                BinaryOp::And | BinaryOp::Or => Some(Type::primitive(TokenType::Boolean)),
+                op if op.is_comparison() => Some(Type::primitive(TokenType::Boolean)),
+                BinaryOp::Shl | BinaryOp::Shr => self.promoted(self.type_of(lhs)?),
+                _ => self.arithmetic_type(self.type_of(lhs)?, self.type_of(rhs)?),
+            },
+            Expr::Assign { target, .. } => self.type_of(target),
+            Expr::Call { callee, .. } => match **callee {
+                Expr::Ident(ref name) => self.find_function(name).map(|f| f.ret.clone()),
+                _ => None,
+            },
+            Expr::Index { base, .. } => element_type(self.type_of(base)?),
+            Expr::Member { base, field, .. } => {
+                let record = self.find_record(self.type_of(base)?.type_name()?)?;
+                record
+                    .fields
+                    .iter()
+                    .find(|f| f.decl.name == *field)
+                    .map(|f| f.decl.ty.clone())
+            }
+            Expr::Cast { ty, .. } => Some(ty.clone()),
+            Expr::Conditional { then, .. } => self.type_of(then),
+            Expr::SizeOf(_) | Expr::AlignOf(_) => {
+                Some(Type::new(BaseType::Named("size_t".to_string())))
+            }
+            Expr::Comma(list) => self.type_of(list.last()?),
+            _ => None,
+        }
+    }
+
+    /// the type a number of type `ty` is computed in, enums as their
+    /// underlying type and types narrower than int as int
+    fn promoted(&self, ty: Type) -> Option<Type> {
+        if ty.is_pointer() || !ty.array.is_empty() {
+            return None;
+        }
+        let ty = match ty.type_name().and_then(|name| self.find_enum(name)) {
+            Some(found) => enum_repr(found),
+            None => Type {
+                is_const: false,
+                reference: false,
+                ..ty
+            },
+        };
+        match self.emit_type(&ty).as_str() {
+            "i8" | "u8" | "i16" | "u16" | "char" | "bool" => {
+                Some(Type::primitive(TokenType::Integer))
+            }
+            rust if rust.starts_with(['i', 'u', 'f']) && rust[1..].parse::<u8>().is_ok() => {
+                Some(ty)
+            }
+            "isize" | "usize" => Some(ty),
+            _ => None,
+        }
+    }
+
+    /**
+     * arithmetic_type:
+     * type of the result of arithmetic on `lhs` and `rhs`, the c usual
+     * conversions: a floating type over integers, the wider integer,
+     * unsigned over signed of the same width. A pointer stays a pointer
+     */
+    fn arithmetic_type(&self, lhs: Type, rhs: Type) -> Option<Type> {
+        if lhs.is_pointer() {
+            return Some(lhs);
+        }
+        if rhs.is_pointer() {
+            return Some(rhs);
+        }
+        let (lhs, rhs) = (self.promoted(lhs)?, self.promoted(rhs)?);
+        let rank = |ty: &Type| {
+            let rust = self.emit_type(ty);
+            let bits = rust[1..].parse::<u8>().unwrap_or(64);
+            (rust.starts_with('f'), bits, rust.starts_with('u'))
+        };
+        Some(if rank(&rhs) > rank(&lhs) { rhs } else { lhs })
+    }
+
+    /**
+     * emit_type:
+     * rust equivalent of the c/c++ type, including pointers and arrays
+     */
+    fn emit_type(&self, ty: &Type) -> String {
+        self.emit_type_as(ty, self.mutability != Mutability::Strict)
+    }
+
+    /// the type, its pointers and references are `&mut` when `mutable`
+    fn emit_type_as(&self, ty: &Type, mutable: bool) -> String {
+        let mut rust_type = match ty.base {
+            BaseType::Primitive(typ) => match parse_type(typ, ty.modifier) {
+                Some(ref t) if t == "void" => "()".to_string(),
+                Some(t) => t,
+                None => "_".to_string(),
+            },
+            BaseType::Named(ref name) if name == "std::string" => "String".to_string(),
+            BaseType::Named(ref name) if fixed_width_type(name).is_some() => {
+                fixed_width_type(name).unwrap_or_default().to_string()
+            }
+            BaseType::Named(ref name) | BaseType::Struct(ref name) | BaseType::Union(ref name) => {
+                name.clone()
+            }
+        };
+        for dim in ty.array.iter().rev() {
+            rust_type = match dim {
+                Some(len) => format!("[{}; {}]", rust_type, self.emit_array_len(len)),
+                None => format!("Vec<{}>", rust_type),
+            };
+        }
+        let depth = ty.pointer + if ty.reference { 1 } else { 0 };
+        for _ in 0..depth {
+            rust_type = if !mutable || ty.is_const {
+                format!("&{}", rust_type)
+            } else {
+                format!("&mut {}", rust_type)
+            };
+        }
+        rust_type
+    }
+
+    /**
+     * defined_path:
+     * path of the free function a prototype declares, when another module of
+     * the crate defines it, `src::util::util_add`
+     */
+    fn defined_path(&self, function: &Function) -> Option<String> {
+        match function.name {
+            FunctionName::Ident(ref name) if function.owner.is_none() => self
+                .defined
+                .and_then(|defined| defined.get(name))
+                .map(|path| format!("{}::{}", path, name)),
+            _ => None,
+        }
+    }
+
+    /**
+     * emit_function:
+     * rust equivalent function, `access` is set for methods of a class
+     */
+    fn emit_function(&mut self, function: &Function, access: Option<Access>) -> Vec<String> {
+        let mut stream: Vec<String> = Vec::new();
+        let callees = Callees {
+            functions: &self.functions,
+            records: &self.records,
+            macros: &self.macros,
+        };
+        self.writes = Writes::of(function, callees);
+        self.ret = function.ret.clone();
+        let body = match function.body {
+            Some(ref body) => body,
+            // rust doesn't need prototypes, their comments are kept
+            None => {
+                stream.append(&mut emit_doc(&function.comments, false));
+                if let Some(path) = self.defined_path(function) {
+                    stream.extend(cfg_attribute(&self.cfg));
+                    stream.push(format!("pub use crate::{};", path));
+                }
+                let params = function.params.iter().flat_map(|param| &param.comments);
+                stream.extend(params.map(emit_comment));
+                stream.append(&mut emit_trailing(&function.comments));
+                return stream;
+            }
+        };
+
+        stream.extend(cfg_attribute(&self.cfg));
+        //if the function has scope resolution we need build a impl `class|struct`
+        let in_impl = access.is_none() && function.owner.is_some();
+        if let Some(ref owner) = function.owner {
+            if in_impl {
+                stream.push("impl".to_string());
+                stream.push(owner.clone());
+                stream.push("{".to_string());
+            }
+        }
+
+        let name = match function.name {
+            FunctionName::Ident(ref name) => name.clone(),
+            FunctionName::Operator(op) => {
+                self.diagnostics.report(OPERATOR_OVERLOAD, function.span);
+                "operator".to_string() + get_operator_as_fucn_name(op)
+            }
+        };
+        if let Some(span) = function.variadic {
+            let text = "`...` taking variadic arguments is left out";
+            stream.append(&mut self.emit_unsupported(KEYWORD, text, span));
+        }
+        stream.append(&mut emit_doc(&function.comments, true));
+        if access == Some(Access::Public) || (access.is_none() && self.module) {
+            stream.push("pub".to_string());
+        }
+        stream.push("fn".to_string());
+        stream.push(name);
+        stream.push("(".to_string());
+
+        let is_main = access.is_none() && function.is_main();
+        // parse arguments differently for functions that are not main
+        // since rust does not have arguments or return type for main
+        if !is_main {
+            let mut args: Vec<String> = Vec::new();
+            if access.is_some() {
+                //first argument of method must be self, for sefety we consider reference/borrow
+                if self.mutability == Mutability::Inferred && self.writes.receiver {
+                    args.push("&mut self".to_string());
+                } else {
+                    args.push("&self".to_string());
+                }
+                if !function.params.is_empty() {
+                    args.last_mut().unwrap().push(',');
+                }
+            }
+            let last = function.params.len().saturating_sub(1);
+            for (index, param) in function.params.iter().enumerate() {
+                args.push(self.emit_param(param, function.span, index == last));
+            }
+            stream.push(args.join(" "));
+        }
+        stream.push(")".to_string());
+        if !is_main && !function.ret.is_void() {
+            stream.push("->".to_string());
+            stream.push(self.emit_type(&function.ret));
+        }
+        stream.push("{".to_string());
+
+        // declare argc and argv inside main, if required
+        if is_main && !function.params.is_empty() {
+            let (argv, argc) = match self.mutability {
+                Mutability::Loose => {
+                    self.diagnostics.report(NO_STRICT, function.span);
+                    (true, true)
+                }
+                Mutability::Strict => {
+                    self.diagnostics.report(STRICT, function.span);
+                    (false, false)
+                }
+                Mutability::Inferred => (
+                    self.writes.is_written("argv", function.span),
+                    self.writes.is_written("argc", function.span),
+                ),
+            };
+            stream.push(format!(
+                "{} argv: Vec<_> = std::env::args().collect();",
+                binding(argv)
+            ));
+            stream.push(format!("{} argc = argv.len();", binding(argc)));
+        }
+
+        self.in_block_stmnt = true;
+        self.in_main = is_main;
+        self.symbols.enter(ScopeKind::Function);
+        for param in &function.params {
+            if let Some(ref name) = param.name {
+                self.declare(name, &param.ty, function.span);
+            }
+        }
+        // the function has the attribute already
+        let cfg = std::mem::take(&mut self.cfg);
+        stream.append(&mut self.emit_function_body(&body.stmts));
+        self.cfg = cfg;
+        self.symbols.leave();
+        self.in_main = false;
+        self.in_block_stmnt = false;
+
+        stream.push("}".to_string());
+        if in_impl {
+            stream.push("} //end impl\n".to_string());
+        }
+        stream.append(&mut emit_trailing(&function.comments));
+        stream
+    }
+
+    /**
+     * emit_param:
+     * parameter of the function declared at `span` between its comments,
+     * followed by a comma unless it is the `last`
+     */
+    fn emit_param(&self, param: &Param, span: Span, last: bool) -> String {
+        let mut stream: Vec<String> = param
+            .comments
+            .iter()
+            .filter(|comment| !comment.trailing)
+            .map(|comment| {
+                emit_comment(&Comment {
+                    trailing: true,
+                    ..comment.clone()
+                })
+            })
+            .collect();
+        stream.push(self.emit_param_binding(param, span));
+        // block comments stay before the comma, line comments end the line
+        let (lines, blocks): (Vec<String>, Vec<String>) = emit_trailing(&param.comments)
+            .into_iter()
+            .partition(|comment| comment.starts_with("//"));
+        stream.extend(blocks);
+        let mut text = stream.join(" ");
+        if !last {
+            text.push(',');
+        }
+        lines
+            .into_iter()
+            .fold(text, |text, line| text + " " + &line)
+    }
+
+    /// name and type of a parameter of the function declared at `span`
+    fn emit_param_binding(&self, param: &Param, span: Span) -> String {
+        let name = match param.name {
+            Some(ref name) => name,
+            None => return format!("_: {}", self.emit_type(&param.ty)),
+        };
+        if self.mutability != Mutability::Inferred {
+            return format!("{}: {}", name, self.emit_type(&param.ty));
+        }
+        let ty = self.emit_type_as(&param.ty, self.writes.is_pointee_written(name, span));
+        if self.writes.is_written(name, span) {
+            format!("mut {}: {}", name, ty)
+        } else {
+            format!("{}: {}", name, ty)
+        }
+    }
+
+    /**
+     * emit_function_body:
+     * the return statement ending the function is
+     * replaced with the rust shorthand notation, the one of main with
+     * `std::process::exit`
+     */
+    fn emit_function_body(&mut self, stmts: &[Stmt]) -> Vec<String> {
+        let mut stream = Vec::new();
+        let last = stmts.iter().rposition(|s| !matches!(s, Stmt::Comment(_)));
+        for (i, stmt) in stmts.iter().enumerate() {
+            match stmt {
+                Stmt::Return(value, span) if Some(i) == last => {
+                    let value = match value {
+                        Some(value) => self.emit_expr(&self.as_integer_of(value, &self.ret)),
+                        None => continue,
+                    };
+                    if self.in_main {
+                        stream.push(format!("std::process::exit({});", value));
+                    } else {
+                        self.diagnostics.report(NO_RETURN, *span);
+                        stream.push(value);
+                    }
+                }
+                _ => stream.append(&mut self.emit_stmt(stmt)),
+            }
+        }
+        stream
+    }
+
+    fn emit_record(&mut self, record: &Record) -> Vec<String> {
+        let mut stream: Vec<String> = emit_doc(&record.comments, true);
+        stream.extend(cfg_attribute(&self.cfg));
+        match record.kind {
+            RecordKind::Union => {
+                self.diagnostics.report(UNION, record.span);
+                stream.extend(self.visibility());
+                stream.push("enum".to_string());
+                stream.push(record.name.clone());
+                stream.push("{".to_string());
+                for field in &record.fields {
+                    stream.append(&mut emit_doc(&field.comments, true));
+                    stream.extend(cfg_attribute(&field.cfg));
+                    stream.push(format!(
+                        "{}({}),",
+                        field.decl.name,
+                        self.emit_type(&field.decl.ty)
+                    ));
+                    stream.append(&mut emit_trailing(&field.comments));
+                }
+                stream.append(&mut emit_body_comments(&record.comments));
+                stream.push("}\n".to_string());
+                return stream;
+            }
+            RecordKind::Struct | RecordKind::Class => {
+                stream.extend(self.visibility());
+                stream.push("struct".to_string());
+                stream.push(record.name.clone());
+                stream.push("{".to_string());
+                for field in &record.fields {
+                    stream.append(&mut emit_doc(&field.comments, true));
+                    stream.extend(cfg_attribute(&field.cfg));
+                    // the fields of a struct are public in the module of a header
+                    if (record.kind == RecordKind::Class || self.module)
+                        && field.access == Access::Public
+                    {
+                        stream.push("pub".to_string());
+                    }
+                    stream.push(format!(
+                        "{}: {},",
+                        field.decl.name,
+                        self.emit_type(&field.decl.ty)
+                    ));
+                    stream.append(&mut emit_trailing(&field.comments));
+                }
+                stream.append(&mut emit_body_comments(&record.comments));
+                stream.push("}\n".to_string());
+            }
+        }
+
+        if record.kind == RecordKind::Struct
+            && record.methods.is_empty()
+            && record.pure_methods.is_empty()
+        {
+            return stream;
+        }
+        stream.push(
+            "\n\n/**Method declarations are wrapped inside the impl block \
+    \n * Which implements the corresponding structure\
+    \n **/\n"
+                .to_string(),
+        );
+        stream.extend(cfg_attribute(&self.cfg));
+        stream.push("impl".to_string());
+        stream.push(record.name.clone());
+        stream.push("{\n".to_string());
+        for constructor in &record.constructors {
+            stream.append(&mut self.emit_unsupported(
+                CONSTRUCTOR,
+                &constructor.text,
+                constructor.span,
+            ));
+        }
+        for method in &record.pure_methods {
+            stream.append(&mut self.emit_unsupported(KEYWORD, &method.text, method.span));
+        }
+        for method in &record.methods {
+            let access = if record.kind == RecordKind::Struct && !self.module {
+                Access::Private
+            } else {
+                method.access
+            };
+            // the methods are in the impl of the record, with its cfg
+            let cfg = std::mem::replace(&mut self.cfg, method.cfg.clone());
+            stream.append(&mut self.emit_function(&method.function, Some(access)));
+            self.cfg = cfg;
+        }
+        stream.push("}\n".to_string());
+        stream
+    }
+
+    /**
+     * emit_enum:
+     * an enum with the underlying type as repr, the enumerators of an
+     * anonymous enum are constants. Enumerators repeating the value of
+     * another one are associated constants, rust rejects the repetition
+     */
+    fn emit_enum(&mut self, found: &Enum) -> Vec<String> {
+        let mut stream: Vec<String> = emit_doc(&found.comments, true);
+        let repr = self.emit_type(&enum_repr(found));
+        let values = enum_values(found);
+        let explicit = found.enumerators.iter().any(|v| v.value.is_some());
+        if found.name.is_empty() {
+            for (i, enumerator) in found.enumerators.iter().enumerate() {
+                let value = match (values[i], &enumerator.value) {
+                    (Some(value), _) => value.to_string(),
+                    (None, Some(value)) => self.emit_expr(value),
+                    (None, None) => format!("{} + 1", found.enumerators[i - 1].name),
+                };
+                stream.append(&mut emit_doc(&enumerator.comments, true));
+                stream.extend(cfg_attribute(&self.cfg));
+                stream.extend(self.visibility());
+                stream.push(format!("const {}: {} = {};", enumerator.name, repr, value));
+                stream.append(&mut emit_trailing(&enumerator.comments));
+            }
+            return stream;
+        }
+
+        stream.extend(cfg_attribute(&self.cfg));
+        stream.push(format!("#[repr({})]\n", repr));
+        stream.push("#[derive(Clone, Copy, PartialEq, Eq, Debug)]\n".to_string());
+        stream.extend(self.visibility());
+        stream.push("enum".to_string());
+        stream.push(found.name.clone());
+        stream.push("{".to_string());
+        let mut aliases = Vec::new();
+        for (i, enumerator) in found.enumerators.iter().enumerate() {
+            let first = values[i]
+                .and_then(|value| values.iter().position(|v| *v == Some(value)))
+                .filter(|&first| first < i);
+            if let Some(first) = first {
+                aliases.push((enumerator, &found.enumerators[first]));
+                continue;
+            }
+            stream.append(&mut emit_doc(&enumerator.comments, true));
+            let discriminant = match (values[i], &enumerator.value) {
+                (Some(value), _) if explicit => format!(" = {}", value),
+                (None, Some(value)) => format!(" = {}", self.emit_expr(value)),
+                _ => String::new(),
+            };
+            stream.push(format!("{}{},", enumerator.name, discriminant));
+            stream.append(&mut emit_trailing(&enumerator.comments));
+        }
+        stream.push("}\n".to_string());
+        if aliases.is_empty() {
+            return stream;
+        }
+        stream.extend(cfg_attribute(&self.cfg));
+        stream.push("impl".to_string());
+        stream.push(found.name.clone());
+        stream.push("{".to_string());
+        for (alias, first) in aliases {
+            stream.append(&mut emit_doc(&alias.comments, true));
+            stream.push(format!(
+                "pub const {}: {} = {}::{};",
+                alias.name, found.name, found.name, first.name
+            ));
+            stream.append(&mut emit_trailing(&alias.comments));
+        }
+        stream.push("}\n".to_string());
+        stream
+    }
+
+    /**
+     * emit_struct_init:
+     * declaration of a structure variable, the fields
+     * are initialized with the default value of its type
+     */
+    fn emit_struct_init(&mut self, declarator: &Declarator, record: &Record) -> Vec<String> {
+        self.diagnostics.report(STRUCT_INIT, declarator.span);
+        let mutable = self.mutability == Mutability::Inferred
+            && self.writes.is_written(&declarator.name, declarator.span);
+        let mut stream = vec![
+            binding(mutable).to_string(),
+            declarator.name.clone(),
+            "=".to_string(),
+            record.name.clone(),
+            "{".to_string(),
+        ];
+        for field in &record.fields {
+            let value = match field.decl.ty.base {
+                BaseType::Primitive(typ) if !field.decl.ty.is_pointer() => {
+                    get_default_value_for(typ)
+                }
+                _ => "Default::default()".to_string(),
+            };
+            stream.push(format!("{}: {},", field.decl.name, value));
+        }
+        stream.push("};".to_string());
+        stream
+    }
+
+    /**
+     * emit_declaration:
+     * local declarations are `let` bindings, globals are `static`
+     */
+    fn emit_declaration(&mut self, decl: &Declaration) -> Vec<String> {
+        let mut stream: Vec<String> = Vec::new();
+        let mut documented = false;
+
+        for declarator in decl.declarators.iter().filter(|d| !d.name.is_empty()) {
+            let ty = &declarator.ty;
+            self.declare(&declarator.name, ty, declarator.span);
+            stream.extend(cfg_attribute(&self.cfg));
+
+            // struct A a; A a = A();
+            if !ty.is_pointer() && !ty.reference {
+                let record = match ty.base {
+                    BaseType::Struct(ref name) | BaseType::Named(ref name) => {
+                        self.find_record(name)
+                    }
+                    _ => None,
+                };
+                if let Some(record) = record {
+                    if record.kind != RecordKind::Union && is_default_construction(declarator) {
+                        stream.append(&mut self.emit_struct_init(declarator, record));
+                        continue;
+                    }
+                }
+            }
+            // locals of the inferred mode are mutable where written, nothing to check
+            let inferred = self.mutability == Mutability::Inferred && self.in_block_stmnt;
+            if !documented && !ty.is_const && !inferred {
+                let doc = if self.mutability == Mutability::Strict {
+                    STRICT
+                } else {
+                    NO_STRICT
+                };
+                self.diagnostics.report(doc, decl.span);
+                documented = true;
+            }
+            if let BaseType::Union(_) = ty.base {
+                self.diagnostics.report(UNION_DECL, declarator.span);
+            }
+            if ty.is_pointer() {
+                self.diagnostics.report(NO_POINTER, declarator.span);
+            }
+
+            if !self.in_block_stmnt {
+                stream.extend(self.visibility());
+            }
+            if ty.is_const {
+                stream.push("const".to_string());
+            } else {
+                let strict = self.mutability == Mutability::Strict;
+                stream.push(match (self.in_block_stmnt, strict) {
+                    (true, _) => binding(self.is_mutable(declarator)).to_string(),
+                    // other units may write the globals of the inferred mode
+                    (false, false) => "static mut".to_string(),
+                    (false, true) => "static".to_string(),
+                });
+            }
+            stream.push(declarator.name.clone());
+
+            //not able to find the type, let the rust compiler do the type inference.
+            if ty.base != BaseType::Primitive(TokenType::Auto) {
+                stream.push(":".to_string());
+                stream.push(self.emit_declared_type(declarator));
+            }
+
+            match declarator.init {
+                // int *p = NULL; rust references can not be null
+                Some(Initializer::Expr(Expr::Literal {
+                    kind: LiteralKind::Null,
+                    ..
+                })) if ty.is_pointer() => self.diagnostics.report(NULL_POINTER, declarator.span),
+                Some(Initializer::Expr(ref value)) => {
+                    stream.push("=".to_string());
+                    let ty = self.given_type(ty, &declarator.name, declarator.span);
+                    stream.push(self.emit_value(value, &ty));
+                }
+                Some(Initializer::List(ref values)) => {
+                    stream.push("=".to_string());
+                    let values: Vec<String> = values.iter().map(|v| self.emit_expr(v)).collect();
+                    stream.push(format!("[{}]", values.join(", ")));
+                }
+                // statics must be initialized
+                None if !self.in_block_stmnt && !ty.is_pointer() && ty.array.is_empty() => {
+                    match ty.base {
+                        BaseType::Primitive(typ) => {
+                            stream.push("=".to_string());
+                            stream.push(get_default_value_for(typ));
+                        }
+                        // zero, the first enumerator unless given another value
+                        BaseType::Named(ref name) => {
+                            if let Some(first) = self
+                                .find_enum(name)
+                                .and_then(|found| found.enumerators.first())
+                            {
+                                stream.push("=".to_string());
+                                stream.push(format!("{}::{}", name, first.name));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                None => {}
+            }
+            stream.push(";".to_string());
+        }
+        stream
+    }
+
+    /// the local variable is declared `mut`
+    fn is_mutable(&self, declarator: &Declarator) -> bool {
+        match self.mutability {
+            Mutability::Inferred => self.writes.is_written(&declarator.name, declarator.span),
+            Mutability::Strict => false,
+            Mutability::Loose => true,
+        }
+    }
+
+    /// the declared type, the length of `int a[] = {1, 2}` is taken from the initializer
+    fn emit_declared_type(&self, declarator: &Declarator) -> String {
+        let ty = &declarator.ty;
+        let mutable = match self.mutability {
+            Mutability::Inferred if self.in_block_stmnt => self
+                .writes
+                .is_pointee_written(&declarator.name, declarator.span),
+            mutability => mutability != Mutability::Strict,
+        };
+        if let (Some(None), Some(Initializer::List(values))) =
+            (ty.array.first(), declarator.init.as_ref())
+        {
+            let mut ty = ty.clone();
+            ty.array[0] = Some(Expr::Literal {
+                kind: LiteralKind::Integer(Number::default()),
+                value: values.len().to_string(),
+            });
+            return self.emit_type_as(&ty, mutable);
+        }
+        self.emit_type_as(ty, mutable)
+    }
+
+    /// array lengths are usize, the type suffix of a literal length is dropped
+    fn emit_array_len(&self, len: &Expr) -> String {
+        match len {
+            Expr::Literal {
+                kind: LiteralKind::Integer(number),
+                value,
+            } => rust_integer(
+                value,
+                Number {
+                    suffix: Suffix::None,
+                    ..*number
+                },
+            ),
+            len if self.scalar_type(len).is_some_and(|ty| ty == "usize") => self.emit_expr(len),
+            // constants and enumerators are typed from their value
+            len => format!("{} as usize", self.emit_operand(len, PREC_CAST)),
+        }
+    }
+
+    /**
+     * emit_cfg_stmts:
+     * statements of an #if branch kept as cfg. Declarations get the
+     * attribute to stay visible after the branch, other statements are
+     * grouped into a block with the attribute, as rust does not allow
+     * attributes on every expression statement
+     */
+    fn emit_cfg_stmts(&mut self, stmts: &[Stmt]) -> Vec<String> {
+        let mut stream: Vec<String> = Vec::new();
+        let mut group: Vec<&Stmt> = Vec::new();
+        for stmt in stmts {
+            match stmt {
+                Stmt::Empty => {}
+                Stmt::Decl(_) | Stmt::Cfg { .. } | Stmt::Comment(_) => {
+                    stream.append(&mut self.emit_cfg_group(&group));
+                    group.clear();
+                    stream.append(&mut self.emit_stmt(stmt));
+                }
+                stmt => group.push(stmt),
+            }
+        }
+        stream.append(&mut self.emit_cfg_group(&group));
+        stream
+    }
+
+    fn emit_cfg_group(&mut self, group: &[&Stmt]) -> Vec<String> {
+        if group.is_empty() {
+            return Vec::new();
+        }
+        let mut stream: Vec<String> = cfg_attribute(&self.cfg).into_iter().collect();
+        // the block has the attribute already
+        let cfg = std::mem::take(&mut self.cfg);
+        stream.push("{".to_string());
+        for stmt in group {
+            stream.append(&mut self.emit_stmt(stmt));
+        }
+        stream.push("}".to_string());
+        self.cfg = cfg;
+        stream
+    }
+
+    fn emit_block(&mut self, body: &Stmt) -> Vec<String> {
+        let mut stream = vec!["{".to_string()];
+        self.symbols.enter(ScopeKind::Block);
+        for stmt in body.as_slice() {
+            stream.append(&mut self.emit_stmt(stmt));
+        }
+        self.symbols.leave();
+        stream.push("}".to_string());
+        stream
+    }
+
+    fn declare(&mut self, name: &str, ty: &Type, span: Span) {
+        self.symbols.declare(Symbol {
+            name: name.to_string(),
+            kind: SymbolKind::Variable,
+            ty: ty.clone(),
+            span,
+        });
+    }
+
+    fn emit_stmt(&mut self, stmt: &Stmt) -> Vec<String> {
+        let mut stream: Vec<String> = Vec::new();
+        match stmt {
+            Stmt::Decl(decl) => stream.append(&mut self.emit_declaration(decl)),
+            Stmt::Cfg { predicate, stmts } => {
+                self.cfg.push(predicate.clone());
+                stream.append(&mut self.emit_cfg_stmts(stmts));
+                self.cfg.pop();
+            }
+            Stmt::Error(error) => stream.append(&mut emit_commented(PARSE_ERROR, &error.text)),
+            Stmt::Expr(expr, _) => stream.append(&mut self.emit_expr_stmt(expr)),
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                stream.push("if".to_string());
+                stream.push(self.emit_condition(cond));
+                stream.append(&mut self.emit_block(then));
+                match otherwise.as_deref() {
+                    Some(nested @ Stmt::If { .. }) => {
+                        stream.push("else".to_string());
+                        stream.append(&mut self.emit_stmt(nested));
+                    }
+                    Some(otherwise) => {
+                        stream.push("else".to_string());
+                        stream.append(&mut self.emit_block(otherwise));
+                    }
+                    None => {}
+                }
+            }
+            Stmt::While { cond, body, .. } => {
+                let was_in_switch = self.in_switch;
+                self.in_switch = false;
+                if cond.is_always_true() {
+                    stream.push("loop".to_string());
+                } else {
+                    stream.push("while".to_string());
+                    stream.push(self.emit_condition(cond));
+                }
+                stream.append(&mut self.emit_block(body));
+                self.in_switch = was_in_switch;
+            }
+            Stmt::DoWhile { body, cond, .. } => {
+                let was_in_switch = self.in_switch;
+                self.in_switch = false;
+                if cond.is_always_true() {
+                    stream.push("loop".to_string());
+                    stream.append(&mut self.emit_block(body));
+                } else {
+                    // the body is the condition block of rust while
+                    stream.push("while".to_string());
+                    stream.push("{".to_string());
+                    for stmt in body.as_slice() {
+                        stream.append(&mut self.emit_stmt(stmt));
+                    }
+                    stream.push(self.emit_condition(cond));
+                    stream.push("}".to_string());
+                    stream.push("{}".to_string());
+                }
+                stream.push(";".to_string());
+                self.in_switch = was_in_switch;
+            }
+            Stmt::For {
+                init,
+                cond,
+                step,
+                body,
+                ..
+            } => {
+                let was_in_switch = self.in_switch;
+                self.in_switch = false;
+                // the declaration of the init ends with the loop
+                self.symbols.enter(ScopeKind::Block);
+                if let Some(init) = init {
+                    stream.append(&mut self.emit_stmt(init));
+                }
+                match cond {
+                    Some(cond) if !cond.is_always_true() => {
+                        stream.push("while".to_string());
+                        stream.push(self.emit_condition(cond));
+                    }
+                    _ => stream.push("loop".to_string()),
+                }
+                let mut block = self.emit_block(body);
+                if let Some(step) = step {
+                    let close = block.pop().unwrap();
+                    block.append(&mut self.emit_expr_stmt(step));
+                    block.push(close);
+                }
+                stream.append(&mut block);
+                self.symbols.leave();
+                self.in_switch = was_in_switch;
+            }
+            Stmt::Switch {
+                scrutinee, cases, ..
+            } => {
+                stream.push("match".to_string());
+                stream.push(self.emit_expr(scrutinee));
+                stream.push("{".to_string());
+                let was_in_switch = self.in_switch;
+                self.in_switch = true;
+                let mut has_default = false;
+                let switched = self.enum_type(scrutinee).map(|found| found.name.as_str());
+                let ty = self.type_of(scrutinee);
+                for case in cases {
+                    let labels: Vec<&Expr> = case.labels.iter().flatten().collect();
+                    // rust patterns can not convert an enumerator compared with
+                    // an integer, the arm is guarded by the comparisons instead
+                    let converted = labels.iter().any(|label| {
+                        self.enum_type(label)
+                            .is_some_and(|found| Some(found.name.as_str()) != switched)
+                    });
+                    has_default |= case.labels.contains(&None);
+                    stream.push(if case.labels.contains(&None) {
+                        "_".to_string()
+                    } else if converted {
+                        let comparisons: Vec<String> = labels
+                            .iter()
+                            .map(|label| {
+                                let ty = match (&ty, self.enum_type(label)) {
+                                    (Some(ty), _) => ty.clone(),
+                                    (None, Some(found)) => enum_repr(found),
+                                    (None, None) => Type::primitive(TokenType::Auto),
+                                };
+                                let value = self.emit_expr(&self.as_integer_of(label, &ty));
+                                format!("switch == {}", value)
+                            })
+                            .collect();
+                        // `switch` is a c keyword, it names no variable of the source
+                        format!("switch if {}", comparisons.join(" || "))
+                    } else {
+                        let labels: Vec<String> =
+                            labels.iter().map(|label| self.emit_expr(label)).collect();
+                        labels.join(" | ")
+                    });
+                    stream.push("=>".to_string());
+                    stream.push("{".to_string());
+                    for stmt in &case.body {
+                        stream.append(&mut self.emit_stmt(stmt));
+                    }
+                    stream.push("}".to_string());
+                }
+                //look whether default case is handled for exaustive search
+                if !has_default {
+                    stream.push("_ => {}".to_string());
+                }
+                stream.push("}".to_string());
+                self.in_switch = was_in_switch;
+            }
+            Stmt::Return(value, _) => {
+                stream.push("return".to_string());
+                if let Some(value) = value {
+                    stream.push(self.emit_expr(&self.as_integer_of(value, &self.ret)));
+                }
+                stream.push(";".to_string());
+            }
+            Stmt::Break(_) => {
+                if !self.in_switch {
+                    stream.push("break;".to_string());
+                }
+            }
+            Stmt::Continue(_) => stream.push("continue;".to_string()),
+            Stmt::Block(_) => stream.append(&mut self.emit_block(stmt)),
+            Stmt::Comment(comment) => stream.push(emit_comment(comment)),
+            Stmt::Unsupported { doc, text, span } => {
+                stream.append(&mut self.emit_unsupported(*doc, text, *span))
+            }
+            Stmt::Empty => {}
+        }
+        stream
+    }
+
+    /**
+     * emit_expr_stmt:
+     * increment and decrement statements become compound assignments
+     * and comma separated expressions become separate statements
+     */
+    fn emit_expr_stmt(&self, expr: &Expr) -> Vec<String> {
+        match expr {
+            Expr::Assign { op, target, value } => {
+                vec![format!("{};", self.emit_assign(*op, target, value))]
+            }
+            Expr::Postfix { op, operand } => {
+                let op = match op {
+                    PostfixOp::Increment => "+=",
+                    PostfixOp::Decrement => "-=",
+                };
+                vec![format!("{} {} 1;", self.emit_expr(operand), op)]
+            }
+            Expr::Unary {
+                op: op @ (UnaryOp::PreIncrement | UnaryOp::PreDecrement),
+                operand,
+            } => {
+                let op = if *op == UnaryOp::PreIncrement {
+                    "+="
+                } else {
+                    "-="
+                };
+                vec![format!("{} {} 1;", self.emit_expr(operand), op)]
+            }
+            Expr::Comma(list) => list
+                .iter()
+                .flat_map(|expr| self.emit_expr_stmt(expr))
+                .collect(),
+            _ => vec![self.emit_expr(expr) + ";"],
+        }
+    }
+
+    /// `target op value` as a statement
+    fn emit_assign(&self, op: AssignOp, target: &Expr, value: &Expr) -> String {
+        let value = match target {
+            Expr::Ident(name) => match self.symbols.variable(name) {
+                Some(symbol) => {
+                    self.emit_value(value, &self.given_type(&symbol.ty, name, symbol.span))
+                }
+                None => self.emit_expr(value),
+            },
+            _ => self.emit_expr(value),
+        };
+        format!(
+            "{} {} {}",
+            self.emit_operand(target, PREC_UNARY),
+            op.as_str(),
+            value
+        )
+    }
+
+    fn emit_expr(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Literal {
+                kind: LiteralKind::Integer(number),
+                value,
+            } => rust_integer(value, *number),
+            Expr::Literal {
+                kind: LiteralKind::Float(number),
+                value,
+            } => rust_float(value, *number),
+            Expr::Literal {
+                kind: LiteralKind::Str(_),
+                value,
+            } => rust_string(value),
+            Expr::Literal {
+                kind: LiteralKind::Char(_),
+                value,
+            } => rust_char(value),
+            Expr::Literal { value, .. } => value.clone(),
+            // a repetition in macro_rules!
+            Expr::Ident(name) if name == "__VA_ARGS__" && self.macro_params.contains(name) => {
+                "$($__VA_ARGS__),*".to_string()
+            }
+            Expr::Ident(name) if self.macro_params.contains(name) => format!("${}", name),
+            Expr::Stringize(name) if name == "__VA_ARGS__" => {
+                "stringify!($($__VA_ARGS__),*)".to_string()
+            }
+            Expr::Stringize(name) => format!("stringify!(${})", name),
+            Expr::Ident(name) => match self.enum_of_enumerator(name) {
+                Some(found) => format!("{}::{}", found.name, name),
+                None => name.clone(),
+            },
+            Expr::This => "self".to_string(),
+            // ::x names the global scope, the crate root of the translation
+            Expr::Path(path) if path.first().is_some_and(String::is_empty) => {
+                format!("crate{}", path.join("::"))
+            }
+            Expr::Path(path) => path.join("::"),
+            Expr::Unary { op, operand } => {
+                let operand = self.emit_operand(operand, PREC_UNARY);
+                match op {
+                    UnaryOp::Plus => operand,
+                    // `--b` reads as a decrement to a c programmer
+                    UnaryOp::Minus if operand.starts_with('-') => format!("-({})", operand),
+                    UnaryOp::Minus => format!("-{}", operand),
+                    UnaryOp::Not | UnaryOp::BitNot => format!("!{}", operand),
+                    UnaryOp::Deref => format!("*{}", operand),
+                    UnaryOp::AddressOf if self.mutability == Mutability::Strict => {
+                        format!("&{}", operand)
+                    }
+                    UnaryOp::AddressOf => format!("&mut {}", operand),
+                    UnaryOp::PreIncrement => format!("{{ {0} += 1; {0} }}", operand),
+                    UnaryOp::PreDecrement => format!("{{ {0} -= 1; {0} }}", operand),
+                }
+            }
+            Expr::Postfix { op, operand } => {
+                let operand = self.emit_expr(operand);
+                match op {
+                    PostfixOp::Increment => format!("{{ {0} += 1; {0} - 1 }}", operand),
+                    PostfixOp::Decrement => format!("{{ {0} -= 1; {0} + 1 }}", operand),
+                }
+            }
+            // enums take part in arithmetic and ordering as integers
+            Expr::Binary { op, lhs, rhs }
+                if (self.enum_type(lhs).is_some() || self.enum_type(rhs).is_some())
+                    && !matches!(op, BinaryOp::And | BinaryOp::Or)
+                    && !(matches!(op, BinaryOp::Eq | BinaryOp::Ne)
+                        && self.enum_type(lhs).is_some() == self.enum_type(rhs).is_some()) =>
+            {
+                self.emit_expr(&Expr::Binary {
+                    op: *op,
+                    lhs: Box::new(self.as_integer(lhs)),
+                    rhs: Box::new(self.as_integer(rhs)),
+                })
+            }
+            Expr::Binary { op, lhs, rhs } => {
+                let prec = binary_precedence(*op);
+                // comparisons do not chain in rust, a < b == c needs brackets
+                let lhs_prec = if op.is_comparison() { prec + 1 } else { prec };
+                // `a as i32 < b` is read as the start of generic arguments
+                let lhs = match **lhs {
+                    Expr::Cast { .. } if matches!(op, BinaryOp::Lt | BinaryOp::Shl) => {
+                        format!("({})", self.emit_expr(lhs))
+                    }
+                    _ => self.emit_operand(lhs, lhs_prec),
+                };
+                format!(
+                    "{} {} {}",
+                    lhs,
+                    op.as_str(),
+                    self.emit_operand(rhs, prec + 1)
+                )
+            }
+            // an assignment is `()` in rust, its value is read after it
+            Expr::Assign { op, target, value } => format!(
+                "{{ {}; {} }}",
+                self.emit_assign(*op, target, value),
+                self.emit_expr(target)
+            ),
+            Expr::Call { callee, args } => {
+                let params = self.callee(callee).map_or(&[][..], |f| f.params.as_slice());
+                let args: Vec<String> = args
+                    .iter()
+                    .enumerate()
+                    .map(|(i, arg)| match params.get(i) {
+                        // a reference parameter borrows the variable given for it
+                        Some(param) if param.ty.reference && !self.is_reference(arg) => {
+                            let operand = self.emit_operand(arg, PREC_UNARY);
+                            if param.ty.is_const || self.mutability == Mutability::Strict {
+                                format!("&{}", operand)
+                            } else {
+                                format!("&mut {}", operand)
+                            }
+                        }
+                        Some(param) => self.emit_value(arg, &param.ty),
+                        None => self.emit_expr(arg),
+                    })
+                    .collect();
+                if let Expr::Ident(name) = &**callee {
+                    if self.find_macro_rules(name).is_some() {
+                        return format!("{}!({})", name, args.join(", "));
+                    }
+                }
+                format!(
+                    "{}({})",
+                    self.emit_operand(callee, PREC_POSTFIX),
+                    args.join(", ")
+                )
+            }
+            Expr::Index { base, index } if self.enum_type(index).is_some() => format!(
+                "{}[{} as usize]",
+                self.emit_operand(base, PREC_POSTFIX),
+                self.emit_operand(index, PREC_CAST)
+            ),
+            Expr::Index { base, index } => format!(
+                "{}[{}]",
+                self.emit_operand(base, PREC_POSTFIX),
+                self.emit_expr(index)
+            ),
+            // a -> x->y(); ==> a.x.y()
+            Expr::Member { base, field, .. } => {
+                format!("{}.{}", self.emit_operand(base, PREC_POSTFIX), field)
+            }
+            Expr::Cast { ty, expr } => match self.cast_enum(ty, expr) {
+                Some(found) => self.emit_enum_of(found, expr),
+                None => format!(
+                    "{} as {}",
+                    self.emit_operand(expr, PREC_CAST),
+                    self.emit_type(ty)
+                ),
+            },
+            Expr::Conditional { .. } => self.emit_conditional(expr),
+            Expr::SizeOf(SizeOfArg::Type(ty)) => {
+                format!("std::mem::size_of::<{}>()", self.emit_type(ty))
+            }
+            Expr::SizeOf(SizeOfArg::Expr(value)) => {
+                format!("std::mem::size_of_val(&{})", self.emit_expr(value))
+            }
+            Expr::AlignOf(ty) => format!("std::mem::align_of::<{}>()", self.emit_type(ty)),
+            Expr::Comma(list) => {
+                let (last, list) = match list.split_last() {
+                    Some(split) => split,
+                    None => return String::new(),
+                };
+                let mut list: Vec<String> =
+                    list.iter().flat_map(|e| self.emit_expr_stmt(e)).collect();
+                list.push(self.emit_expr(last));
+                format!("{{ {} }}", list.join(" "))
+            }
+            Expr::Verbatim(text) => text.clone(),
+        }
+    }
+
+    /// the value given for a variable or parameter of type `ty`, a pointer
+    /// to const borrows what it points to without `mut` and an enum given
+    /// for an integer is converted
+    fn emit_value(&self, value: &Expr, ty: &Type) -> String {
+        match value {
+            Expr::Unary {
+                op: UnaryOp::AddressOf,
+                operand,
+            } if ty.is_pointer() && ty.is_const => {
+                format!("&{}", self.emit_operand(operand, PREC_UNARY))
+            }
+            value if ty.array.is_empty() && self.is_floating(ty) => {
+                self.emit_expr(&self.as_float(value, ty))
+            }
+            value => self.emit_expr(&self.as_integer_of(value, ty)),
+        }
+    }
+
+    /**
+     * given_type:
+     * the type of the variable `name` declared at `span` for the value it is
+     * given. A local pointer of the inferred mode which is not written
+     * through borrows its value without `mut`, as a pointer to const
+     */
+    fn given_type(&self, ty: &Type, name: &str, span: Span) -> Type {
+        let mut ty = ty.clone();
+        if ty.is_pointer()
+            && self.in_block_stmnt
+            && self.mutability == Mutability::Inferred
+            && !self.writes.is_pointee_written(name, span)
+        {
+            ty.is_const = true;
+        }
+        ty
+    }
+
+    /// `expr` names a reference, it is given on as it is
+    fn is_reference(&self, expr: &Expr) -> bool {
+        self.variable_type(expr).is_some_and(|ty| ty.reference)
+    }
+
+    /// `ty` is f32 or f64
+    fn is_floating(&self, ty: &Type) -> bool {
+        !ty.is_pointer() && self.emit_type(ty).starts_with('f')
+    }
+
+    /**
+     * as_float:
+     * `expr` computed in the floating type `ty`, as rust does not mix
+     * integers with floats: 1 + 2.5 ==> 1.0 + 2.5, n * 0.5 ==> n as f64 * 0.5.
+     * Integer operations keep their operands, a / b is converted as a whole
+     */
+    fn as_float(&self, expr: &Expr, ty: &Type) -> Expr {
+        let convert = |expr: &Expr| Box::new(self.as_float(expr, ty));
+        let from = match self.type_of(expr) {
+            // bool does not convert to a float, macro parameters are not known
+            Some(from) if from.base != BaseType::Primitive(TokenType::Boolean) => from,
+            _ => return expr.clone(),
+        };
+        if self.promoted(from.clone()).is_none() {
+            return expr.clone();
+        }
+        match expr {
+            Expr::Binary { op, lhs, rhs } if self.is_floating(&from) => Expr::Binary {
+                op: *op,
+                lhs: convert(lhs),
+                rhs: convert(rhs),
+            },
+            Expr::Unary {
+                op: op @ (UnaryOp::Plus | UnaryOp::Minus),
+                operand,
+            } if self.is_floating(&from) => Expr::Unary {
+                op: *op,
+                operand: convert(operand),
+            },
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } if self.is_floating(&from) => Expr::Conditional {
+                cond: cond.clone(),
+                then: convert(then),
+                otherwise: convert(otherwise),
+            },
+            // an unsuffixed float literal takes the type it is given for
+            Expr::Literal {
+                kind: LiteralKind::Float(number),
+                ..
+            } if number.suffix == Suffix::None => expr.clone(),
+            _ if self.emit_type(&from) == self.emit_type(ty) => expr.clone(),
+            Expr::Literal {
+                kind: LiteralKind::Integer(number),
+                value,
+            } if number.radix == Radix::Decimal && number.suffix == Suffix::None => Expr::Literal {
+                kind: LiteralKind::Float(Number::default()),
+                value: format!("{}.0", value),
+            },
+            expr => Expr::Cast {
+                ty: Type {
+                    is_const: false,
+                    ..ty.clone()
+                },
+                expr: Box::new(expr.clone()),
+            },
+        }
+    }
+
+    /* emit_conditional:
+     * a ? b : c ? d : e ==> if a { b } else if c { d } else { e }
+     */
+    fn emit_conditional(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let otherwise = match **otherwise {
+                    Expr::Conditional { .. } => self.emit_conditional(otherwise),
+                    _ => format!("{{ {} }}", self.emit_expr(otherwise)),
+                };
+                format!(
+                    "if {} {{ {} }} else {}",
+                    self.emit_condition(cond),
+                    self.emit_expr(then),
+                    otherwise
+                )
+            }
+            _ => self.emit_expr(expr),
+        }
+    }
+
+    /// the enum of the value of `expr`, converted to an integer with `as`
+    fn enum_type(&self, expr: &Expr) -> Option<&'a Enum> {
+        match expr {
+            Expr::Ident(name) => match self.symbols.variable(name) {
+                Some(symbol)
+                    if symbol.ty.pointer == 0
+                        && !symbol.ty.reference
+                        && symbol.ty.array.is_empty() =>
+                {
+                    self.find_enum(symbol.ty.type_name()?)
+                }
+                Some(_) => None,
+                None => self.enum_of_enumerator(name),
+            },
+            Expr::Path(path) if path.len() == 2 => self
+                .find_enum(&path[0])
+                .filter(|found| found.enumerators.iter().any(|v| v.name == path[1])),
+            _ => None,
+        }
+    }
+
+    /// the enum an integer `expr` is converted to, `(Color)1`
+    fn cast_enum(&self, ty: &Type, expr: &Expr) -> Option<&'a Enum> {
+        if ty.is_pointer() || ty.reference || !ty.array.is_empty() {
+            return None;
+        }
+        let found = self.find_enum(ty.type_name()?)?;
+        match self.enum_type(expr) {
+            Some(from) if from.name == found.name => None,
+            _ => Some(found),
+        }
+    }
+
+    /**
+     * emit_enum_of:
+     * the enumerator of the integer `value`, rust enums are not converted
+     * from integers with `as`: (Color)n ==> match n { 0 => Color::RED, .. }
+     */
+    fn emit_enum_of(&self, found: &Enum, value: &Expr) -> String {
+        let value = self.emit_expr(&self.as_integer(value));
+        let values = enum_values(found);
+        if values.iter().any(Option::is_none) {
+            return format!(
+                "unsafe {{ std::mem::transmute::<{}, {}>({}) }}",
+                self.emit_type(&enum_repr(found)),
+                found.name,
+                value
+            );
+        }
+        let mut arms: Vec<String> = Vec::new();
+        for (i, enumerator) in found.enumerators.iter().enumerate() {
+            if values[..i].contains(&values[i]) {
+                continue;
+            }
+            let discriminant = values[i].unwrap_or_default();
+            arms.push(format!(
+                "{} => {}::{},",
+                discriminant, found.name, enumerator.name
+            ));
+        }
+        format!(
+            "match {} {{ {} _ => unreachable!(\"no {} of this value\"), }}",
+            value,
+            arms.join(" "),
+            found.name
+        )
+    }
+
+    /// `expr` converted to the underlying type of its enum, unchanged if not an enum
+    fn as_integer(&self, expr: &Expr) -> Expr {
+        match self.enum_type(expr) {
+            Some(found) => Expr::Cast {
+                ty: enum_repr(found),
+                expr: Box::new(expr.clone()),
+            },
+            None => expr.clone(),
+        }
+    }
+
+    /**
+     * as_integer_of:
+     * `value` converted to `ty` when an enum is given for an integer. A
+     * literal loses a suffix typing it otherwise when its value fits `ty`
+     * and is converted when not: size_t n = 10u ==> 10, unsigned char c = 300u ==> 300_u32 as u8
+     */
+    fn as_integer_of(&self, value: &Expr, ty: &Type) -> Expr {
+        let scalar = ty.pointer == 0 && !ty.reference && ty.array.is_empty();
+        if let Some(literal) = self.as_literal_of(value, ty).filter(|_| scalar) {
+            return literal;
+        }
+        match ty.base {
+            BaseType::Primitive(typ) if scalar && typ != TokenType::Void => {
+                if self.enum_type(value).is_none() {
+                    return value.clone();
+                }
+                Expr::Cast {
+                    ty: Type {
+                        modifier: ty.modifier,
+                        ..Type::primitive(typ)
+                    },
+                    expr: Box::new(value.clone()),
+                }
+            }
+            _ => value.clone(),
+        }
+    }
+
+    /// the suffixed integer literal `value`, negated or not, given for the
+    /// integer type `ty` of another rust type
+    fn as_literal_of(&self, value: &Expr, ty: &Type) -> Option<Expr> {
+        let (negated, literal) = match value {
+            Expr::Unary {
+                op: UnaryOp::Minus,
+                operand,
+            } => (true, &**operand),
+            value => (false, value),
+        };
+        let (number, text) = match literal {
+            Expr::Literal {
+                kind: LiteralKind::Integer(number),
+                value,
+            } if number.suffix != Suffix::None => (*number, value),
+            _ => return None,
+        };
+        let rust = self.emit_type(ty);
+        let (min, max) = integer_range(&rust)?;
+        let magnitude = integer_value(text).ok()? as u64 as i128;
+        let value = if negated { -magnitude } else { magnitude };
+        if self.emit_type(&integer_type(text, number)) == rust && (min..=max).contains(&value) {
+            return None;
+        }
+        // rust negates no unsigned value, a negated one is read as i64
+        let suffix = match number.suffix {
+            _ if (min..=max).contains(&value) => Suffix::None,
+            _ if negated => Suffix::Long,
+            suffix => suffix,
+        };
+        let mut literal = Expr::Literal {
+            kind: LiteralKind::Integer(Number { suffix, ..number }),
+            value: text.clone(),
+        };
+        if negated {
+            literal = Expr::Unary {
+                op: UnaryOp::Minus,
+                operand: Box::new(literal),
+            };
+        }
+        if suffix == Suffix::None {
+            return Some(literal);
+        }
+        Some(Expr::Cast {
+            ty: Type {
+                is_const: false,
+                ..ty.clone()
+            },
+            expr: Box::new(literal),
+        })
+    }
+
+    /// declared type of the variable `expr` names
+    fn variable_type(&self, expr: &Expr) -> Option<&Type> {
+        match expr {
+            Expr::Ident(name) => self.symbols.variable(name).map(|symbol| &symbol.ty),
+            _ => None,
+        }
+    }
+
+    /* emit_condition:
+     * rust conditions must be bool, a number tested for truth is
+     * compared with zero: if (n) ==> if n != 0, !n ==> n == 0
+     */
+    fn emit_condition(&self, cond: &Expr) -> String {
+        match cond {
+            Expr::Binary {
+                op: op @ (BinaryOp::And | BinaryOp::Or),
+                lhs,
+                rhs,
+            } => {
+                let prec = binary_precedence(*op);
+                let operand = |expr: &Expr, min_prec: u8| {
+                    if rust_precedence(expr) < min_prec {
+                        format!("({})", self.emit_condition(expr))
+                    } else {
+                        self.emit_condition(expr)
+                    }
+                };
+                format!(
+                    "{} {} {}",
+                    operand(lhs, prec),
+                    op.as_str(),
+                    operand(rhs, prec + 1)
+                )
+            }
+            Expr::Unary {
+                op: UnaryOp::Not,
+                operand,
+            } => self
+                .emit_truth(operand, true)
+                .unwrap_or_else(|| self.emit_expr(cond)),
+            _ => self
+                .emit_truth(cond, false)
+                .unwrap_or_else(|| self.emit_expr(cond)),
+        }
+    }
+
+    /**
+     * emit_truth:
+     * the test of the value of `expr` for truth, or for falsity when
+     * `negated`, from its type: a number is compared with zero, a
+     * character with '\0', an enum through its underlying type and a
+     * pointer tested for null. None for a bool or a value of unknown type
+     */
+    fn emit_truth(&self, expr: &Expr, negated: bool) -> Option<String> {
+        let ty = self.type_of(expr)?;
+        let (eq, not) = if negated { ("==", "") } else { ("!=", "!") };
+        if ty.is_pointer() && ty.array.is_empty() {
+            let operand = self.emit_operand(expr, PREC_CAST);
+            let pointee = self.emit_type(&element_type(ty)?);
+            return Some(format!(
+                "{}({} as *const {}).is_null()",
+                not, operand, pointee
+            ));
+        }
+        let operand = self.emit_operand(expr, binary_precedence(BinaryOp::Ne) + 1);
+        if let Some(found) = ty.type_name().and_then(|name| self.find_enum(name)) {
+            let repr = self.emit_type(&enum_repr(found));
+            let operand = self.emit_operand(expr, PREC_CAST);
+            return Some(format!("{} as {} {} 0", operand, repr, eq));
+        }
+        let zero = match self.emit_type(&ty).as_str() {
+            "bool" => return None,
+            "char" => "'\\0'",
+            _ => match self.emit_type(&self.promoted(ty)?).as_str() {
+                rust if rust.starts_with('f') => "0.0",
+                _ => "0",
+            },
+        };
+        Some(format!("{} {} {}", operand, eq, zero))
+    }
+
+    /* emit_operand:
+     * emits the sub expression, bracketed when it binds weaker than `min_prec`
+     * the ast carries no brackets, they are derived from rust precedence
+     */
+    fn emit_operand(&self, expr: &Expr, min_prec: u8) -> String {
+        let code = self.emit_expr(expr);
+        if rust_precedence(expr) < min_prec {
+            format!("({})", code)
+        } else {
+            code
+        }
+    }
+}
+
+// rust operator precedence, higher binds tighter
+const PREC_ASSIGN: u8 = 0;
+const PREC_CONDITIONAL: u8 = 1;
+const PREC_CAST: u8 = 11;
+const PREC_UNARY: u8 = 12;
+const PREC_POSTFIX: u8 = 13;
+
+fn binary_precedence(op: BinaryOp) -> u8 {
+    match op {
+        BinaryOp::Or => 2,
+        BinaryOp::And => 3,
+        BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+            4
+        }
+        BinaryOp::BitOr => 5,
+        BinaryOp::BitXor => 6,
+        BinaryOp::BitAnd => 7,
+        BinaryOp::Shl | BinaryOp::Shr => 8,
+        BinaryOp::Add | BinaryOp::Sub => 9,
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 10,
+    }
+}
+
+/// precedence of the rust expression the node is emitted as
+fn rust_precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Assign { .. } => PREC_ASSIGN,
+        Expr::Conditional { .. } => PREC_CONDITIONAL,
+        Expr::Binary { op, .. } => binary_precedence(*op),
+        Expr::Cast { .. } => PREC_CAST,
+        // increments are emitted as blocks
+        Expr::Unary {
+            op: UnaryOp::PreIncrement,
+            ..
+        }
+        | Expr::Unary {
+            op: UnaryOp::PreDecrement,
+            ..
+        } => PREC_POSTFIX,
+        Expr::Unary { .. } => PREC_UNARY,
+        _ => PREC_POSTFIX,
+    }
+}
+
+/// type of a literal, a string is a pointer to its characters
+fn literal_type(kind: &LiteralKind, value: &str) -> Option<Type> {
+    let ty = match kind {
+        LiteralKind::Integer(number) => integer_type(value, *number),
+        LiteralKind::Float(number) if number.suffix == Suffix::Float => {
+            Type::primitive(TokenType::Float)
+        }
+        LiteralKind::Float(_) => Type::primitive(TokenType::Double),
+        LiteralKind::Char(_) => Type::primitive(TokenType::Character),
+        LiteralKind::Str(_) => Type {
+            is_const: true,
+            pointer: 1,
+            ..Type::primitive(TokenType::Character)
+        },
+        LiteralKind::Bool => Type::primitive(TokenType::Boolean),
+        LiteralKind::Null => return None,
+    };
+    Some(ty)
+}
+
+/**
+ * integer_type:
+ * type of an integer literal, the first of the types its suffix allows
+ * which holds the value, unsigned ones only for hexadecimal, octal and
+ * binary literals: 3000000000 -> long, 0x80000000 -> unsigned int
+ */
+fn integer_type(value: &str, number: Number) -> Type {
+    // values beyond i64 are read wrapped to negative ones
+    let value = integer_value(value).unwrap_or(-1);
+    let fits = |max: i64| (0..=max).contains(&value);
+    let unsigned = number.radix != Radix::Decimal;
+    let (typ, modifier) = match number.suffix {
+        Suffix::Unsigned if fits(u32::MAX.into()) => (TokenType::Integer, Modifier::Unsigned),
+        Suffix::Unsigned | Suffix::UnsignedLong | Suffix::UnsignedLongLong => {
+            (TokenType::Long, Modifier::Unsigned)
+        }
+        Suffix::Long | Suffix::LongLong if fits(i64::MAX) || !unsigned => {
+            (TokenType::Long, Modifier::Default)
+        }
+        Suffix::Long | Suffix::LongLong => (TokenType::Long, Modifier::Unsigned),
+        _ if fits(i32::MAX.into()) => (TokenType::Integer, Modifier::Default),
+        _ if unsigned && fits(u32::MAX.into()) => (TokenType::Integer, Modifier::Unsigned),
+        _ if fits(i64::MAX) || !unsigned => (TokenType::Long, Modifier::Default),
+        _ => (TokenType::Long, Modifier::Unsigned),
+    };
+    Type {
+        modifier,
+        ..Type::primitive(typ)
+    }
+}
+
+/// type of the element a pointer points to or an array holds
+fn element_type(mut ty: Type) -> Option<Type> {
+    if ty.pointer > 0 {
+        ty.pointer -= 1;
+    } else if !ty.array.is_empty() {
+        ty.array.remove(0);
+    } else {
+        return None;
+    }
+    Some(ty)
+}
+
+/// the values of the rust integer type `rust`
+fn integer_range(rust: &str) -> Option<(i128, i128)> {
+    let range = match rust {
+        "i8" => (i8::MIN.into(), i8::MAX.into()),
+        "i16" => (i16::MIN.into(), i16::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        "i64" | "isize" => (i64::MIN.into(), i64::MAX.into()),
+        "u8" => (0, u8::MAX.into()),
+        "u16" => (0, u16::MAX.into()),
+        "u32" => (0, u32::MAX.into()),
+        "u64" | "usize" => (0, u64::MAX.into()),
+        _ => return None,
+    };
+    Some(range)
+}
+
+/// the type of the values of an enum, int unless given or one of the
+/// values does not fit, unsigned int or long then
+fn enum_repr(found: &Enum) -> Type {
+    if let Some(ref ty) = found.underlying {
+        return Type {
+            is_const: false,
+            ..ty.clone()
+        };
+    }
+    let values: Vec<i64> = enum_values(found).into_iter().flatten().collect();
+    let fits = |min: i64, max: i64| values.iter().all(|v| (min..=max).contains(v));
+    if fits(i32::MIN.into(), i32::MAX.into()) {
+        Type::primitive(TokenType::Integer)
+    } else if fits(0, u32::MAX.into()) {
+        Type {
+            modifier: Modifier::Unsigned,
+            ..Type::primitive(TokenType::Integer)
+        }
+    } else {
+        Type::primitive(TokenType::Long)
+    }
+}
+
+/**
+ * enum_values:
+ * values of the enumerators, counting on from the previous one. `None`
+ * from a value which is not a constant expression on
+ */
+fn enum_values(found: &Enum) -> Vec<Option<i64>> {
+    let mut values: Vec<Option<i64>> = Vec::new();
+    let mut next = Some(0);
+    for enumerator in &found.enumerators {
+        let value = match enumerator.value {
+            Some(ref value) => enumerator_value(value, found, &values),
+            None => next,
+        };
+        next = value.map(|value| value.wrapping_add(1));
+        values.push(value);
+    }
+    values
+}
+
+/// value of a constant expression of literals and earlier enumerators
+fn enumerator_value(expr: &Expr, found: &Enum, values: &[Option<i64>]) -> Option<i64> {
+    match expr {
+        Expr::Literal {
+            kind: LiteralKind::Integer(_),
+            value,
+        } => integer_value(value).ok(),
+        Expr::Ident(name) => {
+            let i = found.enumerators.iter().position(|v| v.name == *name)?;
+            *values.get(i)?
+        }
+        Expr::Path(path) if path.len() == 2 && path[0] == found.name => {
+            enumerator_value(&Expr::Ident(path[1].clone()), found, values)
+        }
+        Expr::Unary { op, operand } => {
+            let value = enumerator_value(operand, found, values)?;
+            match op {
+                UnaryOp::Plus => Some(value),
+                UnaryOp::Minus => Some(value.wrapping_neg()),
+                UnaryOp::BitNot => Some(!value),
+                _ => None,
+            }
+        }
+        Expr::Binary { op, lhs, rhs } => apply(
+            *op,
+            enumerator_value(lhs, found, values)?,
+            enumerator_value(rhs, found, values)?,
+        )
+        .ok(),
+        Expr::Cast { expr, .. } => enumerator_value(expr, found, values),
+        _ => None,
+    }
+}
+
+/// `let mut` for a mutable binding
+fn binding(mutable: bool) -> &'static str {
+    if mutable {
+        "let mut"
+    } else {
+        "let"
+    }
+}
+
+/// `A a;` or `A a = A();`
+fn is_default_construction(declarator: &Declarator) -> bool {
+    match declarator.init {
+        None => true,
+        Some(Initializer::Expr(Expr::Call {
+            ref callee,
+            ref args,
+        })) => {
+            args.is_empty()
+                && **callee == Expr::Ident(declarator.ty.type_name().unwrap_or("").to_string())
+        }
+        _ => false,
+    }
+}
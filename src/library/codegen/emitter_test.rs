@@ -0,0 +1,655 @@
+use std::collections::HashMap;
+
+use crate::library::codegen::emitter::{generate, generate_in, Placement};
+use crate::library::codegen::mutability::Mutability;
+use crate::library::diagnostics::Diagnostics;
+use crate::library::lexer::tokenizer::Tokenizer;
+use crate::library::parser::parser::init_parser;
+use crate::library::preprocessor::preprocessor::{cfg_table, preprocess, Config};
+
+/// translation of the source, preprocessed first
+fn translate_preprocessed(text: &str, config: &Config) -> String {
+    let tokens = Tokenizer::new(text).tokenize().unwrap();
+    let tokens = preprocess(tokens, text, None, config).unwrap();
+    let unit = init_parser(&tokens).unwrap();
+    generate(&unit, Mutability::Loose, &mut Diagnostics::new())
+}
+
+/// translation with mutability overridden, everything immutable when `strict`
+fn translate(text: &str, strict: bool) -> String {
+    let mutability = if strict {
+        Mutability::Strict
+    } else {
+        Mutability::Loose
+    };
+    translate_with(text, mutability)
+}
+
+fn translate_with(text: &str, mutability: Mutability) -> String {
+    let unit = init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap();
+    // fragments are joined with spaces, compare with normalized whitespace
+    generate(&unit, mutability, &mut Diagnostics::new())
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[test]
+fn test_that_function_is_translated() {
+    let rust = translate("int add(int a, int b) { return a + b; }", false);
+    assert!(rust.contains("fn add ( a: i32, b: i32 ) -> i32 {"));
+    assert!(rust.ends_with("a + b }"));
+}
+
+#[test]
+fn test_that_declaration_follows_strict_mode() {
+    let rust = translate("int main() { int a = 5; }", false);
+    assert!(rust.contains("let mut a : i32 = 5 ;"));
+
+    let rust = translate("int main() { int a = 5; }", true);
+    assert!(rust.contains("let a : i32 = 5 ;"));
+}
+
+#[test]
+fn test_that_increment_statement_is_translated() {
+    let rust = translate("int main() { i++; --j; }", false);
+    assert!(rust.contains("i += 1;"));
+    assert!(rust.contains("j -= 1;"));
+}
+
+#[test]
+fn test_that_assignments_used_as_values_become_blocks() {
+    let rust = translate(
+        "int f() { int a, b, c; a = b = 3; while ((c = next()) != 0) { a += c; } return (a += 2) * 2; }",
+        false,
+    );
+    assert!(rust.contains("a = { b = 3; b };"));
+    assert!(rust.contains("while ({ c = next(); c }) != 0"));
+    assert!(rust.contains("({ a += 2; a }) * 2"));
+}
+
+#[test]
+fn test_that_loops_are_translated() {
+    let rust = translate("int main() { for (;;) { } while (1) { } }", false);
+    assert!(rust.contains("loop { } loop { }"));
+
+    let rust = translate("int main() { for (i = 0; i < 10; i++) { f(i); } }", false);
+    assert!(rust.contains("i = 0; while i < 10 { f(i); i += 1; }"));
+}
+
+#[test]
+fn test_that_switch_is_translated_into_match() {
+    let rust = translate(
+        "int main() { switch (x) { case 1: case 2: f(); break; } }",
+        false,
+    );
+    assert!(rust.contains("match x { 1 | 2 => { f(); } _ => {} }"));
+}
+
+#[test]
+fn test_that_struct_variable_is_initialized() {
+    let rust = translate(
+        "struct A { int a; float b; }; int main() { struct A v; }",
+        false,
+    );
+    assert!(rust.contains("struct A { a: i32, b: f32, }"));
+    assert!(rust.contains("let v = A { a: 0i32, b: 0.0f32, };"));
+}
+
+#[test]
+fn test_that_expressions_are_bracketed_by_rust_precedence() {
+    let rust = translate("int main() { x = (a + b) * c; }", false);
+    assert!(rust.contains("x = (a + b) * c;"));
+
+    // `==` binds tighter than `&` in c, weaker in rust
+    let rust = translate("int main() { x = a & b == c; }", false);
+    assert!(rust.contains("x = a & (b == c);"));
+
+    let rust = translate("int main() { x = a - (b - c); }", false);
+    assert!(rust.contains("x = a - (b - c);"));
+
+    let rust = translate("int main() { x = (*p).y; }", false);
+    assert!(rust.contains("x = (*p).y;"));
+
+    let rust = translate("int main() { x = -(-b); *p = 4; *p += 1; }", false);
+    assert!(rust.contains("x = -(-b);"));
+    assert!(rust.contains("*p = 4;"));
+    assert!(rust.contains("*p += 1;"));
+}
+
+#[test]
+fn test_that_cast_and_ternary_are_translated() {
+    let rust = translate("int main() { x = (float) a / b; }", false);
+    assert!(rust.contains("x = a as f32 / b;"));
+
+    let rust = translate("int main() { x = -(int) a < b; }", false);
+    assert!(rust.contains("x = -(a as i32) < b;"));
+
+    let rust = translate("int main() { x = (int) a < b; }", false);
+    assert!(rust.contains("x = (a as i32) < b;"));
+
+    let rust = translate("int main() { x = a > b ? a : b; }", false);
+    assert!(rust.contains("x = if a > b { a } else { b };"));
+}
+
+#[test]
+fn test_that_nested_ternary_is_translated_into_else_if() {
+    let rust = translate("int main() { int s = x < 0 ? -1 : x > 0 ? 1 : 0; }", false);
+    assert!(rust.contains("= if x < 0 { -1 } else if x > 0 { 1 } else { 0 } ;"));
+
+    let rust = translate("int main() { f(a ? b : c, d); }", false);
+    assert!(rust.contains("f(if a { b } else { c }, d);"));
+
+    let rust = translate("int main() { x = 1 + (a ? b : c); }", false);
+    assert!(rust.contains("x = 1 + (if a { b } else { c });"));
+}
+
+#[test]
+fn test_that_untranslated_constructs_are_reported() {
+    let text = "#include <unistd.h>\nint main() {\n    int *p = &x;\n    cout << p;\n    int *q = nullptr;\n}\n\
+                class S {\n    virtual int f() = 0;\n};\nvoid log(int n, ...) {}";
+    let mut diagnostics = Diagnostics::new();
+    let rust = generate(
+        &init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap(),
+        Mutability::Loose,
+        &mut diagnostics,
+    );
+    let reported: Vec<(&str, u32, u32)> = diagnostics
+        .iter()
+        .map(|d| (d.code(), d.span.start.line, d.span.start.column))
+        .collect();
+    assert_eq!(
+        reported,
+        vec![
+            ("CR0005", 1, 1),
+            ("CR0001", 3, 5),
+            ("CR0006", 3, 9),
+            ("CR0012", 4, 5),
+            ("CR0001", 5, 5),
+            ("CR0006", 5, 9),
+            ("CR0022", 5, 9),
+            ("CR0019", 8, 5),
+            ("CR0019", 10, 17),
+        ]
+    );
+    assert!(rust.contains("// FIXME(CR0012): stream operators"));
+    assert!(rust.contains("// cout << p ;"));
+    assert!(rust.contains("// virtual int f ( ) = 0 ;"));
+    assert!(rust.contains("// `...` taking variadic arguments is left out\n"));
+    assert!(rust.contains("fn log ( n: i32 )"));
+}
+
+#[test]
+fn test_that_shorthand_returns_are_reported_at_the_return() {
+    let text = "int f(int a) {\n    a++;\n    return a;\n}\nint main() {\n    return f(1);\n}";
+    let mut diagnostics = Diagnostics::new();
+    let rust = generate(
+        &init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap(),
+        Mutability::Inferred,
+        &mut diagnostics,
+    );
+    let reported: Vec<(&str, u32, u32, u32)> = diagnostics
+        .iter()
+        .map(|d| {
+            (
+                d.code(),
+                d.span.start.line,
+                d.span.start.column,
+                d.span.end.column,
+            )
+        })
+        .collect();
+    // the return of main is a call of `std::process::exit`, not a shorthand
+    assert_eq!(reported, vec![("CR0004", 3, 5, 14)]);
+    assert!(rust.contains("std::process::exit(f(1));"));
+}
+
+#[test]
+fn test_that_unparsed_construct_is_kept_as_comment() {
+    let failure = init_parser(
+        &Tokenizer::new("int main() {\n    x = ;\n    y = 1;\n}")
+            .tokenize()
+            .unwrap(),
+    )
+    .unwrap_err();
+    let mut diagnostics = Diagnostics::new();
+    let rust = generate(&failure.unit, Mutability::Loose, &mut diagnostics);
+    // the error is reported by the parser, not again by the emitter
+    assert!(diagnostics.is_empty());
+    assert!(rust.contains("// FIXME(CR0013): construct could not be parsed"));
+    assert!(rust.contains("// x = ;"));
+    assert!(rust.contains("y = 1;"));
+}
+
+#[test]
+fn test_that_numeric_literals_keep_radix_and_type() {
+    let rust = translate(
+        "int main() { a = 0x1FUL + 0b1010 + 017 + 1'000'000; b = 3.f + .5 + 1e-9 + 0x1.8p3; int c[10u]; }",
+        false,
+    );
+    assert!(rust.contains("a = 0x1F_u64 + 0b1010 + 0o17 + 1_000_000;"));
+    assert!(rust.contains("b = 3.0_f32 + 0.5 + 1e-9 + 12.0;"));
+    assert!(rust.contains("c : [i32; 10]"));
+}
+
+#[test]
+fn test_that_literal_suffixes_follow_the_declared_type() {
+    let rust = translate(
+        "int main() { size_t n = 10u; unsigned char c = 1u; unsigned char d = 300u; \
+         unsigned int e = -1u; long l = 5L; int i = -2L; }",
+        false,
+    );
+    assert!(rust.contains("let mut n : usize = 10 ;"));
+    assert!(rust.contains("let mut c : u8 = 1 ;"));
+    assert!(rust.contains("let mut d : u8 = 300_u32 as u8 ;"));
+    assert!(rust.contains("let mut e : u32 = -1_i64 as u32 ;"));
+    assert!(rust.contains("let mut l : i64 = 5_i64 ;"));
+    assert!(rust.contains("let mut i : i32 = -2 ;"));
+}
+
+#[test]
+fn test_that_text_literals_are_translated() {
+    let rust = translate(
+        r#"int main() { a = "x\101\n" "y"; b = L"\u00e9"; c = R"d(a"b\)d"; d = "\xff"; e = '\0'; f = u8R"(q)" R"(w)"; }"#,
+        false,
+    );
+    assert!(rust.contains(r#"a = "x\x41\ny";"#));
+    assert!(rust.contains(r#"b = "\u{e9}";"#));
+    assert!(rust.contains(r##"c = r#"a"b\"#;"##));
+    assert!(rust.contains(r#"d = b"\xff";"#));
+    assert!(rust.contains(r"e = '\0';"));
+    assert!(rust.contains(r#"f = r"qw";"#));
+}
+
+#[test]
+fn test_that_cpp_keywords_are_translated_or_refused() {
+    let rust = translate(
+        "int main() { a = this->x + static_cast<long>(b) + alignof(double); throw 1; }",
+        false,
+    );
+    assert!(rust.contains("a = self.x + b as i64 + std::mem::align_of::<f64>();"));
+    assert!(rust.contains("// FIXME(CR0017): exception handling is not translated"));
+    assert!(rust.contains("// throw 1 ;"));
+}
+
+#[test]
+fn test_that_bitwise_and_compound_operators_are_translated() {
+    let rust = translate(
+        "int f(int n, ...) { a ^= b ^ c; a &= 1; a |= 2; a <<= 3; a >>= 4; p.x = ::g(n); return 0; }",
+        false,
+    );
+    assert!(rust.contains("fn f ( n: i32 ) -> i32 {"));
+    assert!(rust.contains("a ^= b ^ c;"));
+    assert!(rust.contains("a &= 1;"));
+    assert!(rust.contains("a |= 2;"));
+    assert!(rust.contains("a <<= 3;"));
+    assert!(rust.contains("a >>= 4;"));
+    assert!(rust.contains("p.x = crate::g(n);"));
+}
+
+#[test]
+fn test_that_define_constants_are_translated_into_const_items() {
+    let text = "#define MAX 64\n#define PI 3.14\n#define MASK 0xffUL\n#define NAME \"crust\"\n\
+                #define ON (MAX > 8)\n#define TWICE (MAX * 2)\nint buf[TWICE];";
+    let rust = translate_preprocessed(text, &Config::default());
+    assert!(rust.contains("pub const MAX: i32 = 64;"));
+    assert!(rust.contains("pub const PI: f64 = 3.14;"));
+    assert!(rust.contains("pub const MASK: u64 = 0xff_u64;"));
+    assert!(rust.contains("pub const NAME: &str = \"crust\";"));
+    assert!(rust.contains("pub const ON: bool = MAX > 8;"));
+    assert!(rust.contains("pub const TWICE: i32 = MAX * 2;"));
+    assert!(rust.contains("[i32; TWICE as usize]"));
+
+    let text = "#define MIXED (1 + 2.5)\n#define BIG 3000000000\n#define HIGH 0x80000000\n\
+                #define WIDE (BIG * 2)\n#define MAX 64\n#define HALF (MAX / 2 * 0.5)\nint x;";
+    let rust = translate_preprocessed(text, &Config::default());
+    assert!(rust.contains("pub const MIXED: f64 = 1.0 + 2.5;"));
+    assert!(rust.contains("pub const HALF: f64 = (MAX / 2) as f64 * 0.5;"));
+    assert!(rust.contains("pub const BIG: i64 = 3000000000;"));
+    assert!(rust.contains("pub const HIGH: u32 = 0x80000000;"));
+    assert!(rust.contains("pub const WIDE: i64 = BIG * 2;"));
+}
+
+#[test]
+fn test_that_function_like_macros_are_translated() {
+    let text = "#define SQUARE(x) ((x) * (x))\n\
+                #define IS_EVEN(n) ((n) % 2 == 0)\n\
+                #define HALF(x) ((x) / 2.0)\n\
+                #define SWAP(a, b) do { int t = a; a = b; b = t; } while (0)\n\
+                #define NAME_OF(x) #x\n\
+                #define LOG(fmt, ...) printf(fmt, __VA_ARGS__)\n\
+                #define CAT(a, b) a ## b\n\
+                int main() { int CAT(v, 1) = SQUARE(3); SWAP(v1, v1); LOG(\"%s\", NAME_OF(v1)); }";
+    let rust = translate_preprocessed(text, &Config::default())
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    assert!(rust.contains("#[inline] fn SQUARE(x: i32) -> i32 { x * x }"));
+    assert!(rust.contains("#[inline] fn IS_EVEN(n: i32) -> bool { n % 2 == 0 }"));
+    assert!(rust.contains("#[inline] fn HALF(x: f64) -> f64 { x / 2.0 }"));
+    assert!(rust.contains(
+        "macro_rules! SWAP { ($a:expr, $b:expr) => { { let mut t : i32 = $a ; $a = $b; $b = t; } }; }"
+    ));
+    assert!(rust.contains("macro_rules! NAME_OF { ($x:expr) => { stringify!($x) }; }"));
+    assert!(rust.contains(
+        "macro_rules! LOG { ($fmt:expr, $($__VA_ARGS__:expr),*) => { printf($fmt, $($__VA_ARGS__),*) }; }"
+    ));
+    assert!(rust.contains("// FIXME(CR0021)"));
+    assert!(rust.contains("let mut v1 : i32 = SQUARE(3) ;"));
+    assert!(rust.contains("SWAP!(v1, v1); LOG!(\"%s\", NAME_OF!(v1));"));
+}
+
+#[test]
+fn test_that_macro_parameters_are_typed_from_their_arguments() {
+    let text = "#define SQUARE(x) ((x) * (x))\n\
+                #define MAX(a, b) ((a) > (b) ? (a) : (b))\n\
+                #define NEG(x) (-(x))\n\
+                #define ID(x) (x)\n\
+                int main() { double d = SQUARE(1.5); double m = MAX(1.0, 2.0); \
+                long l = 4; long n = NEG(l) + NEG(3); }";
+    let rust = translate_preprocessed(text, &Config::default())
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    assert!(rust.contains("#[inline] fn SQUARE(x: f64) -> f64 { x * x }"));
+    assert!(rust.contains("#[inline] fn MAX(a: f64, b: f64) -> f64 {"));
+    // invoked with a long and an int, or never, the type is not known
+    assert!(rust.contains("macro_rules! NEG { ($x:expr) => { -$x }; }"));
+    assert!(rust.contains("macro_rules! ID { ($x:expr) => { $x }; }"));
+    assert!(rust.contains("let mut n : i64 = NEG!(l) + NEG!(3) ;"));
+}
+
+#[test]
+fn test_that_macros_of_headers_stay_macro_rules() {
+    let parse = |text: &str| {
+        let tokens = Tokenizer::new(text).tokenize().unwrap();
+        init_parser(&preprocess(tokens, text, None, &Config::default()).unwrap()).unwrap()
+    };
+    let header = parse("#define SQUARE(x) ((x) * (x))\n");
+    let source = parse("int main() { int v = 2; int r = SQUARE(v); }");
+    let headers = [&header];
+    let placement = Placement {
+        headers: &headers,
+        ..Placement::default()
+    };
+    let normalize = |rust: String| rust.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let module = normalize(generate_in(
+        &header,
+        Mutability::Loose,
+        Placement {
+            module: true,
+            ..placement
+        },
+        &mut Diagnostics::new(),
+    ));
+    let rust = normalize(generate_in(
+        &source,
+        Mutability::Loose,
+        placement,
+        &mut Diagnostics::new(),
+    ));
+    assert!(module.contains("macro_rules! SQUARE { ($x:expr) => { $x * $x }; }"));
+    assert!(rust.contains("let mut r : i32 = SQUARE!(v) ;"));
+}
+
+#[test]
+fn test_that_configuration_conditionals_are_translated_into_cfg() {
+    let config = Config {
+        cfg: Some(cfg_table(&[])),
+        ..Config::default()
+    };
+    let text = "#ifdef _WIN32\n#define SEP '\\\\'\n#else\n#define SEP '/'\n#endif\n\
+                struct Conn { int fd;\n#ifdef __linux__\nint epoll;\n#endif\n};\n\
+                #ifndef NDEBUG\nvoid trace(int x) { int y = x; }\n#endif\n\
+                int main() { int total = 0;\n#ifdef _WIN32\nint a = 1, b = 2; total += a;\n\
+                #else\ntotal = 3;\n#endif\nreturn total; }";
+    let rust = translate_preprocessed(text, &config)
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    assert!(rust.contains("#[cfg(windows)] pub const SEP: char = '\\\\';"));
+    assert!(rust.contains("#[cfg(not(windows))] pub const SEP: char = '/';"));
+    assert!(rust.contains("fd: i32, #[cfg(target_os = \"linux\")] epoll: i32,"));
+    assert!(rust.contains("#[cfg(debug_assertions)] fn trace ( x: i32 ) { let mut y : i32 = x ; }"));
+    assert!(rust.contains(
+        "#[cfg(windows)] let mut a : i32 = 1 ; #[cfg(windows)] let mut b : i32 = 2 ; \
+         #[cfg(windows)] { total += a; } #[cfg(not(windows))] { total = 3; }"
+    ));
+}
+
+#[test]
+fn test_that_included_headers_are_translated_into_modules() {
+    let dir = std::env::temp_dir().join(format!("crust-emit-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("str-utils.h"), "#define LEN 8\n").unwrap();
+    let text = "#include <map>\n#include <stdio.h>\n#include <iostream>\n#include <vector>\n\
+                #include \"str-utils.h\"\nint n = LEN;";
+    let tokens = Tokenizer::new(text).tokenize().unwrap();
+    let tokens = preprocess(tokens, text, Some(&dir.join("main.c")), &Config::default()).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let unit = init_parser(&tokens).unwrap();
+    let mut diagnostics = Diagnostics::new();
+    let rust = generate(&unit, Mutability::Loose, &mut diagnostics)
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    assert!(rust.contains(
+        "// #include <map> use std::collections::BTreeMap; // #include <stdio.h> use std::io; \
+         // #include <iostream> // #include <vector> #[macro_use] mod str_utils; use str_utils::*;"
+    ));
+    assert!(rust.contains("static mut n : i32 = LEN ;"));
+    // the headers are known, nothing is reported about them
+    assert!(diagnostics.iter().all(|d| d.code() != "CR0005"));
+    let module = generate_in(
+        &unit,
+        Mutability::Loose,
+        Placement {
+            module: true,
+            ..Placement::default()
+        },
+        &mut Diagnostics::new(),
+    );
+    assert!(module.contains("use crate::str_utils::*;"));
+    // the module of the header is renamed, a source is translated into str_utils.rs
+    let renamed: HashMap<String, String> = unit
+        .headers()
+        .into_iter()
+        .map(|header| (header, "str_utils_h".to_string()))
+        .collect();
+    let rust = generate_in(
+        &unit,
+        Mutability::Loose,
+        Placement {
+            renamed: Some(&renamed),
+            ..Placement::default()
+        },
+        &mut Diagnostics::new(),
+    );
+    assert!(rust.contains("mod str_utils_h;") && rust.contains("use str_utils_h::*;"));
+}
+
+#[test]
+fn test_that_items_of_modules_are_public() {
+    let text =
+        "struct P { int x; int get() { return x; } }; int f() { return 1; } int g; typedef int T;";
+    let unit = init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap();
+    let rust = generate_in(
+        &unit,
+        Mutability::Loose,
+        Placement {
+            module: true,
+            ..Placement::default()
+        },
+        &mut Diagnostics::new(),
+    )
+    .split_whitespace()
+    .collect::<Vec<&str>>()
+    .join(" ");
+    assert!(rust.contains("pub struct P { pub x: i32, }"));
+    assert!(rust.contains("pub fn get ( &self ) -> i32"));
+    assert!(rust.contains("pub fn f ( ) -> i32"));
+    assert!(rust.contains("pub static mut g : i32 = 0i32 ;"));
+    assert!(rust.contains("pub type T = i32 ;"));
+}
+
+#[test]
+fn test_that_prototypes_import_functions_defined_by_other_modules() {
+    let text = "/* adds */ int util_add(int a, int b); int util_sub(int a, int b);";
+    let unit = init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap();
+    let defined: HashMap<String, String> = vec![("util_add".to_string(), "src::util".to_string())]
+        .into_iter()
+        .collect();
+    let rust = generate_in(
+        &unit,
+        Mutability::Loose,
+        Placement {
+            module: true,
+            defined: Some(&defined),
+            ..Placement::default()
+        },
+        &mut Diagnostics::new(),
+    );
+    assert!(rust.contains("pub use crate::src::util::util_add;"));
+    assert!(!rust.contains("util_sub"));
+}
+
+#[test]
+fn test_that_doc_comments_become_rust_doc_comments() {
+    let text = "/**\n * A point\n */\nstruct P {\n  /** abscissa */ int x; // horizontal\n};\n\
+                /// adds\nint add(int a, int b);\n/** adds `a` and `b` */\nint add(int a, int b) { return a + b; }";
+    let unit = init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap();
+    let rust = generate(&unit, Mutability::Loose, &mut Diagnostics::new());
+    assert!(rust.contains("\n/// A point\n"));
+    assert!(rust.contains("\n/// abscissa\n"));
+    assert!(rust.contains("x: i32, // horizontal\n"));
+    // the prototype is not translated, its doc is a plain comment
+    assert!(rust.contains("\n// adds\n"));
+    assert!(rust.contains("\n/// adds `a` and `b`\n"));
+    assert!(!rust.contains("/** "));
+
+    // the comments of the parameters stay in the parameter list
+    let text = "int f(int a, /* the b */ int b) { return a + b; }\n\
+                int g(int a, // first\n int b /* second */) { return a; }";
+    let unit = init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap();
+    let rust = generate(&unit, Mutability::Loose, &mut Diagnostics::new());
+    assert!(rust.contains("a: i32, /* the b */ b: i32 )"));
+    assert!(rust.contains("a: i32, // first\n b: i32 /* second */ )"));
+}
+
+#[test]
+fn test_that_numbers_tested_for_truth_are_compared_with_zero() {
+    let rust = translate(
+        "int f(int n, double d, int *p) { while (n && !d) n--; return p && n ? 1 : 0; }",
+        false,
+    );
+    assert!(rust.contains("while n != 0 && d == 0.0 {"));
+    assert!(rust.contains("if !(p as *const i32).is_null() && n != 0 {"));
+
+    // the whole condition is typed, not only variables
+    let rust = translate(
+        "enum E { A, B }; int g(); int f(int x, char c, enum E e, bool b, int n) { \
+         if (x & 1) x++; if (g()) x++; if (!c) x++; if (e) x++; if (b) x++; \
+         while (n--) x++; return x; }",
+        false,
+    );
+    assert!(rust.contains("if x & 1 != 0 {"));
+    assert!(rust.contains("if g() != 0 {"));
+    assert!(rust.contains("if c == '\\0' {"));
+    assert!(rust.contains("if e as i32 != 0 {"));
+    assert!(rust.contains("if b {"));
+    assert!(rust.contains("while { n -= 1; n + 1 } != 0 {"));
+
+    // the parameter of f is not in the scope of g
+    let rust = translate(
+        "int f(int n) { return n; } int g(bool n) { if (n) return 1; return 0; }",
+        false,
+    );
+    assert!(rust.contains("if n {"));
+}
+
+#[test]
+fn test_that_only_written_variables_are_mutable() {
+    let rust = translate_with(
+        "void swap(int *a, int *b, const int *c) { int t = *a; *a = *b; *b = t; } \
+         int sum(int n, int *v) { int s = 0; while (n--) s += v[n]; return s; }",
+        Mutability::Inferred,
+    );
+    assert!(rust.contains("fn swap ( a: &mut i32, b: &mut i32, c: &i32 )"));
+    assert!(rust.contains("let t : i32 = *a ;"));
+    assert!(rust.contains("fn sum ( mut n: i32, v: &i32 )"));
+    assert!(rust.contains("let mut s : i32 = 0 ;"));
+
+    let rust = translate_with(
+        "struct C { int n; void inc() { n++; } int get() { return n; } };",
+        Mutability::Inferred,
+    );
+    assert!(rust.contains("fn inc ( &mut self )"));
+    assert!(rust.contains("fn get ( &self )"));
+
+    let rust = translate_with(
+        "int read(const int *p) { return *p; } void bump(int *p) { *p += 1; } \
+         int main() { int b = 1, a = 2; int c; c = read(&b); bump(&a); return a + c; }",
+        Mutability::Inferred,
+    );
+    assert!(rust.contains("let b : i32 = 1 ;"));
+    assert!(rust.contains("let mut a : i32 = 2 ;"));
+    assert!(rust.contains("let c : i32 ; c = read(&b);"));
+    assert!(rust.contains("bump(&mut a);"));
+
+    let rust = translate_with(
+        "void set(int &r) { r = 1; } \
+         int main() { int c = 0, x = 1; set(c); int *q = &x; return *q; }",
+        Mutability::Inferred,
+    );
+    assert!(rust.contains("let mut c : i32 = 0 ;"));
+    assert!(rust.contains("set(&mut c);"));
+    assert!(rust.contains("let x : i32 = 1 ;"));
+    assert!(rust.contains("let q : &i32 = &x ;"));
+}
+
+#[test]
+fn test_that_enums_become_rust_enums() {
+    let rust = translate(
+        "enum Color { RED, GREEN = 5, BLUE, CYAN = GREEN }; \
+         enum class Mode : uint8_t { Off, On }; enum { MAX_USERS = 10 }; \
+         int f(Color c) { int x = RED; if (c < BLUE) x = c + 1; return MAX_USERS; }",
+        false,
+    );
+    assert!(rust.contains(
+        "#[repr(i32)] #[derive(Clone, Copy, PartialEq, Eq, Debug)] enum Color { RED = 0, GREEN = 5, BLUE = 6, }"
+    ));
+    assert!(rust.contains("impl Color { pub const CYAN: Color = Color::GREEN; }"));
+    assert!(rust.contains(
+        "#[repr(u8)] #[derive(Clone, Copy, PartialEq, Eq, Debug)] enum Mode { Off, On, }"
+    ));
+    assert!(rust.contains("const MAX_USERS: i32 = 10;"));
+    assert!(rust.contains("let mut x : i32 = Color::RED as i32 ;"));
+    assert!(rust.contains("if (c as i32) < Color::BLUE as i32 {"));
+    assert!(rust.contains("x = c as i32 + 1;"));
+
+    let rust = translate(
+        "enum Color { RED, GREEN = 5, BLUE }; enum Big { A = 1 << 40 }; int g(int v) { return v; } \
+         void f(Color c) { int r = g(c); int arr[BLUE]; Color d = (Color)5; }",
+        false,
+    );
+    assert!(rust.contains("#[repr(i64)] #[derive(Clone, Copy, PartialEq, Eq, Debug)] enum Big {"));
+    assert!(rust.contains("let mut r : i32 = g(c as i32) ;"));
+    assert!(rust.contains("let mut arr : [i32; Color::BLUE as usize] ;"));
+    assert!(rust.contains(
+        "let mut d : Color = match 5 { 0 => Color::RED, 5 => Color::GREEN, 6 => Color::BLUE, \
+         _ => unreachable!(\"no Color of this value\"), } ;"
+    ));
+
+    let rust = translate(
+        "enum Color { RED, GREEN = 5 }; \
+         void f(int v, Color c) { Color d = (enum Color)(v & 1); \
+         switch (v) { case RED: case 3: break; case 4: break; } \
+         switch (c) { case GREEN: break; default: break; } }",
+        false,
+    );
+    assert!(rust.contains("let mut d : Color = match v & 1 {"));
+    assert!(rust.contains(
+        "match v { switch if switch == Color::RED as i32 || switch == 3 => { } 4 => { } _ => {} }"
+    ));
+    assert!(rust.contains("match c { Color::GREEN => { } _ => { } }"));
+}
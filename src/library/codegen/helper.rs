@@ -0,0 +1,354 @@
+use crate::library::lexeme::definition::TokenType;
+use crate::library::lexeme::definition::TokenType::*;
+use crate::library::lexeme::literal::{decode, Encoding, Unit};
+use crate::library::lexeme::number::{Number, Radix, Suffix};
+
+pub fn get_operator_as_fucn_name(op: TokenType) -> &'static str {
+    match op {
+        Plus => "_plus",
+        Minus => "_minus",
+        Multiplication => "_mult",
+        Divide => "_div",
+        PlusEqual => "_plus_eq",
+        MinusEqual => "_minus_eq",
+        MultiplyEqual => "_mult_eq",
+        DivideEqual => "_div_eq",
+        Increment => "_inc",
+        Decrement => "_dec",
+        Equal => "_eq",
+        Assignment => "_assign",
+        RightBracket => "_call",
+        _ => "_misc_op",
+    }
+}
+
+/**
+ * rust_integer:
+ * rust spelling of a c/c++ integer literal
+ * 017 -> 0o17, 0X1FUL -> 0x1F_u64, 1'000 -> 1_000
+ */
+pub fn rust_integer(text: &str, number: Number) -> String {
+    let digits = text
+        .trim_end_matches(['u', 'U', 'l', 'L'])
+        .replace('\'', "_");
+    let digits = match number.radix {
+        Radix::Octal => format!("0o{}", &digits[1..]),
+        Radix::Hexadecimal | Radix::Binary => digits[..2].to_ascii_lowercase() + &digits[2..],
+        Radix::Decimal => digits,
+    };
+    let suffix = match number.suffix {
+        Suffix::Unsigned => "u32",
+        Suffix::Long | Suffix::LongLong => "i64",
+        Suffix::UnsignedLong | Suffix::UnsignedLongLong => "u64",
+        _ => "",
+    };
+    with_suffix(digits, suffix)
+}
+
+/**
+ * rust_float:
+ * rust spelling of a c/c++ floating literal
+ * .5 -> 0.5, 3.f -> 3.0_f32, 0x1.8p3 -> 12.0
+ */
+pub fn rust_float(text: &str, number: Number) -> String {
+    let digits = text.replace('\'', "_");
+    let digits = digits.trim_end_matches(['f', 'F', 'l', 'L']);
+    let digits = if number.radix == Radix::Hexadecimal {
+        // rust has no hexadecimal floating literals
+        format!("{:?}", hex_float_value(&digits[2..]))
+    } else {
+        // rust needs digits on both sides of the point
+        let mut out = String::new();
+        let mut chars = digits.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '.' {
+                if out.is_empty() {
+                    out.push('0');
+                }
+                out.push('.');
+                if !matches!(chars.peek(), Some('0'..='9')) {
+                    out.push('0');
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    };
+    let suffix = match number.suffix {
+        Suffix::Float => "f32",
+        Suffix::LongDouble => "f64",
+        _ => "",
+    };
+    with_suffix(digits, suffix)
+}
+
+/// value of the hexadecimal mantissa and binary exponent, `1.8p3`
+fn hex_float_value(text: &str) -> f64 {
+    let (mantissa, exponent) = text.split_at(text.find(['p', 'P']).unwrap_or(text.len()));
+    let exponent: i32 = exponent.get(1..).and_then(|e| e.parse().ok()).unwrap_or(0);
+    let mut value = 0.0;
+    let mut fraction_digits = 0;
+    let mut fraction = false;
+    for c in mantissa.chars() {
+        match c.to_digit(16) {
+            Some(digit) => {
+                value = value * 16.0 + f64::from(digit);
+                if fraction {
+                    fraction_digits += 1;
+                }
+            }
+            None => fraction = c == '.' || fraction,
+        }
+    }
+    value * 2f64.powi(exponent - 4 * fraction_digits)
+}
+
+fn with_suffix(digits: String, suffix: &str) -> String {
+    if suffix.is_empty() {
+        digits
+    } else {
+        format!("{}_{}", digits, suffix)
+    }
+}
+
+/**
+ * rust_string:
+ * rust spelling of c/c++ string literals, adjacent ones concatenated
+ * R"(a\b)" -> r"a\b", "\xff" -> b"\xff", L"\u00e9" -> "\u{e9}"
+ */
+pub fn rust_string(text: &str) -> String {
+    let literal = match decode(text) {
+        Ok(literal) => literal,
+        Err(_) => return text.to_string(),
+    };
+    let narrow = matches!(literal.prefix.encoding, Encoding::Narrow | Encoding::Utf8);
+    // bytes which are not ascii can only be written in a byte string
+    if narrow
+        && literal
+            .units
+            .iter()
+            .any(|unit| matches!(unit, Unit::Escape(v) if *v > 0x7f))
+    {
+        let mut out = String::from("b\"");
+        for unit in &literal.units {
+            match *unit {
+                Unit::Char(c) if c.is_ascii() => out.push_str(&escape_char(c, '"')),
+                Unit::Char(c) => {
+                    for byte in c.to_string().bytes() {
+                        out.push_str(&format!("\\x{:02x}", byte));
+                    }
+                }
+                Unit::Escape(value) => out.push_str(&format!("\\x{:02x}", value)),
+            }
+        }
+        out.push('"');
+        return out;
+    }
+
+    if literal.prefix.raw {
+        let body: String = literal
+            .units
+            .iter()
+            .filter_map(|unit| match unit {
+                Unit::Char(c) => Some(*c),
+                Unit::Escape(_) => None,
+            })
+            .collect();
+        // enough hashes for no quote in the body to end the literal
+        let mut hashes = String::new();
+        while body.contains(&format!("\"{}", hashes)) {
+            hashes.push('#');
+        }
+        return format!("r{}\"{}\"{}", hashes, body, hashes);
+    }
+
+    let mut out = String::from("\"");
+    for unit in &literal.units {
+        match *unit {
+            Unit::Char(c) => out.push_str(&escape_char(c, '"')),
+            Unit::Escape(value) => out.push_str(&escape_value(value)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/**
+ * rust_char:
+ * rust spelling of a c/c++ character literal
+ * '\101' -> '\x41', L'\u00e9' -> '\u{e9}', a multicharacter
+ * literal such as 'ab' is an int and becomes its value
+ */
+pub fn rust_char(text: &str) -> String {
+    let literal = match decode(text) {
+        Ok(literal) => literal,
+        Err(_) => return text.to_string(),
+    };
+    match literal.units.as_slice() {
+        [Unit::Char(c)] => format!("'{}'", escape_char(*c, '\'')),
+        [Unit::Escape(value)] => format!("'{}'", escape_value(*value)),
+        units => units
+            .iter()
+            .fold(0u32, |value, unit| {
+                let unit = match *unit {
+                    Unit::Char(c) => c as u32,
+                    Unit::Escape(v) => v,
+                };
+                value.wrapping_shl(8) | (unit & 0xff)
+            })
+            .to_string(),
+    }
+}
+
+/// the character as written in a rust literal delimited by `quote`
+fn escape_char(c: char, quote: char) -> String {
+    match c {
+        '\\' => "\\\\".to_string(),
+        '\n' => "\\n".to_string(),
+        '\r' => "\\r".to_string(),
+        '\t' => "\\t".to_string(),
+        '\0' => "\\0".to_string(),
+        c if c == quote => format!("\\{}", c),
+        c if c.is_control() => escape_value(c as u32),
+        c => c.to_string(),
+    }
+}
+
+/// numeric escapes stay explicit, `\x41`, `\u{e9}`
+fn escape_value(value: u32) -> String {
+    match char::from_u32(value) {
+        Some('\0') => "\\0".to_string(),
+        Some(_) if value < 0x80 => format!("\\x{:02x}", value),
+        Some(_) => format!("\\u{{{:x}}}", value),
+        // surrogates and values out of the unicode range
+        None => "\\u{fffd}".to_string(),
+    }
+}
+
+/// keywords of rust, strict and reserved, which can not name a module
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/**
+ * module_name:
+ * name of the module a header is translated into, a rust keyword
+ * gets a trailing `_`
+ * include/str-utils.h -> str_utils, type.h -> type_
+ */
+pub fn module_name(header: &str) -> String {
+    let stem = header.rsplit(['/', '\\']).next().unwrap_or(header);
+    let stem = stem.split('.').next().unwrap_or(stem);
+    let name: String = stem
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+        format!("_{}", name)
+    } else if RUST_KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+/**
+ * std_equivalent:
+ * import of the rust std counterpart of a system header, an empty
+ * import when the prelude has it. `None` for headers with no counterpart
+ * <map> -> use std::collections::BTreeMap;
+ */
+pub fn std_equivalent(header: &str) -> Option<&'static str> {
+    let import = match header {
+        "stdio.h" | "cstdio" | "iostream" | "istream" | "ostream" | "sstream" | "iomanip"
+        | "errno.h" | "cerrno" => "use std::io;",
+        "fstream" => "use std::fs;",
+        "stdlib.h" | "cstdlib" => "use std::process;",
+        "map" => "use std::collections::BTreeMap;",
+        "set" => "use std::collections::BTreeSet;",
+        "unordered_map" => "use std::collections::HashMap;",
+        "unordered_set" => "use std::collections::HashSet;",
+        "deque" | "queue" => "use std::collections::VecDeque;",
+        "list" => "use std::collections::LinkedList;",
+        "memory" => "use std::rc::Rc;",
+        "thread" | "pthread.h" => "use std::thread;",
+        "mutex" => "use std::sync::Mutex;",
+        "atomic" | "stdatomic.h" => "use std::sync::atomic;",
+        "time.h" | "ctime" | "chrono" => "use std::time;",
+        // the prelude, primitive types and methods have them
+        "string" | "string.h" | "cstring" | "vector" | "stack" | "array" | "math.h" | "cmath"
+        | "stdint.h" | "cstdint" | "stdbool.h" | "stddef.h" | "cstddef" | "limits.h"
+        | "climits" | "limits" | "float.h" | "cfloat" | "ctype.h" | "cctype" | "assert.h"
+        | "cassert" | "algorithm" | "utility" | "optional" | "tuple" | "functional" => "",
+        _ => return None,
+    };
+    Some(import)
+}
+
+/// rust primitive of a fixed width type of <stdint.h> and <stddef.h>, uint8_t -> u8
+pub fn fixed_width_type(name: &str) -> Option<&'static str> {
+    let primitive = match name.trim_start_matches("std::") {
+        "int8_t" => "i8",
+        "int16_t" => "i16",
+        "int32_t" => "i32",
+        "int64_t" => "i64",
+        "uint8_t" => "u8",
+        "uint16_t" => "u16",
+        "uint32_t" => "u32",
+        "uint64_t" => "u64",
+        "size_t" | "uintptr_t" => "usize",
+        "ssize_t" | "intptr_t" | "ptrdiff_t" => "isize",
+        _ => return None,
+    };
+    Some(primitive)
+}
+
+/**
+ * doc_comment:
+ * `///` lines of a `/** */` or `/*! */` doc comment, none for other comments
+ * /** Adds `a` and `b`
+ *  * saturating */ -> /// Adds `a` and `b`\n/// saturating
+ */
+pub fn doc_comment(text: &str) -> Option<Vec<String>> {
+    let body = text
+        .strip_prefix("/**")
+        .or_else(|| text.strip_prefix("/*!"))
+        .filter(|body| !body.starts_with('*') && !body.starts_with('/'))?;
+    let body = body.strip_suffix("*/").unwrap_or(body);
+    let mut lines: Vec<&str> = body
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect();
+    while lines.first().is_some_and(|line| line.is_empty()) {
+        lines.remove(0);
+    }
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    Some(
+        lines
+            .into_iter()
+            .map(|line| format!("/// {}", line).trim_end().to_string())
+            .collect(),
+    )
+}
+
+/// a comment which documents nothing, `/** */` and `///` are doc comments in rust
+pub fn plain_comment(text: &str) -> String {
+    match text.get(..3) {
+        Some("///") | Some("//!") => format!("//{}", &text[3..]),
+        Some("/**") | Some("/*!") if text != "/**/" && !text.starts_with("/***") => {
+            format!("/*{}", &text[3..])
+        }
+        _ => text.to_string(),
+    }
+}
@@ -0,0 +1,7 @@
+pub mod emitter;
+pub mod helper;
+pub mod mutability;
+pub mod printer;
+
+#[cfg(test)]
+mod emitter_test;
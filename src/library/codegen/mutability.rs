@@ -0,0 +1,644 @@
+//! Which variables a function writes, so that only those are declared `mut`
+
+use std::collections::BTreeSet;
+
+use crate::library::ast::*;
+use crate::library::lexeme::definition::TokenType;
+use crate::library::lexeme::span::{Position, Span};
+
+/// How the mutability of the variables of the translation is decided
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mutability {
+    /// mutable where the function writes them
+    #[default]
+    Inferred,
+    /// every variable immutable, `--strict`
+    Strict,
+    /// every variable mutable, `--loose`
+    Loose,
+}
+
+impl Mutability {
+    /// name of the mode in the status messages
+    pub fn name(self) -> &'static str {
+        match self {
+            Mutability::Inferred => "Inferred",
+            Mutability::Strict => "Strict",
+            Mutability::Loose => "Loose",
+        }
+    }
+}
+
+/// a variable by its name and where it is declared, parameters are
+/// declared at the function
+type Key = (String, Position);
+
+/**
+ * Writes:
+ * the variables a function writes: assigned, incremented, borrowed
+ * with `&` or passed as pointer or reference. A write through a pointer or reference
+ * makes what it points to mutable, not the pointer itself
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Writes {
+    bindings: BTreeSet<Key>,
+    pointees: BTreeSet<Key>,
+    /// the object the method is called on is written, through `this` or a
+    /// name not declared in the function
+    pub receiver: bool,
+}
+
+/// what the calls of a function are resolved with: the functions, the
+/// records with their methods and the function-like macros of the unit
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Callees<'a> {
+    pub functions: &'a [&'a Function],
+    pub records: &'a [&'a Record],
+    pub macros: &'a [&'a Macro],
+}
+
+impl Writes {
+    /// the writes of `function`, which may call the `callees`
+    pub fn of(function: &Function, callees: Callees) -> Writes {
+        let mut analysis = Analysis {
+            symbols: SymbolTable::new(),
+            writes: Writes::default(),
+            callees,
+            loops: 0,
+            uninitialized: BTreeSet::new(),
+            borrows: Vec::new(),
+        };
+        analysis.symbols.enter(ScopeKind::Function);
+        for param in &function.params {
+            if let Some(ref name) = param.name {
+                analysis.declare(name, &param.ty, function.span);
+            }
+        }
+        if let Some(ref body) = function.body {
+            analysis.stmts(&body.stmts);
+        }
+        analysis.lend();
+        analysis.writes
+    }
+
+    /// the variable declared at `span` is assigned after its declaration
+    pub fn is_written(&self, name: &str, span: Span) -> bool {
+        self.bindings.contains(&(name.to_string(), span.start))
+    }
+
+    /// the value the pointer or reference declared at `span` refers to is written
+    pub fn is_pointee_written(&self, name: &str, span: Span) -> bool {
+        self.pointees.contains(&(name.to_string(), span.start))
+    }
+}
+
+/// walks a function body with the scopes of its declarations, to tell
+/// which declaration a written name refers to
+struct Analysis<'a> {
+    symbols: SymbolTable,
+    writes: Writes,
+    callees: Callees<'a>,
+    /// loops the statement walked is in, an assignment in them may run again
+    loops: usize,
+    /// variables declared without a value, which are not assigned yet
+    uninitialized: BTreeSet<Key>,
+    /// pointers given the address of a variable, which is written when
+    /// they are written through, the variable being a reference or not
+    borrows: Vec<(Key, Key, bool)>,
+}
+
+/**
+ * macro_writes:
+ * the writes of the parameters of a function-like macro, the arguments
+ * given for them are written where the macro is invoked
+ */
+fn macro_writes(found: &Macro) -> Writes {
+    let mut analysis = Analysis {
+        symbols: SymbolTable::new(),
+        writes: Writes::default(),
+        callees: Callees::default(),
+        loops: 0,
+        uninitialized: BTreeSet::new(),
+        borrows: Vec::new(),
+    };
+    for param in &found.params {
+        let ty = Type::primitive(TokenType::Auto);
+        analysis.declare(param, &ty, found.span);
+    }
+    match found.body {
+        MacroBody::Expr(ref body) => analysis.expr(body),
+        MacroBody::Stmts(ref body) => analysis.scoped(body),
+    }
+    analysis.lend();
+    analysis.writes
+}
+
+impl<'a> Analysis<'a> {
+    fn declare(&mut self, name: &str, ty: &Type, span: Span) {
+        self.symbols.declare(Symbol {
+            name: name.to_string(),
+            kind: SymbolKind::Variable,
+            ty: ty.clone(),
+            span,
+        });
+    }
+
+    fn scoped(&mut self, stmts: &[Stmt]) {
+        self.symbols.enter(ScopeKind::Block);
+        self.stmts(stmts);
+        self.symbols.leave();
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Decl(decl) => {
+                for declarator in &decl.declarators {
+                    let ty = &declarator.ty;
+                    let key = (declarator.name.clone(), declarator.span.start);
+                    match declarator.init {
+                        Some(Initializer::Expr(ref value)) => self.value(value, ty, Some(key)),
+                        Some(Initializer::List(ref values)) => {
+                            values.iter().for_each(|value| self.expr(value))
+                        }
+                        // a scalar given its value later needs no `mut`, unless given another
+                        None if ty.array.is_empty()
+                            && !ty.is_static
+                            && (ty.is_pointer() || matches!(ty.base, BaseType::Primitive(_))) =>
+                        {
+                            self.uninitialized.insert(key);
+                        }
+                        None => {}
+                    }
+                    self.declare(&declarator.name, ty, declarator.span);
+                }
+            }
+            Stmt::Expr(expr, _) | Stmt::Return(Some(expr), _) => self.expr(expr),
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                self.expr(cond);
+                self.scoped(then.as_slice());
+                if let Some(otherwise) = otherwise {
+                    self.scoped(otherwise.as_slice());
+                }
+            }
+            Stmt::While { cond, body, .. } | Stmt::DoWhile { body, cond, .. } => {
+                self.loops += 1;
+                self.expr(cond);
+                self.scoped(body.as_slice());
+                self.loops -= 1;
+            }
+            Stmt::For {
+                init,
+                cond,
+                step,
+                body,
+                ..
+            } => {
+                self.symbols.enter(ScopeKind::Block);
+                if let Some(init) = init {
+                    self.stmt(init);
+                }
+                self.loops += 1;
+                cond.iter().chain(step).for_each(|expr| self.expr(expr));
+                self.scoped(body.as_slice());
+                self.loops -= 1;
+                self.symbols.leave();
+            }
+            Stmt::Switch {
+                scrutinee, cases, ..
+            } => {
+                self.expr(scrutinee);
+                self.symbols.enter(ScopeKind::Block);
+                for case in cases {
+                    self.stmts(&case.body);
+                }
+                self.symbols.leave();
+            }
+            Stmt::Block(block) => self.scoped(&block.stmts),
+            Stmt::Cfg { stmts, .. } => self.stmts(stmts),
+            _ => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Assign { op, target, value } => {
+                if *op != AssignOp::Assign || !self.initialize(target) {
+                    self.write(target, false);
+                }
+                self.expr(target);
+                match self.variable(target) {
+                    Some((ty, key)) => self.value(value, &ty, Some(key)),
+                    None => self.expr(value),
+                }
+            }
+            Expr::Unary {
+                op: UnaryOp::PreIncrement | UnaryOp::PreDecrement | UnaryOp::AddressOf,
+                operand,
+            }
+            | Expr::Postfix { operand, .. } => {
+                self.write(operand, false);
+                self.expr(operand);
+            }
+            Expr::Unary { operand, .. } => self.expr(operand),
+            Expr::Binary { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            Expr::Call { callee, args } => {
+                let function = self.callee(callee);
+                // methods which are not const may change the object they are called on
+                if let Expr::Member { base, arrow, .. } = &**callee {
+                    if !function.is_some_and(|method| method.is_const) {
+                        self.write(base, *arrow);
+                    }
+                }
+                if let Expr::Ident(name) = &**callee {
+                    if let Some(found) = self.callees.macros.iter().find(|m| m.name == *name) {
+                        let writes = macro_writes(found);
+                        for (param, arg) in found.params.iter().zip(args) {
+                            if writes.is_written(param, found.span) {
+                                self.write(arg, false);
+                            } else if writes.is_pointee_written(param, found.span) {
+                                self.write(arg, true);
+                            }
+                        }
+                    }
+                }
+                self.expr(callee);
+                for (i, arg) in args.iter().enumerate() {
+                    match function.and_then(|function| function.params.get(i)) {
+                        Some(param) if param.ty.is_pointer() && param.ty.is_const => {
+                            self.value(arg, &param.ty, None)
+                        }
+                        // a reference not to const may be assigned by the function
+                        Some(param) if param.ty.reference && !param.ty.is_const => {
+                            self.write(arg, false);
+                            self.expr(arg);
+                        }
+                        _ => {
+                            self.pass(arg);
+                            self.expr(arg);
+                        }
+                    }
+                }
+            }
+            Expr::Index { base, index } => {
+                self.expr(base);
+                self.expr(index);
+            }
+            Expr::Member { base, .. } | Expr::Cast { expr: base, .. } => self.expr(base),
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                self.expr(cond);
+                self.expr(then);
+                self.expr(otherwise);
+            }
+            Expr::Comma(list) => list.iter().for_each(|expr| self.expr(expr)),
+            _ => {}
+        }
+    }
+
+    /**
+     * value:
+     * walks the value given for a variable or parameter of type `ty`. A
+     * pointer to const only reads what it points to, `read(&b)` with a
+     * `const int *` parameter does not write `b`, and the variable whose
+     * address is kept by the `pointer` is written when it is written through
+     */
+    fn value(&mut self, value: &Expr, ty: &Type, pointer: Option<Key>) {
+        match value {
+            Expr::Unary {
+                op: UnaryOp::AddressOf,
+                operand,
+            } if ty.is_pointer() && ty.is_const => self.expr(operand),
+            Expr::Unary {
+                op: UnaryOp::AddressOf,
+                operand,
+            } if ty.is_pointer() => match (pointer, self.variable(operand)) {
+                (Some(pointer), Some((ty, key))) => {
+                    self.borrows.push((pointer, key, ty.reference));
+                    self.expr(operand)
+                }
+                _ => self.expr(value),
+            },
+            value => self.expr(value),
+        }
+    }
+
+    /// the variables whose address is kept by a pointer written through are written
+    fn lend(&mut self) {
+        let mut lent = true;
+        while lent {
+            lent = false;
+            for (pointer, key, reference) in &self.borrows {
+                if self.writes.pointees.contains(pointer) {
+                    let written = if *reference {
+                        &mut self.writes.pointees
+                    } else {
+                        &mut self.writes.bindings
+                    };
+                    lent |= written.insert(key.clone());
+                }
+            }
+        }
+    }
+
+    /// `target` is a variable declared without a value and given its first
+    /// value out of any loop, `int c; c = 5;` needs no `mut`
+    fn initialize(&mut self, target: &Expr) -> bool {
+        let key = match target {
+            Expr::Ident(name) => match self.symbols.variable(name) {
+                Some(symbol) => (name.clone(), symbol.span.start),
+                None => return false,
+            },
+            _ => return false,
+        };
+        self.loops == 0 && self.uninitialized.remove(&key)
+    }
+
+    /// the function or method `callee` names, with its parameters
+    fn callee(&self, callee: &Expr) -> Option<&'a Function> {
+        let is_named = |function: &Function, name: &str| {
+            function.name == FunctionName::Ident(name.to_string())
+        };
+        match callee {
+            Expr::Ident(name) if self.symbols.variable(name).is_none() => self
+                .callees
+                .functions
+                .iter()
+                .find(|f| f.owner.is_none() && is_named(f, name))
+                .copied(),
+            Expr::Member { base, field, .. } => {
+                let name = match **base {
+                    Expr::Ident(ref name) => self.symbols.variable(name)?.ty.type_name()?,
+                    _ => return None,
+                };
+                let record = self.callees.records.iter().find(|r| r.name == name)?;
+                record
+                    .methods
+                    .iter()
+                    .map(|method| &method.function)
+                    .find(|f| is_named(f, field))
+            }
+            _ => None,
+        }
+    }
+
+    /// declared type of the variable `expr` names, with the variable
+    fn variable(&self, expr: &Expr) -> Option<(Type, Key)> {
+        match expr {
+            Expr::Ident(name) => self
+                .symbols
+                .variable(name)
+                .map(|symbol| (symbol.ty.clone(), (name.clone(), symbol.span.start))),
+            _ => None,
+        }
+    }
+
+    /**
+     * write:
+     * records the variable `target` writes, `through` is set when the
+     * write goes through a pointer: *p = 1, p->x = 1, p[0] = 1
+     */
+    fn write(&mut self, target: &Expr, through: bool) {
+        match target {
+            Expr::Ident(name) => {
+                let symbol = match self.symbols.variable(name) {
+                    Some(symbol) => symbol,
+                    None => {
+                        self.writes.receiver = true;
+                        return;
+                    }
+                };
+                let key = (name.clone(), symbol.span.start);
+                if through || symbol.ty.reference {
+                    self.writes.pointees.insert(key);
+                } else {
+                    self.writes.bindings.insert(key);
+                }
+            }
+            Expr::This => self.writes.receiver = true,
+            Expr::Member { base, arrow, .. } => self.write(base, through || *arrow),
+            Expr::Index { base, .. } => {
+                let through = through || self.is_pointer(base);
+                self.write(base, through)
+            }
+            Expr::Unary {
+                op: UnaryOp::Deref,
+                operand,
+            } => self.write(operand, true),
+            // *(p + 1) = 0
+            Expr::Binary { lhs, .. } if through => self.write(lhs, true),
+            Expr::Cast { expr, .. } => self.write(expr, through),
+            _ => {}
+        }
+    }
+
+    /// an argument passed as pointer may be written by the function called
+    fn pass(&mut self, arg: &Expr) {
+        if let Expr::Ident(name) = arg {
+            if let Some(symbol) = self.symbols.variable(name) {
+                let ty = &symbol.ty;
+                let array = !ty.array.is_empty() && !ty.is_pointer();
+                if !ty.is_const && (ty.is_pointer() || ty.reference || array) {
+                    // an array is passed as a pointer to its elements
+                    self.write(arg, !array);
+                }
+            }
+        }
+    }
+
+    fn is_pointer(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Ident(name) => self
+                .symbols
+                .variable(name)
+                .is_some_and(|symbol| symbol.ty.is_pointer()),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Callees, Writes};
+    use crate::library::ast::*;
+    use crate::library::lexer::tokenizer::Tokenizer;
+    use crate::library::parser::parser::init_parser;
+    use crate::library::preprocessor::preprocessor::{preprocess, Config};
+
+    fn function(text: &str) -> Function {
+        let unit = init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap();
+        match unit.items.into_iter().next() {
+            Some(Item::Function(function)) => function,
+            item => panic!("expected function, found {:?}", item),
+        }
+    }
+
+    fn declarators(function: &Function) -> Vec<Declarator> {
+        let body = function.body.as_ref().unwrap();
+        body.stmts
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::Decl(decl) => Some(decl.declarators.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    #[test]
+    fn test_that_only_written_variables_are_found() {
+        let function = function(
+            "int f(int n, int *p, const int *q, int &r) {
+                int a = 0, b = 1, c[2], d = 0;
+                a += n; c[0] = b; *p = *q; r++;
+                scanf(\"%d\", &d);
+                return a;
+            }",
+        );
+        let writes = Writes::of(&function, Callees::default());
+        let written: Vec<String> = declarators(&function)
+            .into_iter()
+            .filter(|d| writes.is_written(&d.name, d.span))
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(written, vec!["a", "c", "d"]);
+        let span = function.span;
+        assert!(!writes.is_written("n", span));
+        assert!(!writes.is_written("p", span));
+        assert!(writes.is_pointee_written("p", span));
+        assert!(!writes.is_pointee_written("q", span));
+        assert!(writes.is_pointee_written("r", span));
+        assert!(!writes.receiver);
+    }
+
+    #[test]
+    fn test_that_shadowed_variables_are_told_apart() {
+        let function =
+            function("void f() { int i = 0; for (int i = 0; i < 3; i++) {} this->count = i; }");
+        let writes = Writes::of(&function, Callees::default());
+        let body = function.body.as_ref().unwrap();
+        let outer = match &body.stmts[0] {
+            Stmt::Decl(decl) => &decl.declarators[0],
+            stmt => panic!("expected declaration, found {:?}", stmt),
+        };
+        let inner = match &body.stmts[1] {
+            Stmt::For {
+                init: Some(init), ..
+            } => match &**init {
+                Stmt::Decl(decl) => &decl.declarators[0],
+                stmt => panic!("expected declaration, found {:?}", stmt),
+            },
+            stmt => panic!("expected for loop, found {:?}", stmt),
+        };
+        assert!(!writes.is_written("i", outer.span));
+        assert!(writes.is_written("i", inner.span));
+        assert!(writes.receiver);
+    }
+
+    #[test]
+    fn test_that_arguments_written_by_macros_are_found() {
+        let text = "#define SWAP(a, b) do { int t = a; a = b; b = t; } while (0)\n\
+                    void f() { int x = 1, y = 2, z = 3; SWAP(x, y); }";
+        let tokens = Tokenizer::new(text).tokenize().unwrap();
+        let tokens = preprocess(tokens, text, None, &Config::default()).unwrap();
+        let unit = init_parser(&tokens).unwrap();
+        let swap = match &unit.items[0] {
+            Item::Macro(found) => found,
+            item => panic!("expected macro, found {:?}", item),
+        };
+        let function = match &unit.items[1] {
+            Item::Function(function) => function,
+            item => panic!("expected function, found {:?}", item),
+        };
+        let callees = Callees {
+            macros: &[swap],
+            ..Callees::default()
+        };
+        let writes = Writes::of(function, callees);
+        let written: Vec<String> = declarators(function)
+            .into_iter()
+            .filter(|d| writes.is_written(&d.name, d.span))
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(written, vec!["x", "y"]);
+    }
+
+    #[test]
+    fn test_that_reads_through_const_are_not_writes() {
+        let text =
+            "struct P { int x; int get() const { return x; } void set(int v) { x = v; } };\n\
+                    int read(const int *p) { return *p; }\n\
+                    void f() { int b = 1; read(&b); struct P p, s; p.get(); s.set(1); }";
+        let unit = init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap();
+        let (record, read, function) = match unit.items.as_slice() {
+            [Item::Record(record), Item::Function(read), Item::Function(function)] => {
+                (record, read, function)
+            }
+            items => panic!("expected a record and two functions, found {:?}", items),
+        };
+        let callees = Callees {
+            functions: &[read],
+            records: &[record],
+            macros: &[],
+        };
+        let writes = Writes::of(function, callees);
+        let written: Vec<String> = declarators(function)
+            .into_iter()
+            .filter(|d| writes.is_written(&d.name, d.span))
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(written, vec!["s"]);
+    }
+
+    #[test]
+    fn test_that_arguments_and_borrows_are_written_through_what_takes_them() {
+        let text = "void set(int &r) { r = 1; }\n\
+                    void f() { int c = 0, x = 1, y = 2; set(c); int *q = &x; int *p = &y; *p = *q; }";
+        let unit = init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap();
+        let (set, function) = match unit.items.as_slice() {
+            [Item::Function(set), Item::Function(function)] => (set, function),
+            items => panic!("expected two functions, found {:?}", items),
+        };
+        let callees = Callees {
+            functions: &[set],
+            ..Callees::default()
+        };
+        let writes = Writes::of(function, callees);
+        let written: Vec<String> = declarators(function)
+            .into_iter()
+            .filter(|d| writes.is_written(&d.name, d.span))
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(written, vec!["c", "y"]);
+    }
+
+    #[test]
+    fn test_that_a_value_given_once_after_the_declaration_is_not_a_write() {
+        let function = function(
+            "void f(int n) { int c; c = 5; int d; d = 1; d = 2; int e; while (n--) e = n; }",
+        );
+        let writes = Writes::of(&function, Callees::default());
+        let written: Vec<String> = declarators(&function)
+            .into_iter()
+            .filter(|d| writes.is_written(&d.name, d.span))
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(written, vec!["d", "e"]);
+    }
+}
@@ -0,0 +1,633 @@
+//! Layout of the translated source, the emitter joins its fragments with
+//! single spaces and the printer breaks them into indented lines
+
+const INDENT: &str = "    ";
+
+/// operators of more than one character, longest first
+const OPERATORS: [&str; 25] = [
+    "<<=", ">>=", "..=", "...", "::", "->", "=>", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=",
+    "*=", "/=", "%=", "^=", "&=", "|=", "<<", ">>", "..", "#!",
+];
+
+/// keywords an expression follows, `return -n`, `if !done`
+const EXPRESSION_KEYWORDS: [&str; 10] = [
+    "return", "in", "if", "while", "match", "else", "break", "let", "mut", "=>",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Word,
+    Literal,
+    Lifetime,
+    Punct,
+    LineComment,
+    BlockComment,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: Kind,
+    text: &'a str,
+    // a line break separates the token from the one before it
+    newline: bool,
+}
+
+impl Token<'_> {
+    fn is(&self, text: &str) -> bool {
+        self.kind == Kind::Punct && self.text == text
+    }
+
+    fn is_keyword(&self) -> bool {
+        self.kind == Kind::Word && EXPRESSION_KEYWORDS.contains(&self.text)
+    }
+
+    /// an identifier or a literal, which a call or an index follows
+    fn is_operand(&self) -> bool {
+        matches!(self.kind, Kind::Word | Kind::Literal) && !self.is_keyword()
+    }
+}
+
+fn tokens(source: &str) -> Vec<Token<'_>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut at = 0;
+    let mut newline = false;
+    while at < bytes.len() {
+        let c = bytes[at];
+        let start = at;
+        let kind = if c.is_ascii_whitespace() {
+            newline |= c == b'\n';
+            at += 1;
+            continue;
+        } else if source[at..].starts_with("//") {
+            at = source[at..].find('\n').map_or(bytes.len(), |end| at + end);
+            Kind::LineComment
+        } else if source[at..].starts_with("/*") {
+            at = source[at + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |end| at + 2 + end + 2);
+            Kind::BlockComment
+        } else if c == b'"' {
+            at = string_end(bytes, at);
+            Kind::Literal
+        } else if c == b'\'' {
+            // a character, or a lifetime or label when not closed
+            match char_end(source, at) {
+                Some(end) => {
+                    at = end;
+                    Kind::Literal
+                }
+                None => {
+                    at = word_end(bytes, at + 1);
+                    Kind::Lifetime
+                }
+            }
+        } else if c.is_ascii_digit() {
+            at = number_end(bytes, at);
+            Kind::Literal
+        } else if c.is_ascii_alphabetic() || c == b'_' || !c.is_ascii() {
+            at = word_end(bytes, at);
+            let prefix = &source[start..at];
+            match bytes.get(at) {
+                Some(b'"') | Some(b'#') if matches!(prefix, "r" | "br") => {
+                    at = raw_string_end(source, at);
+                    Kind::Literal
+                }
+                Some(b'"') if prefix == "b" => {
+                    at = string_end(bytes, at);
+                    Kind::Literal
+                }
+                Some(b'\'') if prefix == "b" => {
+                    at = char_end(source, at).unwrap_or(at + 1);
+                    Kind::Literal
+                }
+                _ => Kind::Word,
+            }
+        } else {
+            let operator = OPERATORS
+                .iter()
+                .find(|operator| source[at..].starts_with(*operator));
+            at += operator.map_or_else(
+                || source[at..].chars().next().map_or(1, char::len_utf8),
+                |operator| operator.len(),
+            );
+            Kind::Punct
+        };
+        tokens.push(Token {
+            kind,
+            text: &source[start..at],
+            newline,
+        });
+        newline = false;
+    }
+    tokens
+}
+
+fn word_end(bytes: &[u8], mut at: usize) -> usize {
+    while at < bytes.len()
+        && (bytes[at].is_ascii_alphanumeric() || bytes[at] == b'_' || !bytes[at].is_ascii())
+    {
+        at += 1;
+    }
+    at
+}
+
+/// end of a number, `1.5e-3f64`, `0x1F_u64`, the dots of `0..n` excluded
+fn number_end(bytes: &[u8], start: usize) -> usize {
+    let hex = bytes[start..].starts_with(b"0x");
+    let mut at = start;
+    while at < bytes.len() {
+        let c = bytes[at];
+        let exponent =
+            at > start && !hex && matches!(bytes[at - 1], b'e' | b'E') && matches!(c, b'+' | b'-');
+        let fraction = c == b'.' && bytes.get(at + 1).is_some_and(u8::is_ascii_digit);
+        if c.is_ascii_alphanumeric() || c == b'_' || exponent || fraction {
+            at += 1;
+        } else {
+            break;
+        }
+    }
+    at
+}
+
+fn string_end(bytes: &[u8], mut at: usize) -> usize {
+    at += 1;
+    while at < bytes.len() {
+        match bytes[at] {
+            b'\\' => at += 2,
+            b'"' => return at + 1,
+            _ => at += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn raw_string_end(source: &str, at: usize) -> usize {
+    let hashes = source[at..].bytes().take_while(|c| *c == b'#').count();
+    let close = format!("\"{}", "#".repeat(hashes));
+    let body = at + hashes + 1;
+    source
+        .get(body..)
+        .and_then(|rest| rest.find(&close))
+        .map_or(source.len(), |end| body + end + close.len())
+}
+
+/// end of the character literal at `at`, none for a lifetime
+fn char_end(source: &str, at: usize) -> Option<usize> {
+    let mut chars = source[at + 1..].char_indices();
+    match chars.next()? {
+        // the escaped character may be a quote, '\''
+        (_, '\\') => source
+            .get(at + 3..)
+            .and_then(|rest| rest.find('\''))
+            .map(|end| at + 3 + end + 1),
+        _ => match chars.next() {
+            Some((end, '\'')) => Some(at + 1 + end + 1),
+            _ => None,
+        },
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delimiter {
+    // braces laid out over lines
+    Block,
+    // braces of a block which is the operand of an expression, laid out
+    // over lines but carried on after, `{ n -= 1; n + 1 } != 0`
+    Operand,
+    // braces kept on one line, `use std::{a, b}`
+    Group,
+    Paren,
+    Bracket,
+    // brackets of an attribute, `#[inline]`
+    Attribute,
+}
+
+struct Printer {
+    out: String,
+    delimiters: Vec<Delimiter>,
+    // open generic brackets, `Vec<Vec<i32>>`
+    generics: usize,
+    // the `<` or `>` printed last was a generic bracket
+    generic_bracket: bool,
+    // the operator printed last was unary, `-n`, `&self`
+    unary: bool,
+    // the brace printed last closed a block which is an operand
+    operand_closed: bool,
+}
+
+impl Printer {
+    fn indent(&self) -> usize {
+        self.delimiters
+            .iter()
+            .filter(|delimiter| matches!(delimiter, Delimiter::Block | Delimiter::Operand))
+            .count()
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    fn newline(&mut self) {
+        if !self.at_line_start() {
+            let len = self.out.trim_end_matches(' ').len();
+            self.out.truncate(len);
+            self.out.push('\n');
+        }
+    }
+
+    fn blank_line(&mut self) {
+        self.newline();
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn write(&mut self, text: &str, space: bool) {
+        if self.at_line_start() {
+            self.out.push_str(&INDENT.repeat(self.indent()));
+        } else if space {
+            self.out.push(' ');
+        }
+        self.out.push_str(text);
+    }
+
+    /// a comment on the line of the code before it
+    fn write_trailing(&mut self, text: &str) {
+        let blank = self.out.ends_with("\n\n");
+        let len = self.out.trim_end_matches('\n').len();
+        self.out.truncate(len);
+        self.out.push(' ');
+        self.out.push_str(text);
+        if blank {
+            self.blank_line();
+        } else {
+            self.newline();
+        }
+    }
+
+    /// a block comment, its lines indented as the code around it
+    fn write_comment(&mut self, text: &str) {
+        self.newline();
+        let indent = INDENT.repeat(self.indent());
+        for (index, line) in text.lines().enumerate() {
+            if index == 0 {
+                self.out.push_str(&indent);
+                self.out.push_str(line);
+            } else {
+                let line = line.trim_start();
+                self.out.push('\n');
+                self.out.push_str(&indent);
+                if line.starts_with('*') {
+                    self.out.push(' ');
+                }
+                self.out.push_str(line);
+            }
+        }
+        self.out.push('\n');
+    }
+
+    /// the `<` after `previous` opens generic arguments, `Vec<`, `::<`
+    fn opens_generics(previous: Option<&Token>, before: Option<&Token>) -> bool {
+        match previous {
+            Some(token) if token.is("::") => true,
+            Some(token) if token.kind == Kind::Word => {
+                let upper = token.text.starts_with(|c: char| c.is_ascii_uppercase());
+                // MAX < n is a comparison
+                let constant =
+                    token.text.len() > 1 && token.text.chars().all(|c| !c.is_ascii_lowercase());
+                (upper && !constant)
+                    || token.text == "impl"
+                    || before.is_some_and(|before| {
+                        matches!(
+                            before.text,
+                            "fn" | "struct" | "enum" | "union" | "type" | "trait"
+                        )
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    /// `token` follows `previous` without a space
+    fn joined(&self, previous: Option<&Token>, token: &Token, next: Option<&Token>) -> bool {
+        let previous = match previous {
+            Some(previous) => previous,
+            None => return true,
+        };
+        if previous.kind == Kind::Punct {
+            if matches!(
+                previous.text,
+                "(" | "[" | "." | "::" | "#" | "#!" | "$" | ".." | "..="
+            ) || (self.generic_bracket && previous.text == "<")
+                || self.unary
+            {
+                return true;
+            }
+            // the `!` of a macro call
+            if previous.text == "!" && token.kind == Kind::Punct && matches!(token.text, "(" | "[")
+            {
+                return true;
+            }
+        }
+        if token.kind != Kind::Punct {
+            return false;
+        }
+        match token.text {
+            "," | ";" | "." | "?" | ")" | "]" | "::" | ":" | ".." | "..=" => true,
+            "(" | "[" => {
+                previous.is_operand()
+                    || previous.is(")")
+                    || previous.is("]")
+                    || previous.is("!")
+                    || (previous.is(">") && self.generic_bracket)
+            }
+            "!" => {
+                previous.text == "macro_rules"
+                    || (previous.is_operand()
+                        && next.is_some_and(|next| next.is("(") || next.is("[") || next.is("{")))
+            }
+            ">" => self.generics > 0,
+            "<" => false,
+            _ => false,
+        }
+    }
+
+    /// the `{` after `previous` opens a block which is an operand, `x = {`
+    fn opens_operand(previous: Option<&Token>) -> bool {
+        match previous {
+            Some(token) if token.kind == Kind::Punct => !matches!(
+                token.text,
+                ")" | "]" | "}" | ";" | "{" | "=>" | "->" | ">" | "?" | "#" | "#!"
+            ),
+            Some(token) => {
+                token.kind == Kind::Word
+                    && matches!(
+                        token.text,
+                        "while" | "if" | "return" | "match" | "in" | "break"
+                    )
+            }
+            None => false,
+        }
+    }
+
+    /// `token` at `previous` is a unary operator, `-n`, `*p`, `&mut x`
+    fn is_unary(&self, previous: Option<&Token>, token: &Token) -> bool {
+        if !(token.is("-") || token.is("*") || token.is("&") || token.is("!") || token.is("&&")) {
+            return false;
+        }
+        match previous {
+            None => true,
+            Some(previous) if previous.kind == Kind::Punct => {
+                // a statement starts after a block, unless it is an operand
+                let operand = matches!(previous.text, ")" | "]" | "?")
+                    || (previous.is("}") && self.operand_closed);
+                !operand
+            }
+            Some(previous) => previous.is_keyword(),
+        }
+    }
+}
+
+/**
+ * pretty:
+ * lays the translated `source` out, a statement or declaration to a line,
+ * indented by the blocks holding it. Comments are kept as they are,
+ * block comments indented
+ */
+pub fn pretty(source: &str) -> String {
+    let tokens = tokens(source);
+    let mut printer = Printer {
+        out: String::new(),
+        delimiters: Vec::new(),
+        generics: 0,
+        generic_bracket: false,
+        unary: false,
+        operand_closed: false,
+    };
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = tokens[index];
+        let previous = index.checked_sub(1).map(|at| &tokens[at]);
+        let before = index.checked_sub(2).map(|at| &tokens[at]);
+        let next = tokens.get(index + 1);
+        index += 1;
+        // a comment trailing the code stays on its line
+        let trailing = !token.newline && previous.is_some();
+        match token.kind {
+            Kind::LineComment if trailing && printer.at_line_start() => {
+                printer.write_trailing(token.text);
+                continue;
+            }
+            Kind::LineComment => {
+                printer.write(token.text, true);
+                printer.newline();
+                continue;
+            }
+            Kind::BlockComment if trailing && !token.text.contains('\n') => {
+                if printer.at_line_start() {
+                    printer.write_trailing(token.text);
+                } else {
+                    printer.write(token.text, true);
+                }
+                continue;
+            }
+            Kind::BlockComment => {
+                printer.write_comment(token.text);
+                // the banner stands apart from the code
+                if previous.is_none() {
+                    printer.blank_line();
+                }
+                continue;
+            }
+            _ => {}
+        }
+        let joined = printer.joined(previous, &token, next);
+        let unary = printer.is_unary(previous, &token);
+        printer.operand_closed = false;
+        match token.text {
+            "{" if token.kind == Kind::Punct => {
+                if next.is_some_and(|next| next.is("}")) {
+                    // an empty block stays on the line
+                    printer.write("{}", !joined);
+                    index += 1;
+                    close_line(&mut printer, tokens.get(index));
+                } else if previous.is_some_and(|previous| previous.is("::")) {
+                    printer.write("{", false);
+                    printer.delimiters.push(Delimiter::Group);
+                } else {
+                    printer.write("{", !joined);
+                    printer
+                        .delimiters
+                        .push(if Printer::opens_operand(previous) {
+                            Delimiter::Operand
+                        } else {
+                            Delimiter::Block
+                        });
+                    printer.newline();
+                }
+            }
+            "}" if token.kind == Kind::Punct => match printer.delimiters.pop() {
+                Some(Delimiter::Group) => printer.write("}", false),
+                // the expression goes on after the block
+                Some(Delimiter::Operand) => {
+                    printer.newline();
+                    printer.write("}", false);
+                    printer.unary = false;
+                    printer.generic_bracket = false;
+                    printer.operand_closed = true;
+                    continue;
+                }
+                _ => {
+                    printer.newline();
+                    printer.write("}", false);
+                    close_line(&mut printer, next);
+                }
+            },
+            "(" | "[" if token.kind == Kind::Punct => {
+                printer.write(token.text, !joined);
+                let attribute =
+                    previous.is_some_and(|previous| previous.is("#") || previous.is("#!"));
+                printer.delimiters.push(match token.text {
+                    "(" => Delimiter::Paren,
+                    _ if attribute => Delimiter::Attribute,
+                    _ => Delimiter::Bracket,
+                });
+            }
+            ")" | "]" if token.kind == Kind::Punct => {
+                printer.write(token.text, !joined);
+                if printer.delimiters.pop() == Some(Delimiter::Attribute) && printer.in_block() {
+                    printer.newline();
+                }
+            }
+            "<" if token.kind == Kind::Punct && Printer::opens_generics(previous, before) => {
+                printer.write("<", false);
+                printer.generics += 1;
+                printer.generic_bracket = true;
+                printer.unary = false;
+                continue;
+            }
+            ">" | ">>" if token.kind == Kind::Punct && printer.generics > 0 => {
+                for _ in 0..token.text.len() {
+                    printer.write(">", false);
+                    printer.generics = printer.generics.saturating_sub(1);
+                }
+                printer.generic_bracket = true;
+                printer.unary = false;
+                continue;
+            }
+            ";" | "," if token.kind == Kind::Punct => {
+                printer.write(token.text, false);
+                if printer.in_block() {
+                    printer.newline();
+                }
+            }
+            _ => {
+                let joined = joined
+                    || (token.kind != Kind::Punct
+                        && previous.is_some_and(|previous| previous.is(":"))
+                        && before.is_some_and(|before| before.kind == Kind::Word)
+                        && index >= 4
+                        && tokens[index - 4].is("$"));
+                printer.write(token.text, !joined);
+            }
+        }
+        printer.generic_bracket = false;
+        printer.unary = unary;
+    }
+    printer.newline();
+    printer.out
+}
+
+impl Printer {
+    /// the innermost delimiter is a block, or there is none
+    fn in_block(&self) -> bool {
+        matches!(
+            self.delimiters.last(),
+            None | Some(Delimiter::Block) | Some(Delimiter::Operand)
+        )
+    }
+}
+
+/// the line after a closing brace ends, unless `next` carries on the
+/// expression, `} else {`, `};`, `})`
+fn close_line(printer: &mut Printer, next: Option<&Token>) {
+    let carries_on = next.is_some_and(|next| {
+        (next.kind == Kind::Punct && matches!(next.text, "," | ";" | ")" | "]" | "." | "?" | "{"))
+            || (next.kind == Kind::Word && matches!(next.text, "else" | "as"))
+    });
+    if carries_on || !printer.in_block() {
+        return;
+    }
+    // items stand apart
+    if printer.delimiters.is_empty() && next.is_some() {
+        printer.blank_line();
+    } else {
+        printer.newline();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::pretty;
+    use crate::library::doc::CRUST_BANNER;
+
+    #[test]
+    fn test_that_statements_are_laid_out_on_indented_lines() {
+        let source = "fn f ( p: &mut i32, n: i32 ) -> i32 { let mut i : i32 = 0 ; \
+                      while i < MAX { if i > 2 && !n { continue; } else { n += i << 2; } } \
+                      match n { 1 => { return -n ; } _ => { } } *p = -i ; x * x }";
+        assert_eq!(
+            pretty(source),
+            "fn f(p: &mut i32, n: i32) -> i32 {\n    let mut i: i32 = 0;\n    \
+             while i < MAX {\n        if i > 2 && !n {\n            continue;\n        } else {\n            \
+             n += i << 2;\n        }\n    }\n    match n {\n        1 => {\n            return -n;\n        }\n        \
+             _ => {}\n    }\n    *p = -i;\n    x * x\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_that_blocks_in_expressions_carry_the_expression_on() {
+        let source = "fn f ( ) { a = { n += 1 ; n } * { m -= 1 ; m + 1 } ; \
+                      while { n -= 1 ; n + 1 } != 0 { a += 1 ; } *p = a ; }";
+        assert_eq!(
+            pretty(source),
+            "fn f() {\n    a = {\n        n += 1;\n        n\n    } * {\n        m -= 1;\n        \
+             m + 1\n    };\n    while {\n        n -= 1;\n        n + 1\n    } != 0 {\n        \
+             a += 1;\n    }\n    *p = a;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_that_generics_macros_and_attributes_are_joined() {
+        let source = "#[inline] pub fn g ( ) -> Vec<Vec<i32>> { let v : Vec<i32> = vec![1, 2]; \
+                      println!(\"{} \\\" {{\", std::mem::size_of::<i32>()); v.len() as i32 }";
+        assert_eq!(
+            pretty(source),
+            "#[inline]\npub fn g() -> Vec<Vec<i32>> {\n    let v: Vec<i32> = vec![1, 2];\n    \
+             println!(\"{} \\\" {{\", std::mem::size_of::<i32>());\n    v.len() as i32\n}\n"
+        );
+        assert_eq!(
+            pretty("macro_rules! SWAP { ($a:expr, $b:expr) => { { let t = $a; } }; }"),
+            "macro_rules! SWAP {\n    ($a:expr, $b:expr) => {\n        {\n            let t = $a;\n        }\n    };\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_that_comments_and_items_are_kept_apart() {
+        let source = format!(
+            "{} struct A {{ a: i32, // the a\n }} \n\n/**doc\n * of the impl\n **/\n impl A {{\n\
+             // FIXME(CR0007): constructor\n // A ( ) {{ }}\n fn get ( &self ) -> char {{ '{{' }} }}",
+            CRUST_BANNER
+        );
+        assert_eq!(
+            pretty(&source),
+            format!(
+                "{}\nstruct A {{\n    a: i32, // the a\n}}\n\n/**doc\n * of the impl\n **/\nimpl A {{\n    \
+                 // FIXME(CR0007): constructor\n    // A ( ) {{ }}\n    fn get(&self) -> char {{\n        \
+                 '{{'\n    }}\n}}\n",
+                CRUST_BANNER
+            )
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use crate::library::diagnostics::Diagnostic;
+
+/// string literal with the characters json requires to be escaped
+pub fn quote(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/**
+ * to_json:
+ * the diagnostic as a single line json object
+ * {"code":"CR0012","severity":"warning","file":"main.cpp","line":4,"column":5,
+ *  "end_line":4,"end_column":15,"message":"..","suggestion":".."}
+ * suggestion is null when there is nothing to suggest
+ */
+pub fn to_json(diagnostic: &Diagnostic, file: &str) -> String {
+    let span = diagnostic.span;
+    let suggestion = match diagnostic.help {
+        Some(ref help) => quote(help),
+        None => "null".to_string(),
+    };
+    format!(
+        "{{\"code\":{},\"name\":{},\"severity\":{},\"file\":{},\"line\":{},\"column\":{},\
+         \"end_line\":{},\"end_column\":{},\"message\":{},\"suggestion\":{}}}",
+        quote(diagnostic.code()),
+        quote(diagnostic.doc.name()),
+        quote(diagnostic.severity.as_str()),
+        quote(file),
+        span.start.line,
+        span.start.column,
+        span.end.line,
+        span.end.column,
+        quote(&diagnostic.message),
+        suggestion
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{quote, to_json};
+    use crate::library::diagnostics::Diagnostic;
+    use crate::library::doc::DocType;
+    use crate::library::lexeme::span::{Position, Span};
+
+    #[test]
+    fn test_that_strings_are_escaped() {
+        assert_eq!(quote("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn test_that_diagnostic_is_one_json_object() {
+        let start = Position {
+            offset: 10,
+            line: 2,
+            column: 5,
+        };
+        let end = Position {
+            offset: 15,
+            line: 2,
+            column: 10,
+        };
+        let mut diagnostic = Diagnostic::new(DocType::USING_STMT, Span::new(start, end));
+        diagnostic.help = None;
+        assert_eq!(
+            to_json(&diagnostic, "src\\main.cpp"),
+            "{\"code\":\"CR0011\",\"name\":\"using-not-translated\",\"severity\":\"warning\",\
+             \"file\":\"src\\\\main.cpp\",\"line\":2,\"column\":5,\"end_line\":2,\"end_column\":10,\
+             \"message\":\"using declaration is not translated\",\"suggestion\":null}"
+        );
+    }
+}
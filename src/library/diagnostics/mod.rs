@@ -0,0 +1,125 @@
+#![allow(dead_code)]
+
+//! Messages about the translation, reported next to the source they are about
+
+pub mod json;
+pub mod render;
+pub mod sarif;
+
+use std::fmt;
+
+use crate::library::doc::DocType;
+use crate::library::lexeme::span::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Note,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub doc: DocType,
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    /// how to finish the translation by hand
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    /// diagnostic with the severity, title and help of the catalogue entry
+    pub fn new(doc: DocType, span: Span) -> Diagnostic {
+        Diagnostic {
+            doc,
+            severity: doc.severity(),
+            message: doc.title().to_string(),
+            span,
+            help: Some(doc.help().to_string()),
+        }
+    }
+
+    pub fn with_message(mut self, message: String) -> Diagnostic {
+        self.message = message;
+        self
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.doc.code()
+    }
+}
+
+/// diagnostics of one translation, in the order they were reported
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    list: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Diagnostics {
+        Diagnostics::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.list.push(diagnostic);
+    }
+
+    pub fn report(&mut self, doc: DocType, span: Span) {
+        self.push(Diagnostic::new(doc, span));
+    }
+
+    pub fn extend(&mut self, other: Diagnostics) {
+        self.list.extend(other.list);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.list.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.list.iter().filter(|d| d.severity == severity).count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// sorted by location, keeping the report order for the same location
+    pub fn sort(&mut self) {
+        self.list.sort_by_key(|d| d.span.start);
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.iter()
+    }
+}
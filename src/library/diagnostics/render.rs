@@ -0,0 +1,218 @@
+use crate::library::diagnostics::{Diagnostic, Diagnostics, Severity};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BLUE: &str = "\x1b[1;34m";
+
+/**
+ * Renderer:
+ * formats diagnostics for the terminal, the line of source the
+ * diagnostic is about is shown with carets under the span
+ *
+ * warning[CR0012]: stream operators `<<` and `>>` are not translated
+ *  --> main.cpp:4:5
+ *   |
+ * 4 |     cout << x;
+ *   |     ^^^^^^^^^^
+ *   = help: rewrite the statement with `print!`, `println!` or `std::io`
+ */
+pub struct Renderer<'a> {
+    file: &'a str,
+    source: &'a str,
+    color: bool,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(file: &'a str, source: &'a str) -> Renderer<'a> {
+        Renderer {
+            file,
+            source,
+            color: false,
+        }
+    }
+
+    /// use ansi escape codes, only when writing to a terminal
+    pub fn with_color(mut self, color: bool) -> Renderer<'a> {
+        self.color = color;
+        self
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn severity_style(severity: Severity) -> &'static str {
+        match severity {
+            Severity::Error => "\x1b[1;31m",
+            Severity::Warning => "\x1b[1;33m",
+            Severity::Note => "\x1b[1;36m",
+        }
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let style = Renderer::severity_style(diagnostic.severity);
+        let mut out = format!(
+            "{}{}\n",
+            self.paint(
+                style,
+                &format!("{}[{}]", diagnostic.severity, diagnostic.code())
+            ),
+            self.paint(BOLD, &format!(": {}", diagnostic.message)),
+        );
+
+        let start = diagnostic.span.start;
+        // spans of generated constructs have no line
+        let line = match start.line {
+            0 => None,
+            n => self.source.lines().nth(n as usize - 1),
+        };
+        let line = match line {
+            Some(line) => line,
+            None => {
+                out.push_str(&format!(" {} {}\n", self.paint(BLUE, "-->"), self.file));
+                return out + &self.render_help(diagnostic, 0);
+            }
+        };
+
+        let number = start.line.to_string();
+        let gutter = " ".repeat(number.len());
+        out.push_str(&format!(
+            "{}{} {}:{}:{}\n",
+            gutter,
+            self.paint(BLUE, "-->"),
+            self.file,
+            start.line,
+            start.column
+        ));
+        out.push_str(&format!("{} {}\n", gutter, self.paint(BLUE, "|")));
+        out.push_str(&format!(
+            "{} {} {}\n",
+            self.paint(BLUE, &number),
+            self.paint(BLUE, "|"),
+            line
+        ));
+
+        // keep tabs in the padding so the carets line up with the source
+        let skip = start.column.saturating_sub(1) as usize;
+        let padding: String = line
+            .chars()
+            .take(skip)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let line_len = line.chars().count();
+        let end = if diagnostic.span.end.line == start.line {
+            (diagnostic.span.end.column.saturating_sub(1) as usize).min(line_len)
+        } else {
+            line_len
+        };
+        let carets = "^".repeat(end.saturating_sub(skip).max(1));
+        out.push_str(&format!(
+            "{} {} {}{}\n",
+            gutter,
+            self.paint(BLUE, "|"),
+            padding,
+            self.paint(style, &carets)
+        ));
+        out + &self.render_help(diagnostic, number.len())
+    }
+
+    fn render_help(&self, diagnostic: &Diagnostic, width: usize) -> String {
+        match diagnostic.help {
+            Some(ref help) => format!(
+                "{} {} {}: {}\n",
+                " ".repeat(width),
+                self.paint(BLUE, "="),
+                self.paint(BOLD, "help"),
+                help
+            ),
+            None => String::new(),
+        }
+    }
+
+    /// every diagnostic followed by an empty line, then a summary
+    pub fn render_all(&self, diagnostics: &Diagnostics) -> String {
+        let mut out = String::new();
+        for diagnostic in diagnostics {
+            out.push_str(&self.render(diagnostic));
+            out.push('\n');
+        }
+        if let Some(summary) = summary(diagnostics) {
+            out.push_str(&summary);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// `2 warnings, 1 note emitted`
+pub fn summary(diagnostics: &Diagnostics) -> Option<String> {
+    let counts: Vec<String> = [Severity::Error, Severity::Warning, Severity::Note]
+        .iter()
+        .filter_map(|&severity| match diagnostics.count(severity) {
+            0 => None,
+            1 => Some(format!("1 {}", severity)),
+            n => Some(format!("{} {}s", n, severity)),
+        })
+        .collect();
+    if counts.is_empty() {
+        None
+    } else {
+        Some(format!("{} emitted", counts.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{summary, Renderer};
+    use crate::library::diagnostics::{Diagnostic, Diagnostics};
+    use crate::library::doc::DocType;
+    use crate::library::lexeme::span::{Position, Span};
+
+    fn span(line: u32, start: u32, end: u32) -> Span {
+        let at = |column| Position {
+            offset: 0,
+            line,
+            column,
+        };
+        Span::new(at(start), at(end))
+    }
+
+    #[test]
+    fn test_that_snippet_is_rendered_with_carets() {
+        let source = "int main() {\n\tcout << x;\n}\n";
+        let diagnostic = Diagnostic::new(DocType::STREAM_OP, span(2, 2, 12));
+        let text = Renderer::new("main.cpp", source).render(&diagnostic);
+        let expected = "warning[CR0012]: stream operators `<<` and `>>` are not translated\n \
+                        --> main.cpp:2:2\n  \
+                        |\n\
+                        2 | \tcout << x;\n  \
+                        | \t^^^^^^^^^^\n  \
+                        = help: rewrite the statement with `print!`, `println!` or `std::io`\n";
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn test_that_diagnostic_without_location_names_the_file() {
+        let diagnostic = Diagnostic::new(DocType::NO_RETURN, Span::default());
+        let text = Renderer::new("main.cpp", "").render(&diagnostic);
+        assert!(text.starts_with("note[CR0004]: "));
+        assert!(text.contains(" --> main.cpp\n"));
+    }
+
+    #[test]
+    fn test_that_summary_counts_severities() {
+        let mut diagnostics = Diagnostics::new();
+        assert_eq!(summary(&diagnostics), None);
+        diagnostics.report(DocType::STREAM_OP, Span::default());
+        diagnostics.report(DocType::USING_STMT, Span::default());
+        diagnostics.report(DocType::NO_STRICT, Span::default());
+        assert_eq!(
+            summary(&diagnostics),
+            Some("2 warnings, 1 note emitted".to_string())
+        );
+    }
+}
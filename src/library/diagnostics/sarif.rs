@@ -0,0 +1,130 @@
+use crate::library::diagnostics::json::quote;
+use crate::library::diagnostics::{Diagnostic, Diagnostics};
+use crate::library::doc::DocType;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/**
+ * to_sarif:
+ * SARIF 2.1.0 log of the diagnostics of every translated file,
+ * one run with a rule for each entry of the DocType catalogue
+ */
+pub fn to_sarif(files: &[(String, Diagnostics)]) -> String {
+    let rules: Vec<String> = DocType::ALL.iter().map(|&doc| rule(doc)).collect();
+    let results: Vec<String> = files
+        .iter()
+        .flat_map(|(file, diagnostics)| diagnostics.iter().map(move |d| result(d, file)))
+        .collect();
+    format!(
+        "{{\n  \"$schema\": {},\n  \"version\": \"2.1.0\",\n  \"runs\": [\n    {{\n      \
+         \"tool\": {{\n        \"driver\": {{\n          \"name\": \"crust\",\n          \
+         \"version\": {},\n          \"rules\": [\n            {}\n          ]\n        }}\n      }},\n      \
+         \"columnKind\": \"unicodeCodePoints\",\n      \"results\": [\n        {}\n      ]\n    }}\n  ]\n}}\n",
+        quote(SCHEMA),
+        quote(env!("CARGO_PKG_VERSION")),
+        rules.join(",\n            "),
+        results.join(",\n        ")
+    )
+}
+
+/// the SARIF levels are named as the severities
+fn rule(doc: DocType) -> String {
+    format!(
+        "{{\"id\": {}, \"name\": {}, \"shortDescription\": {{\"text\": {}}}, \
+         \"help\": {{\"text\": {}}}, \"defaultConfiguration\": {{\"level\": {}}}}}",
+        quote(doc.code()),
+        quote(doc.name()),
+        quote(doc.title()),
+        quote(doc.help()),
+        quote(doc.severity().as_str())
+    )
+}
+
+fn result(diagnostic: &Diagnostic, file: &str) -> String {
+    let span = diagnostic.span;
+    // diagnostics without a location only name the file
+    let region = if span.start.line == 0 {
+        String::new()
+    } else {
+        format!(
+            ", \"region\": {{\"startLine\": {}, \"startColumn\": {}, \"endLine\": {}, \"endColumn\": {}}}",
+            span.start.line, span.start.column, span.end.line, span.end.column
+        )
+    };
+    let rule_index = DocType::ALL
+        .iter()
+        .position(|&doc| doc == diagnostic.doc)
+        .unwrap_or(0);
+    format!(
+        "{{\"ruleId\": {}, \"ruleIndex\": {}, \"level\": {}, \"message\": {{\"text\": {}}}, \
+         \"locations\": [{{\"physicalLocation\": {{\"artifactLocation\": {{\"uri\": {}}}{}}}}}]}}",
+        quote(diagnostic.code()),
+        rule_index,
+        quote(diagnostic.severity.as_str()),
+        quote(&diagnostic.message),
+        quote(&to_uri(file)),
+        region
+    )
+}
+
+/// uri of the file path, absolute paths get the file scheme
+fn to_uri(file: &str) -> String {
+    let mut uri = if file.starts_with('/') {
+        "file://".to_string()
+    } else {
+        String::new()
+    };
+    for c in file.chars() {
+        match c {
+            '\\' => uri.push('/'),
+            ' ' => uri.push_str("%20"),
+            '%' => uri.push_str("%25"),
+            '#' => uri.push_str("%23"),
+            '?' => uri.push_str("%3F"),
+            c => uri.push(c),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod test {
+    use super::{to_sarif, to_uri};
+    use crate::library::diagnostics::Diagnostics;
+    use crate::library::doc::DocType;
+    use crate::library::lexeme::span::{Position, Span};
+
+    #[test]
+    fn test_that_paths_are_uri_references() {
+        assert_eq!(to_uri("src\\my file#1.cpp"), "src/my%20file%231.cpp");
+        assert_eq!(to_uri("/tmp/main.cpp"), "file:///tmp/main.cpp");
+    }
+
+    #[test]
+    fn test_that_results_point_at_rules_and_regions() {
+        let start = Position {
+            offset: 0,
+            line: 3,
+            column: 5,
+        };
+        let end = Position {
+            offset: 9,
+            line: 3,
+            column: 14,
+        };
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.report(DocType::STREAM_OP, Span::new(start, end));
+        let log = to_sarif(&[("main.cpp".to_string(), diagnostics)]);
+
+        assert!(log.contains("\"version\": \"2.1.0\""));
+        for doc in DocType::ALL.iter() {
+            assert!(log.contains(&format!("{{\"id\": \"{}\"", doc.code())));
+        }
+        assert!(log.contains(
+            "{\"ruleId\": \"CR0012\", \"ruleIndex\": 11, \"level\": \"warning\", \
+             \"message\": {\"text\": \"stream operators `<<` and `>>` are not translated\"}, \
+             \"locations\": [{\"physicalLocation\": {\"artifactLocation\": {\"uri\": \"main.cpp\"}, \
+             \"region\": {\"startLine\": 3, \"startColumn\": 5, \"endLine\": 3, \"endColumn\": 14}}}]}"
+        ));
+    }
+}
@@ -1,7 +1,24 @@
 #![allow(dead_code)]
 
-#[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+use crate::library::diagnostics::Severity;
+
+/// banner placed at the top of every generated file
+pub const CRUST_BANNER: &str =
+    "/*************************************************************************\
+    \n * This file was generated by CRUST by an automated semantics preserving\
+    \n * translation from C/C++ to Rust\
+    \n * CRUST isn't perfect and may require manual editing\
+    \n * Check warnings and errors and refer to the official Rust Documentation\
+    \n ************************************************************************/\n";
+
+/**
+ * DocType:
+ * catalogue of the things crust reports about a translation,
+ * every entry has a stable code, a default severity, a one line
+ * title and a help text telling how to finish the translation by hand
+ */
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DocType {
     NO_STRICT,
     STRICT,
@@ -10,69 +27,203 @@ pub enum DocType {
     INCLUDE_STMT,
     NO_POINTER,
     CONSTRUCTOR,
-    CRUST,
-    DEFAULT,
     UNION,
     UNION_DECL,
     OPERATOR_OVERLOAD,
+    USING_STMT,
+    STREAM_OP,
+    PARSE_ERROR,
+    LEX_ERROR,
+    NAMESPACE,
+    TEMPLATE,
+    EXCEPTION,
+    GOTO,
+    KEYWORD,
+    PREPROCESS_ERROR,
+    TOKEN_PASTE,
+    NULL_POINTER,
 }
 
 impl DocType {
-    pub fn get_doc(self) -> &'static str {
+    /// every entry, in code order
+    pub const ALL: [DocType; 22] = [
+        DocType::NO_STRICT,
+        DocType::STRICT,
+        DocType::STRUCT_INIT,
+        DocType::NO_RETURN,
+        DocType::INCLUDE_STMT,
+        DocType::NO_POINTER,
+        DocType::CONSTRUCTOR,
+        DocType::UNION,
+        DocType::UNION_DECL,
+        DocType::OPERATOR_OVERLOAD,
+        DocType::USING_STMT,
+        DocType::STREAM_OP,
+        DocType::PARSE_ERROR,
+        DocType::LEX_ERROR,
+        DocType::NAMESPACE,
+        DocType::TEMPLATE,
+        DocType::EXCEPTION,
+        DocType::GOTO,
+        DocType::KEYWORD,
+        DocType::PREPROCESS_ERROR,
+        DocType::TOKEN_PASTE,
+        DocType::NULL_POINTER,
+    ];
+
+    /// stable code, never reuse or renumber a code
+    pub fn code(self) -> &'static str {
+        match self {
+            DocType::NO_STRICT => "CR0001",
+            DocType::STRICT => "CR0002",
+            DocType::STRUCT_INIT => "CR0003",
+            DocType::NO_RETURN => "CR0004",
+            DocType::INCLUDE_STMT => "CR0005",
+            DocType::NO_POINTER => "CR0006",
+            DocType::CONSTRUCTOR => "CR0007",
+            DocType::UNION => "CR0008",
+            DocType::UNION_DECL => "CR0009",
+            DocType::OPERATOR_OVERLOAD => "CR0010",
+            DocType::USING_STMT => "CR0011",
+            DocType::STREAM_OP => "CR0012",
+            DocType::PARSE_ERROR => "CR0013",
+            DocType::LEX_ERROR => "CR0014",
+            DocType::NAMESPACE => "CR0015",
+            DocType::TEMPLATE => "CR0016",
+            DocType::EXCEPTION => "CR0017",
+            DocType::GOTO => "CR0018",
+            DocType::KEYWORD => "CR0019",
+            DocType::PREPROCESS_ERROR => "CR0020",
+            DocType::TOKEN_PASTE => "CR0021",
+            DocType::NULL_POINTER => "CR0022",
+        }
+    }
+
+    /// short kebab case name of the entry
+    pub fn name(self) -> &'static str {
         match self {
+            DocType::NO_STRICT => "mutable-declaration",
+            DocType::STRICT => "immutable-declaration",
+            DocType::STRUCT_INIT => "struct-default-init",
+            DocType::NO_RETURN => "return-shorthand",
+            DocType::INCLUDE_STMT => "include-not-resolved",
+            DocType::NO_POINTER => "pointer-as-reference",
+            DocType::CONSTRUCTOR => "constructor-not-translated",
+            DocType::UNION => "union-as-enum",
+            DocType::UNION_DECL => "union-variable",
+            DocType::OPERATOR_OVERLOAD => "operator-overload",
+            DocType::USING_STMT => "using-not-translated",
+            DocType::STREAM_OP => "stream-operator",
+            DocType::PARSE_ERROR => "syntax-error",
+            DocType::LEX_ERROR => "lexical-error",
+            DocType::NAMESPACE => "namespace-not-translated",
+            DocType::TEMPLATE => "template-not-translated",
+            DocType::EXCEPTION => "exception-not-translated",
+            DocType::GOTO => "goto-not-translated",
+            DocType::KEYWORD => "keyword-not-translated",
+            DocType::PREPROCESS_ERROR => "preprocessor-error",
+            DocType::TOKEN_PASTE => "token-paste",
+            DocType::NULL_POINTER => "null-pointer",
+        }
+    }
+
+    /// notes describe choices made by crust, warnings mark code needing manual work
+    pub fn severity(self) -> Severity {
+        match self {
+            DocType::NO_STRICT | DocType::STRICT | DocType::STRUCT_INIT | DocType::NO_RETURN => {
+                Severity::Note
+            }
+            DocType::PARSE_ERROR | DocType::LEX_ERROR | DocType::PREPROCESS_ERROR => {
+                Severity::Error
+            }
+            _ => Severity::Warning,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            DocType::NO_STRICT => "variable declared as mutable",
+            DocType::STRICT => "variable declared as immutable in strict mode",
+            DocType::STRUCT_INIT => "structure fields initialized with default values",
+            DocType::NO_RETURN => "return statement replaced with the shorthand notation",
+            DocType::INCLUDE_STMT => "included header is not translated",
+            DocType::NO_POINTER => "pointer translated into a reference",
+            DocType::CONSTRUCTOR => "constructor is not translated",
+            DocType::UNION => "union translated into an enum",
+            DocType::UNION_DECL => "union variable needs an enum variant",
+            DocType::OPERATOR_OVERLOAD => "operator overloading is not translated",
+            DocType::USING_STMT => "using declaration is not translated",
+            DocType::STREAM_OP => "stream operators `<<` and `>>` are not translated",
+            DocType::PARSE_ERROR => "construct could not be parsed",
+            DocType::LEX_ERROR => "source could not be tokenized",
+            DocType::NAMESPACE => "namespace is not translated",
+            DocType::TEMPLATE => "template is not translated",
+            DocType::EXCEPTION => "exception handling is not translated",
+            DocType::GOTO => "goto and labels are not translated",
+            DocType::KEYWORD => "construct has no rust equivalent",
+            DocType::PREPROCESS_ERROR => "directive could not be carried out",
+            DocType::TOKEN_PASTE => "macro pasting tokens is expanded where it is used",
+            DocType::NULL_POINTER => "null pointer left out, references can not be null",
+        }
+    }
+
+    pub fn help(self) -> &'static str {
+        match self {
+            DocType::NO_STRICT => "avoid using mutable variables unless it is necessary to do so",
             DocType::STRICT => {
-                "\n/*Crust with Strict Mode enabled, declares all variables as immutable.\n * If \
-                 you are mutating the below variable anywhere in program, please change the \
-                 declaration statement as\n * let mut var_name:type=init_val;\n **/\n"
+                "if the variable is mutated anywhere in the program, declare it as \
+                 `let mut var_name: type = init_val;`, or translate without --strict \
+                 to have the written variables declared mutable"
             }
-            DocType::NO_STRICT => "\n/*Avoid using mutable variables unless it is necessary to do so\n */\n",
             DocType::STRUCT_INIT => {
-                "\n/** Declaration of a structure should be completed with initialization of it's \
-                 fields\n * It should be in the following format\n * let variable:struct_name = struct_name { \
-                 member1:value1, member2:value2,..}\n */"
+                "a structure variable must be initialized as \
+                 `let variable = Name { member1: value1, member2: value2, .. };`"
             }
             DocType::NO_RETURN => {
-                "\n/** Crust tries to identify return statement and replace with rust equivalent\n \
-                 * shorthand notation. If error found in this line, Please replace shorthand \
-                 notation \n * with return statement \n **/\n"
+                "if the compiler rejects the shorthand notation, replace it with a return statement"
             }
             DocType::INCLUDE_STMT => {
-                "\n/** Crust doesn't resolve C/C++ dependencies or included header.\n* You may have \
-                 to define your own module and implement those functionality in Rust \n* Or you \
-                 can translate header file with Crust to produce Rust code. * \n* >>>>>>>>"
+                "define a module implementing the functionality in rust, or give \
+                 the directory of the header with -I to translate it into a module"
             }
             DocType::NO_POINTER => {
-                "\n/** Crust doesn't support pointers yet,\
-                \n * Please handle the pointers manually with rust refernce, Rc, Arc or RefCell \
-                \n * ======> "
+                "check the lifetime of the reference, or use Box, Rc, Arc or RefCell instead"
+            }
+            DocType::CONSTRUCTOR => "rust structures have no constructors, use a static builder function",
+            DocType::UNION => "tagged unions are not handled completely, check the variant used at every access",
+            DocType::UNION_DECL => "initialize the variable with the enum variant it holds",
+            DocType::OPERATOR_OVERLOAD => {
+                "the operator is defined as a method named after it, `operator==()` becomes `operator_eq()`"
             }
-            DocType::CONSTRUCTOR => {
-                "\n/** Rust structures do not support constructors\
-                \n * Please handle them with static builder functions\n * >>>>>>>>"
+            DocType::USING_STMT => "bring the names into scope with a `use` declaration",
+            DocType::STREAM_OP => "rewrite the statement with `print!`, `println!` or `std::io`",
+            DocType::PARSE_ERROR => {
+                "the construct is kept as a comment in the output, check that the source compiles"
             }
-            DocType::CRUST => {
-                "/*************************************************************************\
-                \n * This file was generated by CRUST by an automated semantics preserving\
-                \n * translation from C/C++ to Rust\
-                \n * CRUST isn't perfect and may require manual editing\
-                \n * Check warnings and errors and refer to the official Rust Documentation\
-                \n ************************************************************************/\n"
+            DocType::LEX_ERROR => {
+                "invalid characters are left out of the output, unterminated literals and \
+                 comments are closed where the tokenizer stopped reading them"
             }
-            DocType::UNION => {
-                "\n/* CRUST does not hanlde C/C++ tagged union type completely.\
-				 \n *It translates union type into Rust Some type. \
-				 \n *Please check the varient used properly */\n"
+            DocType::NAMESPACE => "move the declarations into a module, `mod name { .. }`",
+            DocType::TEMPLATE => "rewrite the declaration with generics, `fn name<T>(..)`",
+            DocType::EXCEPTION => "return a `Result` and propagate the error with the `?` operator",
+            DocType::GOTO => "restructure the control flow with loops and labeled `break`",
+            DocType::KEYWORD => {
+                "delete, new, asm, static_assert, decltype, typeid, pure virtual methods and \
+                 variadic parameters are left out, free memory by dropping its owner, allocate \
+                 with `Box::new`, use `std::arch::asm!`, `const _: () = assert!(..);`, the type \
+                 itself, `std::any::type_name`, a trait method and a slice parameter"
             }
-            DocType::UNION_DECL => {
-                "\n/* Union declaration must be translated to Rust equivalent Some type varients\
-				\n * Make changes before compilation \n */"
+            DocType::PREPROCESS_ERROR => {
+                "check the directive, and the macros and include paths given with -D and -I"
             }
-            DocType::OPERATOR_OVERLOAD => {
-                "\n/* Operator overloading is not supported at the current state of Crust.\
-				\n * It will convert into operator_op_name\
-				\n * Ex : operator==()  will be defined as operator_eq()\n */"
+            DocType::TOKEN_PASTE => {
+                "rust macros can not build names with `##`, spell out the names the macro builds"
+            }
+            DocType::NULL_POINTER => {
+                "the variable is declared without its value, make it an `Option<&T>` \
+                 initialized with `None` if it may be null"
             }
-            _ => "//Doc Not Found. Please Report bug",
         }
     }
 }
@@ -35,6 +35,10 @@ pub enum TokenType {
     Signed,
     Auto,
     Using,
+    //wchar_t, char16_t, char32_t
+    WideChar,
+    Char16,
+    Char32,
 
     //operators token types
     Equal,
@@ -52,8 +56,14 @@ pub enum TokenType {
     BitwiseAnd,
     BitwiseOr,
     BitwiseNegate,
+    BitwiseXor,
     BitwiseLeftShift,
     BitwiseRightShift,
+    AndEqual,
+    OrEqual,
+    XorEqual,
+    LeftShiftEqual,
+    RightShiftEqual,
     LogicalAnd,
     LogicalOr,
     LogicalNot,
@@ -92,6 +102,12 @@ pub enum TokenType {
     Identifier,
     Arrow,
     ScopeResolution,
+    Dot,
+    //...
+    Ellipsis,
+    //pointer to member .* and ->*
+    DotStar,
+    ArrowStar,
 
     //Identifiers an=d keywords
     KeywordClass,
@@ -115,9 +131,66 @@ pub enum TokenType {
     KeywordStruct,
     KeywordEnum,
     KeywordUnion,
+    KeywordGoto,
+    KeywordThis,
+    KeywordOperator,
+    KeywordDelete,
+    KeywordNamespace,
+    KeywordTemplate,
+    KeywordTypename,
+    KeywordFriend,
+    KeywordTry,
+    KeywordCatch,
+    KeywordThrow,
+    KeywordNoexcept,
+    KeywordAsm,
+    KeywordExport,
+    KeywordDecltype,
+    KeywordTypeid,
+    //static_assert, _Static_assert
+    KeywordStaticAssert,
+    //_Generic
+    KeywordGeneric,
+    //alignof, _Alignof
+    KeywordAlignof,
+    KeywordStaticCast,
+    KeywordConstCast,
+    KeywordDynamicCast,
+    KeywordReinterpretCast,
+
+    //declaration specifiers and qualifiers
+    KeywordExtern,
+    KeywordInline,
+    KeywordRegister,
+    KeywordVolatile,
+    KeywordRestrict,
+    KeywordConstexpr,
+    KeywordMutable,
+    KeywordVirtual,
+    KeywordExplicit,
+    //thread_local, _Thread_local
+    KeywordThreadLocal,
+    //_Noreturn
+    KeywordNoreturn,
+    //_Atomic
+    KeywordAtomic,
+    //alignas, _Alignas
+    KeywordAlignas,
+    //_Complex, _Imaginary
+    KeywordComplex,
+    KeywordImaginary,
 
     HeaderDefine,
+    //# at the start of a directive, and the stringizing operator of macros
     HeaderInclude,
+    //## of macros
+    TokenPaste,
+    //directive left for the translation by the preprocessor, `#pragma pack(1)`
+    Directive,
+    //end of a #define kept for the translation by the preprocessor
+    DirectiveEnd,
+    //#include of a header translated into a module, the value is the path of the header
+    HeaderModule,
     HeaderIfDefineStart,
     HeaderIfDefineEnd,
     Main,
@@ -0,0 +1,286 @@
+/// character type of a string or character literal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Narrow,
+    /// L"..."
+    Wide,
+    /// u8"..."
+    Utf8,
+    /// u"..."
+    Utf16,
+    /// U"..."
+    Utf32,
+}
+
+/// Encoding prefix of a string or character literal, recorded on its token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Prefix {
+    pub encoding: Encoding,
+    /// R"delim(...)delim"
+    pub raw: bool,
+}
+
+impl Prefix {
+    /// prefix written before the quote, `L`, `u8R`, `R`
+    pub fn parse(text: &str) -> Option<Prefix> {
+        let (encoding, rest) = if let Some(rest) = text.strip_prefix("u8") {
+            (Encoding::Utf8, rest)
+        } else if let Some(rest) = text.strip_prefix('L') {
+            (Encoding::Wide, rest)
+        } else if let Some(rest) = text.strip_prefix('u') {
+            (Encoding::Utf16, rest)
+        } else if let Some(rest) = text.strip_prefix('U') {
+            (Encoding::Utf32, rest)
+        } else {
+            (Encoding::Narrow, text)
+        };
+        match rest {
+            "" => Some(Prefix {
+                encoding,
+                raw: false,
+            }),
+            "R" => Some(Prefix {
+                encoding,
+                raw: true,
+            }),
+            _ => None,
+        }
+    }
+
+    /// wider encodings win when literals are concatenated, `"a" L"b"` is wide
+    pub fn concat(self, other: Prefix) -> Prefix {
+        Prefix {
+            encoding: if self.encoding == Encoding::Narrow {
+                other.encoding
+            } else {
+                self.encoding
+            },
+            raw: self.raw && other.raw,
+        }
+    }
+}
+
+/// A character of a literal, written as is or as an escape sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// the character itself, or a simple escape such as `\n`
+    Char(char),
+    /// value of an octal, hexadecimal or universal character escape
+    Escape(u32),
+}
+
+/// Contents of one literal, or of adjacent string literals concatenated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Literal {
+    pub prefix: Prefix,
+    pub units: Vec<Unit>,
+}
+
+/**
+ * decode:
+ * contents of the literal as written in the source, adjacent
+ * string literals are concatenated, `"a\x41" L"b"`
+ * the error describes the first invalid escape sequence
+ */
+pub fn decode(text: &str) -> Result<Literal, String> {
+    let mut literal: Option<Literal> = None;
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let quote = rest.find(['"', '\'']).ok_or("missing quote")?;
+        let prefix = Prefix::parse(&rest[..quote]).ok_or("unknown literal prefix")?;
+        let quote_char = rest[quote..].chars().next().unwrap_or('"');
+        let body = &rest[quote + 1..];
+        let (units, next) = if prefix.raw {
+            decode_raw(body)?
+        } else {
+            decode_escaped(body, quote_char, prefix.encoding)?
+        };
+        literal = Some(match literal {
+            Some(mut literal) => {
+                literal.prefix = literal.prefix.concat(prefix);
+                literal.units.extend(units);
+                literal
+            }
+            None => Literal { prefix, units },
+        });
+        rest = next.trim_start();
+    }
+    literal.ok_or_else(|| "empty literal".to_string())
+}
+
+/// R"delim(...)delim", the body starts after the opening quote
+fn decode_raw(body: &str) -> Result<(Vec<Unit>, &str), String> {
+    let open = body.find('(').ok_or("missing `(` in raw string literal")?;
+    let delimiter = &body[..open];
+    let close = format!("){}\"", delimiter);
+    let contents = &body[open + 1..];
+    let end = contents.find(&close).unwrap_or(contents.len());
+    let units = contents[..end].chars().map(Unit::Char).collect();
+    let next = contents.get(end + close.len()..).unwrap_or("");
+    Ok((units, next))
+}
+
+/// body of a quoted literal with escape sequences, up to the closing quote
+fn decode_escaped(
+    body: &str,
+    quote: char,
+    encoding: Encoding,
+) -> Result<(Vec<Unit>, &str), String> {
+    let mut units = Vec::new();
+    let mut chars = body.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            return Ok((units, &body[i + 1..]));
+        }
+        if c != '\\' {
+            units.push(Unit::Char(c));
+            continue;
+        }
+        let (_, escape) = match chars.next() {
+            Some(next) => next,
+            None => break,
+        };
+        let unit = match escape {
+            '\'' | '"' | '?' | '\\' => Unit::Char(escape),
+            'a' => Unit::Char('\x07'),
+            'b' => Unit::Char('\x08'),
+            'f' => Unit::Char('\x0c'),
+            'n' => Unit::Char('\n'),
+            'r' => Unit::Char('\r'),
+            't' => Unit::Char('\t'),
+            'v' => Unit::Char('\x0b'),
+            // line splice
+            '\n' => continue,
+            '\r' if matches!(chars.peek(), Some(&(_, '\n'))) => {
+                chars.next();
+                continue;
+            }
+            '0'..='7' => {
+                let mut value = escape.to_digit(8).unwrap_or(0);
+                for _ in 0..2 {
+                    match chars.peek().and_then(|&(_, c)| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                Unit::Escape(value)
+            }
+            'x' => {
+                let mut value: u32 = 0;
+                let mut digits = 0;
+                while let Some(digit) = chars.peek().and_then(|&(_, c)| c.to_digit(16)) {
+                    value = value.saturating_mul(16).saturating_add(digit);
+                    digits += 1;
+                    chars.next();
+                }
+                if digits == 0 {
+                    return Err("\\x used with no following hex digits".to_string());
+                }
+                Unit::Escape(value)
+            }
+            'u' | 'U' => {
+                let length = if escape == 'u' { 4 } else { 8 };
+                let mut value = 0;
+                for _ in 0..length {
+                    match chars.peek().and_then(|&(_, c)| c.to_digit(16)) {
+                        Some(digit) => {
+                            value = value * 16 + digit;
+                            chars.next();
+                        }
+                        None => {
+                            return Err(format!(
+                                "incomplete universal character name, `\\{}` needs {} hex digits",
+                                escape, length
+                            ))
+                        }
+                    }
+                }
+                Unit::Escape(value)
+            }
+            // unknown escapes stand for the character, as with gcc and clang
+            other => Unit::Char(other),
+        };
+        if let Unit::Escape(value) = unit {
+            let max = match encoding {
+                Encoding::Narrow | Encoding::Utf8 => 0xff,
+                Encoding::Utf16 => 0xffff,
+                Encoding::Wide | Encoding::Utf32 => u32::MAX,
+            };
+            // universal character names are encoded, not truncated
+            if value > max && escape != 'u' && escape != 'U' {
+                return Err("escape sequence out of range".to_string());
+            }
+        }
+        units.push(unit);
+    }
+    // unterminated, reported by the tokenizer
+    Ok((units, ""))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, Encoding, Prefix, Unit};
+
+    #[test]
+    fn test_that_prefixes_are_recognized() {
+        let raw = |encoding| Prefix {
+            encoding,
+            raw: true,
+        };
+        assert_eq!(Prefix::parse(""), Some(Prefix::default()));
+        assert_eq!(Prefix::parse("u8R"), Some(raw(Encoding::Utf8)));
+        assert_eq!(Prefix::parse("LR"), Some(raw(Encoding::Wide)));
+        assert_eq!(
+            Prefix::parse("U").map(|p| p.encoding),
+            Some(Encoding::Utf32)
+        );
+        assert_eq!(Prefix::parse("x"), None);
+        assert_eq!(Prefix::parse("Ru"), None);
+    }
+
+    #[test]
+    fn test_that_escapes_are_decoded() {
+        let literal = decode(r#""a\n\101\x41é\?""#).unwrap();
+        assert_eq!(
+            literal.units,
+            vec![
+                Unit::Char('a'),
+                Unit::Char('\n'),
+                Unit::Escape(0o101),
+                Unit::Escape(0x41),
+                Unit::Char('é'),
+                Unit::Char('?'),
+            ]
+        );
+        assert_eq!(decode(r#""\;""#).unwrap().units, vec![Unit::Char(';')]);
+        assert_eq!(
+            decode(r#""\x""#),
+            Err("\\x used with no following hex digits".to_string())
+        );
+        assert_eq!(
+            decode(r#""\x100""#),
+            Err("escape sequence out of range".to_string())
+        );
+        assert!(decode(r#"L"\x100""#).is_ok());
+    }
+
+    #[test]
+    fn test_that_adjacent_literals_are_concatenated() {
+        let literal = decode("\"a\" L\"b\"\n R\"xy(\")x\")xy\"").unwrap();
+        assert_eq!(literal.prefix.encoding, Encoding::Wide);
+        assert!(!literal.prefix.raw);
+        let text: String = literal
+            .units
+            .iter()
+            .map(|unit| match unit {
+                Unit::Char(c) => *c,
+                Unit::Escape(_) => '?',
+            })
+            .collect();
+        assert_eq!(text, "ab\")x\"");
+    }
+}
@@ -1,4 +1,7 @@
 #![allow(dead_code)]
 
 pub(crate) mod definition;
+pub mod literal;
+pub mod number;
+pub mod span;
 pub mod token;
@@ -0,0 +1,221 @@
+use crate::library::lexeme::definition::TokenType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Radix {
+    Binary,
+    Octal,
+    #[default]
+    Decimal,
+    Hexadecimal,
+}
+
+/// type suffix of a numeric literal, `10UL`, `3.0f`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Suffix {
+    #[default]
+    None,
+    Unsigned,
+    Long,
+    UnsignedLong,
+    LongLong,
+    UnsignedLongLong,
+    Float,
+    LongDouble,
+}
+
+/// How a numeric literal is written, recorded on its token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Number {
+    pub radix: Radix,
+    pub suffix: Suffix,
+}
+
+impl Number {
+    /**
+     * parse:
+     * classifies the text of a numeric literal, digit separators included
+     * 0x1F, 0b1010, 017, 1e-9, 0x1.8p3, 10UL, 3.0f, 1'000'000
+     * the error describes what makes the literal invalid
+     */
+    pub fn parse(text: &str) -> Result<(TokenType, Number), String> {
+        let digits: String = text.chars().filter(|&c| c != '\'').collect();
+        let lower = digits.to_ascii_lowercase();
+        let (radix, body) = if lower.starts_with("0x") {
+            (Radix::Hexadecimal, &digits[2..])
+        } else if lower.starts_with("0b") {
+            (Radix::Binary, &digits[2..])
+        } else {
+            (Radix::Decimal, &digits[..])
+        };
+
+        let mut chars = body.char_indices().peekable();
+        let is_digit = |c: char| match radix {
+            Radix::Hexadecimal => c.is_ascii_hexdigit(),
+            _ => c.is_ascii_digit(),
+        };
+        let mut mantissa_digits = 0;
+        let mut float = false;
+        while let Some(&(_, c)) = chars.peek() {
+            if is_digit(c) {
+                mantissa_digits += 1;
+            } else if c == '.' && !float && radix != Radix::Binary {
+                float = true;
+            } else {
+                break;
+            }
+            chars.next();
+        }
+        if mantissa_digits == 0 {
+            return Err(format!("no digits in {} literal", radix_name(radix)));
+        }
+
+        let exponent = match radix {
+            Radix::Hexadecimal => 'p',
+            _ => 'e',
+        };
+        let has_exponent =
+            matches!(chars.peek(), Some(&(_, c)) if c.to_ascii_lowercase() == exponent);
+        if has_exponent && radix != Radix::Binary {
+            float = true;
+            chars.next();
+            if let Some(&(_, '+')) | Some(&(_, '-')) = chars.peek() {
+                chars.next();
+            }
+            let mut exponent_digits = 0;
+            while let Some(&(_, c)) = chars.peek() {
+                if !c.is_ascii_digit() {
+                    break;
+                }
+                exponent_digits += 1;
+                chars.next();
+            }
+            if exponent_digits == 0 {
+                return Err("exponent has no digits".to_string());
+            }
+        } else if float && radix == Radix::Hexadecimal {
+            return Err("hexadecimal floating literal requires an exponent".to_string());
+        }
+
+        let suffix_start = chars.peek().map_or(body.len(), |&(i, _)| i);
+        let (mantissa, suffix) = body.split_at(suffix_start);
+
+        if float {
+            let suffix = match suffix {
+                "" => Suffix::None,
+                "f" | "F" => Suffix::Float,
+                "l" | "L" => Suffix::LongDouble,
+                _ => return Err(format!("invalid suffix `{}` on floating literal", suffix)),
+            };
+            return Ok((TokenType::NumberFloat, Number { radix, suffix }));
+        }
+
+        // 017 is octal, 0 alone is decimal
+        let radix = if radix == Radix::Decimal && mantissa.len() > 1 && mantissa.starts_with('0') {
+            Radix::Octal
+        } else {
+            radix
+        };
+        let limit = match radix {
+            Radix::Binary => Some('1'),
+            Radix::Octal => Some('7'),
+            _ => None,
+        };
+        if let Some(c) = limit.and_then(|limit| mantissa.chars().find(|&c| c > limit)) {
+            return Err(format!(
+                "invalid digit `{}` in {} literal",
+                c,
+                radix_name(radix)
+            ));
+        }
+        let suffix = match suffix.to_ascii_lowercase().as_str() {
+            "" => Suffix::None,
+            "u" => Suffix::Unsigned,
+            "l" => Suffix::Long,
+            "ul" | "lu" => Suffix::UnsignedLong,
+            "ll" => Suffix::LongLong,
+            "ull" | "llu" => Suffix::UnsignedLongLong,
+            _ => return Err(format!("invalid suffix `{}` on integer literal", suffix)),
+        };
+        Ok((TokenType::NumberInteger, Number { radix, suffix }))
+    }
+}
+
+fn radix_name(radix: Radix) -> &'static str {
+    match radix {
+        Radix::Binary => "binary",
+        Radix::Octal => "octal",
+        Radix::Decimal => "decimal",
+        Radix::Hexadecimal => "hexadecimal",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Number, Radix, Suffix};
+    use crate::library::lexeme::definition::TokenType::{NumberFloat, NumberInteger};
+
+    fn number(radix: Radix, suffix: Suffix) -> Number {
+        Number { radix, suffix }
+    }
+
+    #[test]
+    fn test_that_radix_and_suffix_are_recognized() {
+        let cases = [
+            (
+                "0x1F",
+                NumberInteger,
+                number(Radix::Hexadecimal, Suffix::None),
+            ),
+            ("0b1010", NumberInteger, number(Radix::Binary, Suffix::None)),
+            ("017", NumberInteger, number(Radix::Octal, Suffix::None)),
+            ("0", NumberInteger, number(Radix::Decimal, Suffix::None)),
+            (
+                "10UL",
+                NumberInteger,
+                number(Radix::Decimal, Suffix::UnsignedLong),
+            ),
+            (
+                "10llu",
+                NumberInteger,
+                number(Radix::Decimal, Suffix::UnsignedLongLong),
+            ),
+            (
+                "1'000'000",
+                NumberInteger,
+                number(Radix::Decimal, Suffix::None),
+            ),
+            ("1e-9", NumberFloat, number(Radix::Decimal, Suffix::None)),
+            ("3.0f", NumberFloat, number(Radix::Decimal, Suffix::Float)),
+            (
+                ".5L",
+                NumberFloat,
+                number(Radix::Decimal, Suffix::LongDouble),
+            ),
+            ("017.5", NumberFloat, number(Radix::Decimal, Suffix::None)),
+            (
+                "0x1.8p3",
+                NumberFloat,
+                number(Radix::Hexadecimal, Suffix::None),
+            ),
+        ];
+        for (text, typ, expected) in cases.iter() {
+            assert_eq!(Number::parse(text), Ok((*typ, *expected)), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_that_invalid_literals_are_described() {
+        let cases = [
+            ("089", "invalid digit `8` in octal literal"),
+            ("0b102", "invalid digit `2` in binary literal"),
+            ("0x", "no digits in hexadecimal literal"),
+            ("1e+", "exponent has no digits"),
+            ("0x1.8", "hexadecimal floating literal requires an exponent"),
+            ("10lul", "invalid suffix `lul` on integer literal"),
+            ("1.0u", "invalid suffix `u` on floating literal"),
+        ];
+        for (text, message) in cases.iter() {
+            assert_eq!(Number::parse(text), Err(message.to_string()), "{}", text);
+        }
+    }
+}
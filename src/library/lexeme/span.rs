@@ -0,0 +1,106 @@
+use std::fmt;
+
+/// A location in the source text, `line` and `column` start at 1,
+/// `column` counts characters and `offset` counts bytes from the start of the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Position {
+    pub offset: usize,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// The region of source text a token or construct was read from, `end` is exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Position {
+    /// position of the first character of a file
+    pub fn first() -> Position {
+        Position {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// position right after the character `c` read at this position
+    pub fn advance(self, c: char) -> Position {
+        if c == '\n' {
+            Position {
+                offset: self.offset + c.len_utf8(),
+                line: self.line + 1,
+                column: 1,
+            }
+        } else {
+            Position {
+                offset: self.offset + c.len_utf8(),
+                column: self.column + 1,
+                ..self
+            }
+        }
+    }
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Span {
+        Span { start, end }
+    }
+
+    /// span covering both `self` and `other`
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    /// length of the span in bytes
+    pub fn len(&self) -> usize {
+        self.end.offset - self.start.offset
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Position, Span};
+
+    #[test]
+    fn test_that_position_advances_over_lines() {
+        let pos = Position::first().advance('a').advance('é');
+        assert_eq!(pos.offset, 3);
+        assert_eq!((pos.line, pos.column), (1, 3));
+
+        let pos = pos.advance('\n');
+        assert_eq!(pos.offset, 4);
+        assert_eq!((pos.line, pos.column), (2, 1));
+    }
+
+    #[test]
+    fn test_that_spans_are_joined() {
+        let a = Position::first();
+        let b = a.advance('x');
+        let c = b.advance('y');
+        let span = Span::new(b, c).to(Span::new(a, b));
+        assert_eq!(span, Span::new(a, c));
+        assert_eq!(span.len(), 2);
+    }
+}
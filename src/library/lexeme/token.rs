@@ -1,13 +1,33 @@
 use crate::library::lexeme::definition::{TokenKind, TokenType};
+use crate::library::lexeme::literal::Prefix;
+use crate::library::lexeme::number::Number;
+use crate::library::lexeme::span::Span;
 use std::fmt;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Eq)]
 pub struct Token {
     token: String,
     token_kind: TokenKind,
     token_type: TokenType,
     line_number: u32,
     id: u32,
+    span: Span,
+    // radix and suffix of numeric literals
+    number: Option<Number>,
+    // encoding prefix of string and character literals
+    prefix: Option<Prefix>,
+}
+
+// span is where the token was read from, not part of what the token is,
+// number and prefix are derived from the token text
+impl PartialEq for Token {
+    fn eq(&self, other: &Token) -> bool {
+        self.token == other.token
+            && self.token_kind == other.token_kind
+            && self.token_type == other.token_type
+            && self.line_number == other.line_number
+            && self.id == other.id
+    }
 }
 
 impl Clone for Token {
@@ -31,9 +51,34 @@ impl Token {
             token_type,
             line_number,
             id,
+            span: Span::default(),
+            number: None,
+            prefix: None,
         }
     }
 
+    pub fn with_span(mut self, span: Span) -> Token {
+        self.span = span;
+        self
+    }
+
+    /// the token as it appears at `span`, used for tokens of macro expansions and included files
+    pub fn with_location(mut self, span: Span) -> Token {
+        self.span = span;
+        self.line_number = span.start.line;
+        self
+    }
+
+    pub fn with_number(mut self, number: Number) -> Token {
+        self.number = Some(number);
+        self
+    }
+
+    pub fn with_prefix(mut self, prefix: Prefix) -> Token {
+        self.prefix = Some(prefix);
+        self
+    }
+
     // returns both token kind and token type.
     pub fn get_type(&self) -> (TokenKind, TokenType) {
         (self.token_kind, self.token_type)
@@ -58,6 +103,18 @@ impl Token {
         self.id
     }
 
+    pub fn get_span(&self) -> Span {
+        self.span
+    }
+
+    pub fn get_number(&self) -> Option<Number> {
+        self.number
+    }
+
+    pub fn get_prefix(&self) -> Option<Prefix> {
+        self.prefix
+    }
+
     pub fn set_token_value(&mut self, val: &str) {
         self.token = val.to_string();
     }
@@ -82,8 +139,8 @@ impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(
             f,
-            "[Token ID {}, Token: {}, Kind : {:?}, Type: {:?}, LineNumber : {}]",
-            self.id, self.token, self.token_kind, self.token_type, self.line_number,
+            "[Token ID {}, Token: {}, Kind : {:?}, Type: {:?}, LineNumber : {}, Span : {}]",
+            self.id, self.token, self.token_kind, self.token_type, self.line_number, self.span,
         )
     }
 }
@@ -119,7 +176,7 @@ mod test {
         );
         assert_eq!(token.get_token_value(), "\'");
 
-        token.set_token_value(&"\"");
+        token.set_token_value("\"");
         assert_eq!(token.get_token_value(), "\"");
     }
 
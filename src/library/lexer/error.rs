@@ -0,0 +1,38 @@
+use std::fmt;
+
+use crate::library::diagnostics::Diagnostic;
+use crate::library::doc::DocType;
+use crate::library::lexeme::span::Span;
+use crate::library::lexeme::token::Token;
+
+/// Characters the tokenizer could not read, `span` covers them
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl LexError {
+    pub fn new(message: String, span: Span) -> LexError {
+        LexError { message, span }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(DocType::LEX_ERROR, self.span).with_message(self.message.clone())
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.span.start, self.message)
+    }
+}
+
+/// Errors found while tokenizing, along with the tokens read around
+/// them. Unterminated literals and comments are closed at the point
+/// the tokenizer gave up on them
+#[derive(Debug)]
+pub struct LexErrors {
+    pub errors: Vec<LexError>,
+    pub tokens: Vec<Token>,
+}
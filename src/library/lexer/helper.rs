@@ -41,12 +41,69 @@ pub fn identify_token_type(tok: &[char]) -> (TokenType, TokenKind) {
         "struct" => (TokenType::KeywordStruct, TokenKind::Keyword),
         "string" => (TokenType::StringValue, TokenKind::DataTypes),
         "NULL" => (TokenType::Null, TokenKind::Keyword),
-        "#include" => (TokenType::HeaderInclude, TokenKind::Preprocessors),
-        "#define" => (TokenType::HeaderDefine, TokenKind::Preprocessors),
-        "#ifdef" => (TokenType::HeaderIfDefineStart, TokenKind::Preprocessors),
-        "#endif" => (TokenType::HeaderIfDefineEnd, TokenKind::Preprocessors),
         "sizeof" => (TokenType::SizeOf, TokenKind::UnaryOperators),
         "using" => (TokenType::Using, TokenKind::Keyword),
+        "wchar_t" => (TokenType::WideChar, TokenKind::DataTypes),
+        "char16_t" => (TokenType::Char16, TokenKind::DataTypes),
+        "char32_t" => (TokenType::Char32, TokenKind::DataTypes),
+        "_Bool" => (TokenType::Boolean, TokenKind::DataTypes),
+        "nullptr" => (TokenType::Null, TokenKind::Keyword),
+        "goto" => (TokenType::KeywordGoto, TokenKind::Keyword),
+        "this" => (TokenType::KeywordThis, TokenKind::Keyword),
+        "operator" => (TokenType::KeywordOperator, TokenKind::Keyword),
+        "delete" => (TokenType::KeywordDelete, TokenKind::Keyword),
+        "namespace" => (TokenType::KeywordNamespace, TokenKind::Keyword),
+        "template" => (TokenType::KeywordTemplate, TokenKind::Keyword),
+        "typename" => (TokenType::KeywordTypename, TokenKind::Keyword),
+        "friend" => (TokenType::KeywordFriend, TokenKind::Modifiers),
+        "try" => (TokenType::KeywordTry, TokenKind::Keyword),
+        "catch" => (TokenType::KeywordCatch, TokenKind::Keyword),
+        "throw" => (TokenType::KeywordThrow, TokenKind::Keyword),
+        "noexcept" => (TokenType::KeywordNoexcept, TokenKind::Keyword),
+        "asm" => (TokenType::KeywordAsm, TokenKind::Keyword),
+        "export" => (TokenType::KeywordExport, TokenKind::Keyword),
+        "decltype" => (TokenType::KeywordDecltype, TokenKind::Keyword),
+        "typeid" => (TokenType::KeywordTypeid, TokenKind::Keyword),
+        "static_assert" | "_Static_assert" => (TokenType::KeywordStaticAssert, TokenKind::Keyword),
+        "_Generic" => (TokenType::KeywordGeneric, TokenKind::Keyword),
+        "alignof" | "_Alignof" => (TokenType::KeywordAlignof, TokenKind::UnaryOperators),
+        "static_cast" => (TokenType::KeywordStaticCast, TokenKind::Keyword),
+        "const_cast" => (TokenType::KeywordConstCast, TokenKind::Keyword),
+        "dynamic_cast" => (TokenType::KeywordDynamicCast, TokenKind::Keyword),
+        "reinterpret_cast" => (TokenType::KeywordReinterpretCast, TokenKind::Keyword),
+        "extern" => (TokenType::KeywordExtern, TokenKind::Modifiers),
+        "inline" => (TokenType::KeywordInline, TokenKind::Modifiers),
+        "register" => (TokenType::KeywordRegister, TokenKind::Modifiers),
+        "volatile" => (TokenType::KeywordVolatile, TokenKind::Modifiers),
+        "restrict" => (TokenType::KeywordRestrict, TokenKind::Modifiers),
+        "constexpr" => (TokenType::KeywordConstexpr, TokenKind::Modifiers),
+        "mutable" => (TokenType::KeywordMutable, TokenKind::Modifiers),
+        "virtual" => (TokenType::KeywordVirtual, TokenKind::Modifiers),
+        "explicit" => (TokenType::KeywordExplicit, TokenKind::Modifiers),
+        "thread_local" | "_Thread_local" => (TokenType::KeywordThreadLocal, TokenKind::Modifiers),
+        "_Noreturn" => (TokenType::KeywordNoreturn, TokenKind::Modifiers),
+        "_Atomic" => (TokenType::KeywordAtomic, TokenKind::Modifiers),
+        "alignas" | "_Alignas" => (TokenType::KeywordAlignas, TokenKind::Modifiers),
+        "_Complex" => (TokenType::KeywordComplex, TokenKind::Modifiers),
+        "_Imaginary" => (TokenType::KeywordImaginary, TokenKind::Modifiers),
+        //alternative tokens of c++ and <iso646.h>
+        "and" => (TokenType::LogicalAnd, TokenKind::BinaryOperators),
+        "or" => (TokenType::LogicalOr, TokenKind::BinaryOperators),
+        "not" => (TokenType::LogicalNot, TokenKind::UnaryOperators),
+        "not_eq" => (TokenType::NotEqual, TokenKind::BinaryOperators),
+        "bitand" => (TokenType::BitwiseAnd, TokenKind::BinaryOperators),
+        "bitor" => (TokenType::BitwiseOr, TokenKind::BinaryOperators),
+        "compl" => (TokenType::BitwiseNegate, TokenKind::UnaryOperators),
+        "xor" => (TokenType::BitwiseXor, TokenKind::BinaryOperators),
+        "and_eq" => (TokenType::AndEqual, TokenKind::AssignmentOperators),
+        "or_eq" => (TokenType::OrEqual, TokenKind::AssignmentOperators),
+        "xor_eq" => (TokenType::XorEqual, TokenKind::AssignmentOperators),
         _ => (TokenType::Identifier, TokenKind::Identifiers),
     }
 }
+
+/// characters outside the source character set of c/c++,
+/// they are allowed in literals and comments only
+pub fn is_invalid_char(c: char) -> bool {
+    !c.is_ascii() || (c.is_ascii_control() && !c.is_ascii_whitespace()) || matches!(c, '@' | '`')
+}
@@ -1,2 +1,3 @@
+pub mod error;
 mod helper;
 pub mod tokenizer;
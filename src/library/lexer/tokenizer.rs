@@ -1,84 +1,78 @@
 use std::str::Chars;
 
 use crate::library::lexeme::definition::{TokenKind, TokenType, BLACK_HOLE};
+use crate::library::lexeme::literal::{decode, Prefix};
+use crate::library::lexeme::number::Number;
+use crate::library::lexeme::span::{Position, Span};
 use crate::library::lexeme::token::Token;
+use crate::library::lexer::error::{LexError, LexErrors};
 use crate::library::lexer::helper::*;
 
 pub struct Tokenizer<'a> {
-    line_no: u32,
     id: u32,
+    // where current_char was read from
+    location: Position,
+    // where the character after current_char will be read from
+    next_location: Position,
+    // span of the characters collected in token
+    token_start: Position,
+    token_end: Position,
     position: usize,
     current_char: char,
     token: Vec<char>,
     length: usize,
     input: Chars<'a>,
     pub token_buffer: Vec<Token>,
+    errors: Vec<LexError>,
 }
 
 impl<'a> Tokenizer<'a> {
-    pub fn new(text: &str) -> Tokenizer {
+    pub fn new(text: &str) -> Tokenizer<'_> {
         let token_vec: Vec<char> = Vec::new();
         let token_stream: Vec<Token> = Vec::new();
 
         Tokenizer {
             position: 0,
             id: 0,
-            line_no: 0,
+            location: Position::first(),
+            next_location: Position::first(),
+            token_start: Position::first(),
+            token_end: Position::first(),
             current_char: BLACK_HOLE,
-            length: text.len(),
+            length: text.chars().count(),
             token: token_vec,
             token_buffer: token_stream,
             input: text.chars(),
+            errors: Vec::new(),
         }
     }
 
-    ///increment the line number
-    fn new_line(&mut self) {
-        self.line_no += 1;
-    }
-
     /// Walks over given text and returns the stream of tokens
     /// We will take ownership of Tokenizer, as we wont be needing after.
-    pub fn tokenize(mut self) -> Vec<Token> {
+    /// Lexical errors do not stop the tokenizer, all of them are returned
+    /// along with the tokens read around them
+    pub fn tokenize(mut self) -> Result<Vec<Token>, LexErrors> {
         self.current_char = self.get_next_char();
         loop {
             match self.current_char {
-                '\n' => {
-                    self.new_line();
-                    self.current_char = self.get_next_char()
-                }
-
-                ' ' | '\t' => {
+                ' ' | '\t' | '\n' | '\r' | '\x0b' | '\x0c' => {
                     self.current_char = self.get_next_char();
                 }
 
-                '"' => {
-                    self.push_advance();
+                '"' | '\'' => self.push_text(Prefix::default()),
 
-                    while self.current_char != '"' {
-                        self.push_advance();
-                        if self.current_char == '\\' {
-                            self.push_advance();
-                            self.push_advance();
-                        }
+                '\\' => {
+                    // backslash at the end of a line splices it with the next one
+                    let span = Span::new(self.location, self.next_location);
+                    self.current_char = self.get_next_char();
+                    if self.current_char == '\r' && self.input.clone().next() == Some('\n') {
+                        self.current_char = self.get_next_char();
                     }
-
-                    self.push_advance();
-                    self.push_to_tok_buffer(TokenType::StringValue, TokenKind::Values);
-                }
-
-                '\'' => {
-                    self.push_advance();
-
-                    while self.current_char != '\'' {
-                        if self.current_char == '\\' {
-                            self.push_advance();
-                        }
-                        self.push_advance();
+                    if self.current_char == '\n' {
+                        self.current_char = self.get_next_char();
+                    } else {
+                        self.error("stray `\\` in program".to_string(), span);
                     }
-
-                    self.push_advance();
-                    self.push_to_tok_buffer(TokenType::CharValue, TokenKind::Values);
                 }
 
                 '{' => {
@@ -115,10 +109,36 @@ impl<'a> Tokenizer<'a> {
                     self.push_advance();
                     match self.current_char {
                         '<' => {
+                            self.push_advance();
+                            if self.current_char == '=' {
+                                self.push_advance();
+                                self.push_to_tok_buffer(
+                                    TokenType::LeftShiftEqual,
+                                    TokenKind::AssignmentOperators,
+                                );
+                            } else {
+                                self.push_to_tok_buffer(
+                                    TokenType::BitwiseLeftShift,
+                                    TokenKind::BinaryOperators,
+                                );
+                            }
+                        }
+
+                        // digraph <: unless it begins <::, as in vector<::A>
+                        ':' if !self.is_less_scope() => {
                             self.push_advance();
                             self.push_to_tok_buffer(
-                                TokenType::BitwiseLeftShift,
-                                TokenKind::BinaryOperators,
+                                TokenType::LeftSquareBracket,
+                                TokenKind::SpecialChars,
+                            );
+                        }
+
+                        // digraph <%
+                        '%' => {
+                            self.push_advance();
+                            self.push_to_tok_buffer(
+                                TokenType::LeftCurlyBrace,
+                                TokenKind::SpecialChars,
                             );
                         }
 
@@ -144,10 +164,18 @@ impl<'a> Tokenizer<'a> {
                     match self.current_char {
                         '>' => {
                             self.push_advance();
-                            self.push_to_tok_buffer(
-                                TokenType::BitwiseRightShift,
-                                TokenKind::BinaryOperators,
-                            );
+                            if self.current_char == '=' {
+                                self.push_advance();
+                                self.push_to_tok_buffer(
+                                    TokenType::RightShiftEqual,
+                                    TokenKind::AssignmentOperators,
+                                );
+                            } else {
+                                self.push_to_tok_buffer(
+                                    TokenType::BitwiseRightShift,
+                                    TokenKind::BinaryOperators,
+                                );
+                            }
                         }
 
                         '=' => {
@@ -201,33 +229,41 @@ impl<'a> Tokenizer<'a> {
                     //                            }
                     //                        }
                     //                    }
-                    let (token_type, base_type) = identify_token_type(&self.token);
-                    self.push_to_tok_buffer(token_type, base_type);
+                    // L"..", u8R"(..)", U'.'
+                    let text: String = self.token.iter().collect();
+                    match Prefix::parse(&text) {
+                        Some(prefix) if self.current_char == '"' => self.push_text(prefix),
+                        Some(prefix) if self.current_char == '\'' && !prefix.raw => {
+                            self.push_text(prefix)
+                        }
+                        _ => {
+                            let (token_type, base_type) = identify_token_type(&self.token);
+                            self.push_to_tok_buffer(token_type, base_type);
+                        }
+                    }
                 }
 
-                '0'..='9' => {
-                    self.push_advance();
-                    let mut is_int: bool = true;
+                '0'..='9' => self.push_number(),
 
-                    loop {
-                        match self.current_char {
-                            '0'..='9' => {
-                                self.push_advance();
-                            }
-                            '.' => {
-                                self.push_advance();
-                                is_int = false;
-                            }
-                            _ => {
-                                break;
-                            }
-                        };
-                    }
-                    if is_int {
-                        self.push_to_tok_buffer(TokenType::NumberInteger, TokenKind::Values);
-                    } else {
-                        self.push_to_tok_buffer(TokenType::NumberFloat, TokenKind::Values);
-                    }
+                // .5
+                '.' if matches!(self.input.clone().next(), Some('0'..='9')) => self.push_number(),
+
+                '.' => {
+                    self.push_advance();
+                    match self.current_char {
+                        '.' if self.input.clone().next() == Some('.') => {
+                            self.push_advance();
+                            self.push_advance();
+                            self.push_to_tok_buffer(TokenType::Ellipsis, TokenKind::SpecialChars);
+                        }
+                        '*' => {
+                            self.push_advance();
+                            self.push_to_tok_buffer(TokenType::DotStar, TokenKind::SpecialChars);
+                        }
+                        _ => {
+                            self.push_to_tok_buffer(TokenType::Dot, TokenKind::SpecialChars);
+                        }
+                    };
                 }
 
                 '+' => {
@@ -274,7 +310,15 @@ impl<'a> Tokenizer<'a> {
                         }
                         '>' => {
                             self.push_advance();
-                            self.push_to_tok_buffer(TokenType::Arrow, TokenKind::SpecialChars);
+                            if self.current_char == '*' {
+                                self.push_advance();
+                                self.push_to_tok_buffer(
+                                    TokenType::ArrowStar,
+                                    TokenKind::SpecialChars,
+                                );
+                            } else {
+                                self.push_to_tok_buffer(TokenType::Arrow, TokenKind::SpecialChars);
+                            }
                         }
                         _ => {
                             self.push_to_tok_buffer(TokenType::Minus, TokenKind::BinaryOperators);
@@ -311,6 +355,32 @@ impl<'a> Tokenizer<'a> {
                                 TokenKind::AssignmentOperators,
                             );
                         }
+                        // digraphs %> and %:
+                        '>' => {
+                            self.push_advance();
+                            self.push_to_tok_buffer(
+                                TokenType::RightCurlyBrace,
+                                TokenKind::SpecialChars,
+                            );
+                        }
+                        ':' => {
+                            self.push_advance();
+                            // %:%: is ##
+                            let mut rest = self.input.clone();
+                            if self.current_char == '%' && rest.next() == Some(':') {
+                                self.push_advance();
+                                self.push_advance();
+                                self.push_to_tok_buffer(
+                                    TokenType::TokenPaste,
+                                    TokenKind::Preprocessors,
+                                );
+                            } else {
+                                self.push_to_tok_buffer(
+                                    TokenType::HeaderInclude,
+                                    TokenKind::Preprocessors,
+                                );
+                            }
+                        }
                         _ => {
                             self.push_to_tok_buffer(TokenType::Module, TokenKind::BinaryOperators);
                         }
@@ -322,6 +392,25 @@ impl<'a> Tokenizer<'a> {
                     self.push_to_tok_buffer(TokenType::BitwiseNegate, TokenKind::UnaryOperators);
                 }
 
+                '^' => {
+                    self.push_advance();
+                    match self.current_char {
+                        '=' => {
+                            self.push_advance();
+                            self.push_to_tok_buffer(
+                                TokenType::XorEqual,
+                                TokenKind::AssignmentOperators,
+                            );
+                        }
+                        _ => {
+                            self.push_to_tok_buffer(
+                                TokenType::BitwiseXor,
+                                TokenKind::BinaryOperators,
+                            );
+                        }
+                    };
+                }
+
                 '&' => {
                     self.push_advance();
                     match self.current_char {
@@ -332,6 +421,13 @@ impl<'a> Tokenizer<'a> {
                                 TokenKind::BinaryOperators,
                             );
                         }
+                        '=' => {
+                            self.push_advance();
+                            self.push_to_tok_buffer(
+                                TokenType::AndEqual,
+                                TokenKind::AssignmentOperators,
+                            );
+                        }
                         _ => {
                             //FIXME: could be address or bitwise operator
                             //this is ambiguous resolution, should be validated at the parser side,
@@ -354,6 +450,13 @@ impl<'a> Tokenizer<'a> {
                                 TokenKind::BinaryOperators,
                             );
                         }
+                        '=' => {
+                            self.push_advance();
+                            self.push_to_tok_buffer(
+                                TokenType::OrEqual,
+                                TokenKind::AssignmentOperators,
+                            );
+                        }
                         _ => {
                             self.push_to_tok_buffer(
                                 TokenType::BitwiseOr,
@@ -387,20 +490,23 @@ impl<'a> Tokenizer<'a> {
                     match self.current_char {
                         '*' => {
                             // start of multi line comment
+                            self.push_advance();
                             loop {
+                                if self.at_end() {
+                                    self.close_unterminated("block comment", "*/");
+                                    break;
+                                }
+                                let c = self.current_char;
                                 self.push_advance();
-                                if self.current_char == '*' {
+                                if c == '*' && self.current_char == '/' {
                                     self.push_advance();
-                                    if self.current_char == '/' {
-                                        self.push_advance();
-                                        self.push_to_tok_buffer(
-                                            TokenType::MultilineComment,
-                                            TokenKind::Comments,
-                                        );
-                                        break;
-                                    }
+                                    break;
                                 }
                             }
+                            self.push_to_tok_buffer(
+                                TokenType::MultilineComment,
+                                TokenKind::Comments,
+                            );
                         }
 
                         '/' => {
@@ -413,7 +519,6 @@ impl<'a> Tokenizer<'a> {
                                             TokenKind::Comments,
                                         );
                                         self.current_char = self.get_next_char();
-                                        self.new_line();
                                         break;
                                     }
 
@@ -451,6 +556,14 @@ impl<'a> Tokenizer<'a> {
                                 TokenKind::SpecialChars,
                             );
                         }
+                        // digraph :>
+                        '>' => {
+                            self.push_advance();
+                            self.push_to_tok_buffer(
+                                TokenType::RightSquareBracket,
+                                TokenKind::SpecialChars,
+                            );
+                        }
                         _ => {
                             self.push_to_tok_buffer(TokenType::Colon, TokenKind::SpecialChars);
                         }
@@ -463,13 +576,27 @@ impl<'a> Tokenizer<'a> {
                 }
                 '#' => {
                     self.push_advance();
-                    self.push_to_tok_buffer(TokenType::HeaderInclude, TokenKind::Preprocessors);
+                    if self.current_char == '#' {
+                        self.push_advance();
+                        self.push_to_tok_buffer(TokenType::TokenPaste, TokenKind::Preprocessors);
+                    } else {
+                        self.push_to_tok_buffer(TokenType::HeaderInclude, TokenKind::Preprocessors);
+                    }
                 }
                 '?' => {
                     self.push_advance();
                     self.push_to_tok_buffer(TokenType::TernaryOpetator, TokenKind::BinaryOperators);
                 }
 
+                c if is_invalid_char(c) && !self.at_end() => {
+                    let span = Span::new(self.location, self.next_location);
+                    self.error(
+                        format!("invalid character `{}` in program", c.escape_debug()),
+                        span,
+                    );
+                    self.current_char = self.get_next_char();
+                }
+
                 _ => {
                     self.push_advance();
                     self.push_to_tok_buffer(TokenType::Others, TokenKind::None);
@@ -483,27 +610,200 @@ impl<'a> Tokenizer<'a> {
 
         //as we own the self, we can return the ownership of token_buffer to the caller.
         //All we want from here is the tokens
-        self.token_buffer
+        if self.errors.is_empty() {
+            Ok(self.token_buffer)
+        } else {
+            Err(LexErrors {
+                errors: self.errors,
+                tokens: self.token_buffer,
+            })
+        }
+    }
+
+    /// true once every character of the input has been read
+    fn at_end(&self) -> bool {
+        self.position > self.length
+    }
+
+    /// `<::` after `<`, which is `<` `::` unless followed by `:` or `>`
+    fn is_less_scope(&self) -> bool {
+        let mut rest = self.input.clone();
+        rest.next() == Some(':') && !matches!(rest.next(), Some(':') | Some('>'))
+    }
+
+    fn error(&mut self, message: String, span: Span) {
+        self.errors.push(LexError::new(message, span));
+    }
+
+    /**
+     * push_number:
+     * pushes a numeric literal, the characters are collected as
+     * a preprocessing number first, then checked to be a valid literal
+     */
+    fn push_number(&mut self) {
+        let mut previous = BLACK_HOLE;
+        loop {
+            let c = self.current_char;
+            let hex = self.token.len() > 1 && (self.token[1] == 'x' || self.token[1] == 'X');
+            match c {
+                '0'..='9' | 'a'..='z' | 'A'..='Z' | '_' | '.' => {}
+                '+' | '-' if matches!(previous, 'e' | 'E') && !hex => {}
+                '+' | '-' if matches!(previous, 'p' | 'P') && hex => {}
+                // digit separator, 1'000'000
+                '\'' if previous.is_ascii_alphanumeric()
+                    && matches!(self.input.clone().next(), Some(c) if c.is_ascii_alphanumeric()) => {
+                }
+                _ => break,
+            }
+            previous = c;
+            self.push_advance();
+        }
+
+        let text: String = self.token.iter().collect();
+        match Number::parse(&text) {
+            Ok((token_type, number)) => self.push_token(token_type, TokenKind::Values, |token| {
+                token.with_number(number)
+            }),
+            Err(message) => {
+                let span = Span::new(self.token_start, self.token_end);
+                self.error(message, span);
+                self.push_to_tok_buffer(TokenType::NumberInteger, TokenKind::Values);
+            }
+        }
+    }
+
+    /**
+     * push_text:
+     * pushes a string or character literal, the prefix if any
+     * is already in the token and the head is at the opening quote
+     */
+    fn push_text(&mut self, prefix: Prefix) {
+        let errors = self.errors.len();
+        let (token_type, what) = match self.current_char {
+            '"' => (TokenType::StringValue, "string literal"),
+            _ => (TokenType::CharValue, "character literal"),
+        };
+        if prefix.raw {
+            self.push_raw_string();
+        } else {
+            self.push_literal(self.current_char, what);
+        }
+
+        // escapes are checked once the literal is read in full
+        let text: String = self.token.iter().collect();
+        if let (true, Err(message)) = (self.errors.len() == errors, decode(&text)) {
+            let span = Span::new(self.token_start, self.token_end);
+            self.error(message, span);
+        }
+        self.push_token(token_type, TokenKind::Values, |token| {
+            token.with_prefix(prefix)
+        });
+    }
+
+    /**
+     * push_raw_string:
+     * pushes R"delim(...)delim", which may span lines
+     */
+    fn push_raw_string(&mut self) {
+        self.push_advance();
+        let mut delimiter = String::new();
+        while self.current_char != '(' {
+            let c = self.current_char;
+            if self.at_end()
+                || delimiter.len() == 16
+                || matches!(c, ' ' | ')' | '\\' | '\t' | '\n' | '"')
+            {
+                let span = Span::new(self.location, self.next_location);
+                self.error("invalid delimiter in raw string literal".to_string(), span);
+                // read the rest of the line as an ordinary literal
+                while !self.at_end() && self.current_char != '\n' {
+                    let c = self.current_char;
+                    self.push_advance();
+                    if c == '"' {
+                        break;
+                    }
+                }
+                return;
+            }
+            delimiter.push(c);
+            self.push_advance();
+        }
+        self.push_advance();
+
+        let close: Vec<char> = format!("){}\"", delimiter).chars().collect();
+        while !self.token.ends_with(&close) {
+            if self.at_end() {
+                let close: String = close.iter().collect();
+                self.close_unterminated("raw string literal", &close);
+                return;
+            }
+            self.push_advance();
+        }
+    }
+
+    /**
+     * push_literal:
+     * pushes a string or character literal, escapes included
+     * literals can not span lines, an unterminated one
+     * is closed at the end of its line
+     */
+    fn push_literal(&mut self, quote: char, what: &str) {
+        self.push_advance();
+        while self.current_char != quote {
+            if self.at_end() || self.current_char == '\n' {
+                self.close_unterminated(what, &quote.to_string());
+                return;
+            }
+            if self.current_char == '\\' {
+                self.push_advance();
+                if self.at_end() {
+                    continue;
+                }
+            }
+            self.push_advance();
+        }
+        self.push_advance();
+    }
+
+    /// reports the token being read as unterminated and closes it
+    fn close_unterminated(&mut self, what: &str, close: &str) {
+        let span = Span::new(self.token_start, self.token_end);
+        self.error(format!("unterminated {}", what), span);
+        self.token.extend(close.chars());
     }
 
     /// Returns the next char in a input stream pointed by `pos` position
     /// null (\0) otherwise
+    /// Keeps track of the location of the returned character
     fn get_next_char(&mut self) -> char {
         self.position += 1;
-        if let Some(ch) = self.input.next() {
-            ch
-        } else {
-            '\0'
+        self.location = self.next_location;
+        match self.input.next() {
+            Some(c) => {
+                self.next_location = self.location.advance(c);
+                c
+            }
+            None => '\0',
         }
     }
 
     /// Creates a Token from current token and pushes into Token buffer.
     /// Clear the current token
     fn push_to_tok_buffer(&mut self, token_type: TokenType, token_kind: TokenKind) {
+        self.push_token(token_type, token_kind, |token| token);
+    }
+
+    /// push_to_tok_buffer, with what the tokenizer has learned about a literal
+    fn push_token<F>(&mut self, token_type: TokenType, token_kind: TokenKind, with: F)
+    where
+        F: FnOnce(Token) -> Token,
+    {
         let token: String = self.token.iter().cloned().collect();
         if !token.is_empty() {
-            let token = Token::new(token, token_kind, token_type, self.line_no, self.id);
-            self.token_buffer.push(token);
+            let span = Span::new(self.token_start, self.token_end);
+            let token =
+                Token::new(token, token_kind, token_type, span.start.line, self.id).with_span(span);
+            self.token_buffer.push(with(token));
             self.id += 1;
         }
         self.token.clear();
@@ -513,6 +813,10 @@ impl<'a> Tokenizer<'a> {
     /// pushes the current character into self.token
     /// updates the current_char with next character
     fn push_advance(&mut self) {
+        if self.token.is_empty() {
+            self.token_start = self.location;
+        }
+        self.token_end = self.next_location;
         self.token.push(self.current_char);
         self.current_char = self.get_next_char();
     }
@@ -521,11 +825,13 @@ impl<'a> Tokenizer<'a> {
 #[cfg(test)]
 mod test {
     use super::Tokenizer;
+    use crate::library::lexeme::literal::Encoding;
+    use crate::library::lexeme::number::Radix;
     use crate::library::lexeme::{definition::TokenKind, definition::TokenType, token::Token};
 
     #[test]
     fn test_get_next_char() {
-        let get_next_char = |x: &str| Tokenizer::new(&x).get_next_char();
+        let get_next_char = |x: &str| Tokenizer::new(x).get_next_char();
 
         assert_eq!('\0', get_next_char(""));
         assert_eq!(' ', get_next_char(" "));
@@ -595,7 +901,7 @@ mod test {
         assert_eq!(tok.token_buffer[0].get_token_type(), TokenType::Identifier);
         assert_eq!(tok.token_buffer[0].get_token_kind(), TokenKind::Identifiers);
         assert_eq!(tok.token_buffer[0].get_token_value(), String::from("a"));
-        assert_eq!(tok.token_buffer[0].get_token_line_num(), 0);
+        assert_eq!(tok.token_buffer[0].get_token_line_num(), 1);
         assert_eq!(0, tok.token.len());
 
         tok.push_advance();
@@ -606,7 +912,7 @@ mod test {
             TokenKind::AssignmentOperators
         );
         assert_eq!(tok.token_buffer[1].get_token_value(), String::from("="));
-        assert_eq!(tok.token_buffer[1].get_token_line_num(), 0);
+        assert_eq!(tok.token_buffer[1].get_token_line_num(), 1);
 
         tok.push_advance();
         tok.push_advance();
@@ -615,612 +921,850 @@ mod test {
         assert_eq!(tok.token_buffer[2].get_token_type(), TokenType::StringValue);
         assert_eq!(tok.token_buffer[2].get_token_kind(), TokenKind::Values);
         assert_eq!(tok.token_buffer[2].get_token_value(), String::from("\"2\""));
-        assert_eq!(tok.token_buffer[2].get_token_line_num(), 0);
+        assert_eq!(tok.token_buffer[2].get_token_line_num(), 1);
         assert_eq!(0, tok.token.len());
     }
 
     #[test]
     fn test_tokenize_keywords() {
         let text ="signed\n unsigned\n class\n new\n while\n for\n do\n break\n continue\n switch\n if\n else\n public\n private\n protected\n case\n static\n const\n default\n return\n";
-        let tok = Tokenizer::new(&text);
+        let tok = Tokenizer::new(text);
         let tok_vector = vec![
             Token::new(
                 String::from("signed"),
                 TokenKind::Modifiers,
                 TokenType::Signed,
-                0,
+                1,
                 0,
             ),
             Token::new(
                 String::from("unsigned"),
                 TokenKind::Modifiers,
                 TokenType::Unsigned,
-                1,
+                2,
                 1,
             ),
             Token::new(
                 String::from("class"),
                 TokenKind::Keyword,
                 TokenType::KeywordClass,
-                2,
+                3,
                 2,
             ),
             Token::new(
                 String::from("new"),
                 TokenKind::Keyword,
                 TokenType::KeywordNew,
-                3,
+                4,
                 3,
             ),
             Token::new(
                 String::from("while"),
                 TokenKind::Keyword,
                 TokenType::KeywordWhile,
-                4,
+                5,
                 4,
             ),
             Token::new(
                 String::from("for"),
                 TokenKind::Keyword,
                 TokenType::KeywordFor,
-                5,
+                6,
                 5,
             ),
             Token::new(
                 String::from("do"),
                 TokenKind::Keyword,
                 TokenType::KeywordDo,
-                6,
+                7,
                 6,
             ),
             Token::new(
                 String::from("break"),
                 TokenKind::Keyword,
                 TokenType::KeywordBreak,
-                7,
+                8,
                 7,
             ),
             Token::new(
                 String::from("continue"),
                 TokenKind::Keyword,
                 TokenType::KeywordContinue,
-                8,
+                9,
                 8,
             ),
             Token::new(
                 String::from("switch"),
                 TokenKind::Keyword,
                 TokenType::KeywordSwitch,
-                9,
+                10,
                 9,
             ),
             Token::new(
                 String::from("if"),
                 TokenKind::Keyword,
                 TokenType::KeywordIf,
-                10,
+                11,
                 10,
             ),
             Token::new(
                 String::from("else"),
                 TokenKind::Keyword,
                 TokenType::KeywordElse,
-                11,
+                12,
                 11,
             ),
             Token::new(
                 String::from("public"),
                 TokenKind::Modifiers,
                 TokenType::KeywordPublic,
-                12,
+                13,
                 12,
             ),
             Token::new(
                 String::from("private"),
                 TokenKind::Modifiers,
                 TokenType::keywordPrivate,
-                13,
+                14,
                 13,
             ),
             Token::new(
                 String::from("protected"),
                 TokenKind::Modifiers,
                 TokenType::KeywordProtected,
-                14,
+                15,
                 14,
             ),
             Token::new(
                 String::from("case"),
                 TokenKind::Keyword,
                 TokenType::KeywordCase,
-                15,
+                16,
                 15,
             ),
             Token::new(
                 String::from("static"),
                 TokenKind::Modifiers,
                 TokenType::KeywordStatic,
-                16,
+                17,
                 16,
             ),
             Token::new(
                 String::from("const"),
                 TokenKind::Modifiers,
                 TokenType::KeywordConst,
-                17,
+                18,
                 17,
             ),
             Token::new(
                 String::from("default"),
                 TokenKind::Keyword,
                 TokenType::KeywordDefault,
-                18,
+                19,
                 18,
             ),
             Token::new(
                 String::from("return"),
                 TokenKind::Keyword,
                 TokenType::KeywordReturn,
-                19,
+                20,
                 19,
             ),
         ];
-        assert_eq!(tok_vector, tok.tokenize());
+        assert_eq!(tok_vector, tok.tokenize().unwrap());
     }
 
     #[test]
     fn test_tokenize_types() {
         let text = "int\n short\n long\n float\n double\n char\n bool\n void\n typedef\n";
-        let tok = Tokenizer::new(&text);
+        let tok = Tokenizer::new(text);
         let tok_vector = vec![
             Token::new(
                 String::from("int"),
                 TokenKind::DataTypes,
                 TokenType::Integer,
-                0,
+                1,
                 0,
             ),
             Token::new(
                 String::from("short"),
                 TokenKind::DataTypes,
                 TokenType::Short,
-                1,
+                2,
                 1,
             ),
             Token::new(
                 String::from("long"),
                 TokenKind::DataTypes,
                 TokenType::Long,
-                2,
+                3,
                 2,
             ),
             Token::new(
                 String::from("float"),
                 TokenKind::DataTypes,
                 TokenType::Float,
-                3,
+                4,
                 3,
             ),
             Token::new(
                 String::from("double"),
                 TokenKind::DataTypes,
                 TokenType::Double,
-                4,
+                5,
                 4,
             ),
             Token::new(
                 String::from("char"),
                 TokenKind::DataTypes,
                 TokenType::Character,
-                5,
+                6,
                 5,
             ),
             Token::new(
                 String::from("bool"),
                 TokenKind::DataTypes,
                 TokenType::Boolean,
-                6,
+                7,
                 6,
             ),
             Token::new(
                 String::from("void"),
                 TokenKind::DataTypes,
                 TokenType::Void,
-                7,
+                8,
                 7,
             ),
             Token::new(
                 String::from("typedef"),
                 TokenKind::Typedef,
                 TokenType::Typedef,
-                8,
+                9,
                 8,
             ),
         ];
-        assert_eq!(tok_vector, tok.tokenize());
+        assert_eq!(tok_vector, tok.tokenize().unwrap());
     }
 
     #[test]
     fn test_tokenize_comments() {
         let text = "// Hello World\n/** hello\n * world\n */\n// Goodbye";
         //read_file("src/test/resources/tokenize_comments.cpp");
-        let tok = Tokenizer::new(&text);
+        let tok = Tokenizer::new(text);
         let tok_vector = vec![
             Token::new(
                 String::from("// Hello World"),
                 TokenKind::Comments,
                 TokenType::SingleLineComment,
-                0,
+                1,
                 0,
             ),
             Token::new(
                 String::from("/** hello\n * world\n */"),
                 TokenKind::Comments,
                 TokenType::MultilineComment,
-                1,
+                2,
                 1,
             ),
             Token::new(
                 String::from("// Goodbye"),
                 TokenKind::Comments,
                 TokenType::SingleLineComment,
-                2,
+                5,
                 2,
             ),
         ];
-        assert_eq!(tok_vector, tok.tokenize(), "returns token of comments");
+        assert_eq!(
+            tok_vector,
+            tok.tokenize().unwrap(),
+            "returns token of comments"
+        );
     }
 
     #[test]
     fn test_tokenize_operators() {
         let text = "++\n --\n ~\n !\n +\n -\n /\n *\n %\n >\n >=\n >>\n <\n <=\n <<\n ==\n !=\n &\n &&\n |\n ||\n =\n +=\n -=\n /=\n %=\n ->\n ::\n ?\n";
-        let tok = Tokenizer::new(&text);
+        let tok = Tokenizer::new(text);
         let tok_vector = vec![
             Token::new(
                 String::from("++"),
                 TokenKind::UnaryOperators,
                 TokenType::Increment,
-                0,
+                1,
                 0,
             ),
             Token::new(
                 String::from("--"),
                 TokenKind::UnaryOperators,
                 TokenType::Decrement,
-                1,
+                2,
                 1,
             ),
             Token::new(
                 String::from("~"),
                 TokenKind::UnaryOperators,
                 TokenType::BitwiseNegate,
-                2,
+                3,
                 2,
             ),
             Token::new(
                 String::from("!"),
                 TokenKind::UnaryOperators,
                 TokenType::LogicalNot,
-                3,
+                4,
                 3,
             ),
             Token::new(
                 String::from("+"),
                 TokenKind::BinaryOperators,
                 TokenType::Plus,
-                4,
+                5,
                 4,
             ),
             Token::new(
                 String::from("-"),
                 TokenKind::BinaryOperators,
                 TokenType::Minus,
-                5,
+                6,
                 5,
             ),
             Token::new(
                 String::from("/"),
                 TokenKind::BinaryOperators,
                 TokenType::Divide,
-                6,
+                7,
                 6,
             ),
             Token::new(
                 String::from("*"),
                 TokenKind::BinaryOperators,
                 TokenType::Multiplication,
-                7,
+                8,
                 7,
             ),
             Token::new(
                 String::from("%"),
                 TokenKind::BinaryOperators,
                 TokenType::Module,
-                8,
+                9,
                 8,
             ),
             Token::new(
                 String::from(">"),
                 TokenKind::BinaryOperators,
                 TokenType::GreaterThan,
-                9,
+                10,
                 9,
             ),
             Token::new(
                 String::from(">="),
                 TokenKind::BinaryOperators,
                 TokenType::GreaterThanOrEqual,
-                10,
+                11,
                 10,
             ),
             Token::new(
                 String::from(">>"),
                 TokenKind::BinaryOperators,
                 TokenType::BitwiseRightShift,
-                11,
+                12,
                 11,
             ),
             Token::new(
                 String::from("<"),
                 TokenKind::BinaryOperators,
                 TokenType::LessThan,
-                12,
+                13,
                 12,
             ),
             Token::new(
                 String::from("<="),
                 TokenKind::BinaryOperators,
                 TokenType::LessThanOrEqual,
-                13,
+                14,
                 13,
             ),
             Token::new(
                 String::from("<<"),
                 TokenKind::BinaryOperators,
                 TokenType::BitwiseLeftShift,
-                14,
+                15,
                 14,
             ),
             Token::new(
                 String::from("=="),
                 TokenKind::BinaryOperators,
                 TokenType::Equal,
-                15,
+                16,
                 15,
             ),
             Token::new(
                 String::from("!="),
                 TokenKind::BinaryOperators,
                 TokenType::NotEqual,
-                16,
+                17,
                 16,
             ),
             Token::new(
                 String::from("&"),
                 TokenKind::BinaryOperators,
                 TokenType::BitwiseAnd,
-                17,
+                18,
                 17,
             ),
             Token::new(
                 String::from("&&"),
                 TokenKind::BinaryOperators,
                 TokenType::LogicalAnd,
-                18,
+                19,
                 18,
             ),
             Token::new(
                 String::from("|"),
                 TokenKind::BinaryOperators,
                 TokenType::BitwiseOr,
-                19,
+                20,
                 19,
             ),
             Token::new(
                 String::from("||"),
                 TokenKind::BinaryOperators,
                 TokenType::LogicalOr,
-                20,
+                21,
                 20,
             ),
             Token::new(
                 String::from("="),
                 TokenKind::AssignmentOperators,
                 TokenType::Assignment,
-                21,
+                22,
                 21,
             ),
             Token::new(
                 String::from("+="),
                 TokenKind::AssignmentOperators,
                 TokenType::PlusEqual,
-                22,
+                23,
                 22,
             ),
             Token::new(
                 String::from("-="),
                 TokenKind::AssignmentOperators,
                 TokenType::MinusEqual,
-                23,
+                24,
                 23,
             ),
             Token::new(
                 String::from("/="),
                 TokenKind::AssignmentOperators,
                 TokenType::DivideEqual,
-                24,
+                25,
                 24,
             ),
             Token::new(
                 String::from("%="),
                 TokenKind::AssignmentOperators,
                 TokenType::ModuleEqual,
-                25,
+                26,
                 25,
             ),
             Token::new(
                 String::from("->"),
                 TokenKind::SpecialChars,
                 TokenType::Arrow,
-                26,
+                27,
                 26,
             ),
             Token::new(
                 String::from("::"),
                 TokenKind::SpecialChars,
                 TokenType::ScopeResolution,
-                27,
+                28,
                 27,
             ),
             Token::new(
                 String::from("?"),
                 TokenKind::BinaryOperators,
                 TokenType::TernaryOpetator,
-                28,
+                29,
                 28,
             ),
         ];
-        debug_assert_eq!(tok_vector, tok.tokenize());
+        debug_assert_eq!(tok_vector, tok.tokenize().unwrap());
     }
 
     #[test]
     fn test_tokenize_punctuations() {
         let text = "{\n }\n (\n)\n [\n ]\n :\n ;\n ,\n";
-        let tok = Tokenizer::new(&text);
+        let tok = Tokenizer::new(text);
         let tok_vector = vec![
             Token::new(
                 String::from("{"),
                 TokenKind::SpecialChars,
                 TokenType::LeftCurlyBrace,
-                0,
+                1,
                 0,
             ),
             Token::new(
                 String::from("}"),
                 TokenKind::SpecialChars,
                 TokenType::RightCurlyBrace,
-                1,
+                2,
                 1,
             ),
             Token::new(
                 String::from("("),
                 TokenKind::SpecialChars,
                 TokenType::LeftBracket,
-                2,
+                3,
                 2,
             ),
             Token::new(
                 String::from(")"),
                 TokenKind::SpecialChars,
                 TokenType::RightBracket,
-                3,
+                4,
                 3,
             ),
             Token::new(
                 String::from("["),
                 TokenKind::SpecialChars,
                 TokenType::LeftSquareBracket,
-                4,
+                5,
                 4,
             ),
             Token::new(
                 String::from("]"),
                 TokenKind::SpecialChars,
                 TokenType::RightSquareBracket,
-                5,
+                6,
                 5,
             ),
             Token::new(
                 String::from(":"),
                 TokenKind::SpecialChars,
                 TokenType::Colon,
-                6,
+                7,
                 6,
             ),
             Token::new(
                 String::from(";"),
                 TokenKind::SpecialChars,
                 TokenType::Semicolon,
-                7,
+                8,
                 7,
             ),
             Token::new(
                 String::from(","),
                 TokenKind::SpecialChars,
                 TokenType::Comma,
-                8,
+                9,
                 8,
             ),
         ];
-        assert_eq!(tok_vector, tok.tokenize());
+        assert_eq!(tok_vector, tok.tokenize().unwrap());
     }
 
     #[test]
     fn test_tokenize_values() {
         let text = "\"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ`1234567890-=[]\\;\',./~!@#$%^&*()_+{}|:\\\"<>?\\\"\'\"\n'a'\n\'\\\'\'\n\'\\\"\'\n'\\\\'\n 1234567890\n1234567890.0987654321\ntrue\nfalse\n";
-        let tok = Tokenizer::new(&text);
+        let tok = Tokenizer::new(text);
         let tok_vector = vec![
             Token::new(String::from("\"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ`1234567890-=[]\\;\',./~!@#$%^&*()_+{}|:\\\"<>?\\\"\'\""),
                        TokenKind::Values,
                        TokenType::StringValue,
-                       0,
+                       1,
                        0),
-            Token::new(String::from("'a'"), TokenKind::Values, TokenType::CharValue, 1, 1),
-            Token::new(String::from("\'\\\'\'"), TokenKind::Values, TokenType::CharValue, 2, 2),
-            Token::new(String::from("\'\\\"\'"), TokenKind::Values, TokenType::CharValue, 3, 3),
-            Token::new(String::from("\'\\\\\'"), TokenKind::Values, TokenType::CharValue, 4, 4),
-            Token::new(String::from("1234567890"), TokenKind::Values, TokenType::NumberInteger, 5, 5),
+            Token::new(String::from("'a'"), TokenKind::Values, TokenType::CharValue, 2, 1),
+            Token::new(String::from("\'\\\'\'"), TokenKind::Values, TokenType::CharValue, 3, 2),
+            Token::new(String::from("\'\\\"\'"), TokenKind::Values, TokenType::CharValue, 4, 3),
+            Token::new(String::from("\'\\\\\'"), TokenKind::Values, TokenType::CharValue, 5, 4),
+            Token::new(String::from("1234567890"), TokenKind::Values, TokenType::NumberInteger, 6, 5),
             Token::new(String::from("1234567890.0987654321"),
                        TokenKind::Values,
                        TokenType::NumberFloat,
-                       6,
+                       7,
                        6),
-            Token::new(String::from("true"), TokenKind::Values, TokenType::True, 7, 7),
-            Token::new(String::from("false"), TokenKind::Values, TokenType::False, 8, 8)];
-        assert_eq!(tok_vector, tok.tokenize());
+            Token::new(String::from("true"), TokenKind::Values, TokenType::True, 8, 7),
+            Token::new(String::from("false"), TokenKind::Values, TokenType::False, 9, 8)];
+        assert_eq!(tok_vector, tok.tokenize().unwrap());
     }
 
     #[test]
     fn test_tokenize_ids() {
         let text = "_\n _1123abcd_deff04\n abcd_deff04_\n integer\n main\n";
-        let tok = Tokenizer::new(&text);
+        let tok = Tokenizer::new(text);
         let tok_vector = vec![
             Token::new(
                 String::from("_"),
                 TokenKind::Identifiers,
                 TokenType::Identifier,
-                0,
+                1,
                 0,
             ),
             Token::new(
                 String::from("_1123abcd_deff04"),
                 TokenKind::Identifiers,
                 TokenType::Identifier,
-                1,
+                2,
                 1,
             ),
             Token::new(
                 String::from("abcd_deff04_"),
                 TokenKind::Identifiers,
                 TokenType::Identifier,
-                2,
+                3,
                 2,
             ),
             Token::new(
                 String::from("integer"),
                 TokenKind::Identifiers,
                 TokenType::Identifier,
-                3,
+                4,
                 3,
             ),
             Token::new(
                 String::from("main"),
                 TokenKind::Identifiers,
                 TokenType::Main,
-                4,
+                5,
                 4,
             ),
         ];
-        assert_eq!(tok_vector, tok.tokenize());
+        assert_eq!(tok_vector, tok.tokenize().unwrap());
+    }
+
+    #[test]
+    fn test_that_tokens_carry_span() {
+        let tokens = Tokenizer::new(
+            "int a;
+  /* x
+ */ b = \"é\";",
+        )
+        .tokenize()
+        .unwrap();
+        let spans: Vec<(u32, u32, u32, u32)> = tokens
+            .iter()
+            .map(|t| {
+                let span = t.get_span();
+                (
+                    span.start.line,
+                    span.start.column,
+                    span.end.line,
+                    span.end.column,
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (1, 1, 1, 4),
+                (1, 5, 1, 6),
+                (1, 6, 1, 7),
+                (2, 3, 3, 4),
+                (3, 5, 3, 6),
+                (3, 7, 3, 8),
+                (3, 9, 3, 12),
+                (3, 12, 3, 13),
+            ]
+        );
+        // offsets are in bytes, `é` takes two
+        let string = tokens[6].get_span();
+        assert_eq!((string.start.offset, string.end.offset), (22, 26));
+        assert_eq!(tokens[6].get_token_line_num(), 3);
+    }
+
+    fn errors(text: &str) -> Vec<(String, u32, u32, u32)> {
+        let failure = Tokenizer::new(text).tokenize().unwrap_err();
+        failure
+            .errors
+            .iter()
+            .map(|e| {
+                let span = e.span;
+                (
+                    e.message.clone(),
+                    span.start.line,
+                    span.start.column,
+                    span.end.column,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_that_all_lexical_errors_are_collected() {
+        let text = "char *s = \"abc;\nint @x = 1;\nchar c = 'a;\n\\ y; /* end";
+        assert_eq!(
+            errors(text),
+            vec![
+                ("unterminated string literal".to_string(), 1, 11, 16),
+                ("invalid character `@` in program".to_string(), 2, 5, 6),
+                ("unterminated character literal".to_string(), 3, 10, 13),
+                ("stray `\\` in program".to_string(), 4, 1, 2),
+                ("unterminated block comment".to_string(), 4, 6, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_that_unterminated_tokens_are_closed() {
+        let failure = Tokenizer::new("s = \"ab\\\"c\nx; /* é")
+            .tokenize()
+            .unwrap_err();
+        let values: Vec<String> = failure.tokens.iter().map(|t| t.get_token_value()).collect();
+        assert_eq!(values, vec!["s", "=", "\"ab\\\"c\"", "x", ";", "/* é*/"]);
+    }
+
+    #[test]
+    fn test_that_backslash_newline_splices_lines() {
+        let tokens = Tokenizer::new("a \\\r\n+ \\\nb").tokenize().unwrap();
+        let values: Vec<String> = tokens.iter().map(|t| t.get_token_value()).collect();
+        assert_eq!(values, vec!["a", "+", "b"]);
+    }
+
+    #[test]
+    fn test_that_numeric_literals_are_single_tokens() {
+        let tokens = Tokenizer::new("x=0x1e+1'000'000-1e-9f*.5;0b12")
+            .tokenize()
+            .unwrap_err()
+            .tokens;
+        let values: Vec<(String, TokenType)> = tokens
+            .iter()
+            .map(|t| (t.get_token_value(), t.get_token_type()))
+            .collect();
+        let expected = [
+            ("x", TokenType::Identifier),
+            ("=", TokenType::Assignment),
+            ("0x1e", TokenType::NumberInteger),
+            ("+", TokenType::Plus),
+            ("1'000'000", TokenType::NumberInteger),
+            ("-", TokenType::Minus),
+            ("1e-9f", TokenType::NumberFloat),
+            ("*", TokenType::Multiplication),
+            (".5", TokenType::NumberFloat),
+            (";", TokenType::Semicolon),
+            ("0b12", TokenType::NumberInteger),
+        ];
+        let expected: Vec<(String, TokenType)> =
+            expected.iter().map(|&(v, t)| (v.to_string(), t)).collect();
+        assert_eq!(values, expected);
+        assert_eq!(
+            tokens[4].get_number().map(|n| n.radix),
+            Some(Radix::Decimal)
+        );
+    }
+
+    #[test]
+    fn test_that_prefixed_and_raw_literals_are_single_tokens() {
+        let tokens = Tokenizer::new("L\"a\" u8R\"x(b)\"\n)x\" U'c' R")
+            .tokenize()
+            .unwrap();
+        let values: Vec<(String, Option<Encoding>)> = tokens
+            .iter()
+            .map(|t| (t.get_token_value(), t.get_prefix().map(|p| p.encoding)))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("L\"a\"".to_string(), Some(Encoding::Wide)),
+                ("u8R\"x(b)\"\n)x\"".to_string(), Some(Encoding::Utf8)),
+                ("U'c'".to_string(), Some(Encoding::Utf32)),
+                ("R".to_string(), None),
+            ]
+        );
+        assert!(tokens[1].get_prefix().unwrap().raw);
+        assert_eq!(tokens[2].get_span().start.line, 2);
+    }
+
+    #[test]
+    fn test_that_c11_and_cpp17_keywords_have_token_types() {
+        let tokens = Tokenizer::new(
+            "namespace template typename virtual nullptr goto extern inline volatile \
+             constexpr try catch throw delete this operator friend explicit mutable \
+             _Bool wchar_t static_assert _Alignof override not_eq and",
+        )
+        .tokenize()
+        .unwrap();
+        let types: Vec<TokenType> = tokens.iter().map(|t| t.get_token_type()).collect();
+        assert_eq!(
+            types,
+            vec![
+                TokenType::KeywordNamespace,
+                TokenType::KeywordTemplate,
+                TokenType::KeywordTypename,
+                TokenType::KeywordVirtual,
+                TokenType::Null,
+                TokenType::KeywordGoto,
+                TokenType::KeywordExtern,
+                TokenType::KeywordInline,
+                TokenType::KeywordVolatile,
+                TokenType::KeywordConstexpr,
+                TokenType::KeywordTry,
+                TokenType::KeywordCatch,
+                TokenType::KeywordThrow,
+                TokenType::KeywordDelete,
+                TokenType::KeywordThis,
+                TokenType::KeywordOperator,
+                TokenType::KeywordFriend,
+                TokenType::KeywordExplicit,
+                TokenType::KeywordMutable,
+                TokenType::Boolean,
+                TokenType::WideChar,
+                TokenType::KeywordStaticAssert,
+                TokenType::KeywordAlignof,
+                // contextual, only a keyword after a declarator
+                TokenType::Identifier,
+                TokenType::NotEqual,
+                TokenType::LogicalAnd,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_that_every_punctuator_has_a_token_type() {
+        let tokens =
+            Tokenizer::new("^ ^= &= |= <<= >>= . ... .* ->* ::x xor_eq <: :> <% %> %: a<::b>")
+                .tokenize()
+                .unwrap();
+        let types: Vec<TokenType> = tokens.iter().map(|t| t.get_token_type()).collect();
+        assert_eq!(
+            types,
+            vec![
+                TokenType::BitwiseXor,
+                TokenType::XorEqual,
+                TokenType::AndEqual,
+                TokenType::OrEqual,
+                TokenType::LeftShiftEqual,
+                TokenType::RightShiftEqual,
+                TokenType::Dot,
+                TokenType::Ellipsis,
+                TokenType::DotStar,
+                TokenType::ArrowStar,
+                TokenType::ScopeResolution,
+                TokenType::Identifier,
+                TokenType::XorEqual,
+                TokenType::LeftSquareBracket,
+                TokenType::RightSquareBracket,
+                TokenType::LeftCurlyBrace,
+                TokenType::RightCurlyBrace,
+                TokenType::HeaderInclude,
+                TokenType::Identifier,
+                TokenType::LessThan,
+                TokenType::ScopeResolution,
+                TokenType::Identifier,
+                TokenType::GreaterThan,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_that_bad_escapes_and_raw_strings_are_reported() {
+        assert_eq!(
+            errors("a = \"\\x\";\nb = R\"(abc"),
+            vec![
+                ("\\x used with no following hex digits".to_string(), 1, 5, 9),
+                ("unterminated raw string literal".to_string(), 2, 5, 11),
+            ]
+        );
     }
 }
@@ -1,4 +1,9 @@
+pub mod ast;
+pub mod codegen;
+pub mod diagnostics;
 pub mod doc;
 pub mod lexeme;
 pub mod lexer;
 pub mod parser;
+pub mod preprocessor;
+pub mod project;
@@ -0,0 +1,38 @@
+use std::fmt;
+
+use crate::library::ast::TranslationUnit;
+use crate::library::diagnostics::Diagnostic;
+use crate::library::doc::DocType;
+use crate::library::lexeme::span::Span;
+
+/// A construct the parser could not read, `span` is the offending token
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl ParseError {
+    pub fn new(message: String, span: Span) -> ParseError {
+        ParseError { message, span }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(DocType::PARSE_ERROR, self.span).with_message(self.message.clone())
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.span.start, self.message)
+    }
+}
+
+/// Errors found while parsing, along with the translation unit
+/// recovered around them. Constructs which could not be parsed are
+/// kept in the unit as source text
+#[derive(Debug)]
+pub struct ParseErrors {
+    pub errors: Vec<ParseError>,
+    pub unit: TranslationUnit,
+}
@@ -1,55 +1,91 @@
 use crate::library::lexeme::definition::TokenType::*;
+use crate::library::lexeme::definition::{TokenKind, TokenType};
 use crate::library::lexeme::token::Token;
 
 /**
- * skip_stmt:
- * forwards the lookahead by one statement
- * returns the lookahead at the lexeme after the semi-colon
+ * is_type_specifier:
+ * true if the token can start or continue a type specifier
+ * int, unsigned, const, static ...
  */
-pub fn skip_stmt(lexeme: &Vec<Token>, mut lookahead: usize) -> usize {
-    while lexeme[lookahead].get_token_type() != Semicolon {
-        lookahead += 1;
-    }
-    lookahead + 1
+pub fn is_type_specifier(token: &Token) -> bool {
+    matches!(
+        token.get_type(),
+        (TokenKind::DataTypes, _)
+            | (_, Signed)
+            | (_, Unsigned)
+            | (_, KeywordConst)
+            | (_, KeywordStatic)
+            | (_, KeywordConstexpr)
+            | (_, KeywordTypename)
+            | (_, KeywordAlignas)
+    ) || is_ignored_specifier(token.get_token_type())
 }
 
 /**
- * skip_block:
- * forwards the lookahead by one block
- * returns the lookahead at the lexeme after the closing brace
+ * is_ignored_specifier:
+ * specifiers and qualifiers with no rust equivalent, they are dropped
+ * extern, inline, volatile, virtual ...
  */
-pub fn skip_block(lexeme: &Vec<Token>, mut lookahead: usize) -> usize {
-    let mut paren = 1;
+pub fn is_ignored_specifier(typ: TokenType) -> bool {
+    matches!(
+        typ,
+        KeywordExtern
+            | KeywordInline
+            | KeywordRegister
+            | KeywordVolatile
+            | KeywordRestrict
+            | KeywordMutable
+            | KeywordVirtual
+            | KeywordExplicit
+            | KeywordThreadLocal
+            | KeywordNoreturn
+            | KeywordAtomic
+    )
+}
 
-    // while all braces are not closed
-    // skip nested blocks if any
-    while paren != 0 && lookahead < lexeme.len() {
-        if lexeme[lookahead].get_token_type() == LeftCurlyBrace {
-            paren += 1;
-        }
-        if lexeme[lookahead].get_token_type() == RightCurlyBrace {
-            paren -= 1;
-        }
-        lookahead += 1;
-    }
-    lookahead
+/**
+ * is_identifier:
+ * true for identifiers, `main` is lexed as its own token type
+ */
+pub fn is_identifier(token: &Token) -> bool {
+    matches!(token.get_token_type(), Identifier | Main)
 }
 
-pub fn get_operator_as_fucn_name(token: &Token) -> &str {
-    match token.get_token_type() {
-        Plus => "_plus",
-        Minus => "_minus",
-        Multiplication => "_mult",
-        Divide => "_div",
-        PlusEqual => "_plus_eq",
-        MinusEqual => "_minus_eq",
-        MultiplyEqual => "_mult_eq",
-        DivideEqual => "_div_eq",
-        Increment => "_inc",
-        Decrement => "_dec",
-        Equal => "_eq",
-        Assignment => "_assign",
-        RightBracket => "_call",
-        _ => "_misc_op",
-    }
+/**
+ * is_access_specifier:
+ * public, private and protected
+ */
+pub fn is_access_specifier(token: &Token) -> bool {
+    matches!(
+        token.get_token_type(),
+        KeywordPublic | keywordPrivate | KeywordProtected
+    )
+}
+
+/**
+ * token_text:
+ * how the token is written in the source, quoted, for error messages
+ */
+pub fn token_text(typ: TokenType) -> String {
+    let text = match typ {
+        Semicolon => ";",
+        Colon => ":",
+        Comma => ",",
+        LeftBracket => "(",
+        RightBracket => ")",
+        LeftCurlyBrace => "{",
+        RightCurlyBrace => "}",
+        LeftSquareBracket => "[",
+        RightSquareBracket => "]",
+        Assignment => "=",
+        KeywordWhile => "while",
+        KeywordEnum => "enum",
+        LessThan => "<",
+        GreaterThan => ">",
+        HeaderIfDefineEnd => "#endif",
+        // the preprocessor ends the macros it leaves for the translation
+        DirectiveEnd => return "the end of the #define".to_string(),
+        _ => return "another token".to_string(),
+    };
+    format!("`{}`", text)
 }
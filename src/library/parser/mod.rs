@@ -1,6 +1,8 @@
+pub mod error;
 mod helper;
+#[allow(clippy::module_inception)]
 pub mod parser;
-mod rust_type;
+pub(crate) mod rust_type;
 
 #[cfg(test)]
 mod parser_test;
@@ -1,2112 +1,2134 @@
 #![allow(dead_code)]
 
+use std::collections::BTreeSet;
+
+use crate::library::ast::*;
 use crate::library::doc::DocType;
 use crate::library::doc::DocType::*;
-use crate::library::lexeme::definition::TokenKind::Identifiers;
 use crate::library::lexeme::definition::TokenType::*;
 use crate::library::lexeme::definition::{TokenKind, TokenType};
+use crate::library::lexeme::span::Span;
 use crate::library::lexeme::token::Token;
+use crate::library::parser::error::{ParseError, ParseErrors};
 use crate::library::parser::helper::*;
-use crate::library::parser::rust_type::*;
-
-#[derive(Debug)]
-struct SymbolTable {
-    symbol_type: TokenType,
-    symbol_modifier: Modifier,
-    id_name: String,
-    is_assigned: bool,
-    is_ptr: bool,
-    assigned_val: String,
-}
-
-#[derive(Debug)]
-struct CStructMem {
-    name: String,
-    member_type: TokenType,
-    identifier: String,
+use crate::library::parser::rust_type::Modifier;
+
+type PResult<T> = Result<T, ParseError>;
+
+struct Parser<'a> {
+    lexeme: &'a [Token],
+    // index of the next token to be consumed
+    head: usize,
+    // declarations of the scopes open at the head, used to tell
+    // declarations from expressions: `A * b;` `(A) - b`
+    symbols: SymbolTable,
+    // errors recovered from so far
+    errors: Vec<ParseError>,
+    // reading the body of a macro, where `#x` stringizes a parameter
+    in_macro: bool,
+    // number of #if branches kept as cfg being read
+    cfg_depth: usize,
+    // comments passed over inside a construct, by index, kept after it
+    skipped: BTreeSet<usize>,
+    // the construct being read holds a keyword crust does not translate, it
+    // is kept as is and reported with this doc rather than as a parse error
+    untranslated: Option<DocType>,
 }
 
-impl Clone for SymbolTable {
-    fn clone(&self) -> SymbolTable {
-        let id = self.id_name.clone();
-        let val = self.assigned_val.clone();
-        SymbolTable {
-            assigned_val: val,
-            id_name: id,
-            symbol_modifier: self.symbol_modifier,
-            symbol_type: self.symbol_type,
-            is_ptr: self.is_ptr,
-            is_assigned: self.is_assigned,
-        }
+/// Parses the token stream into a translation unit.
+/// On errors the parser recovers at the next statement or top level
+/// declaration, the unit recovered is returned along with the errors
+pub fn init_parser(lexeme: &[Token]) -> Result<TranslationUnit, ParseErrors> {
+    let mut parser = Parser {
+        lexeme,
+        head: 0,
+        symbols: SymbolTable::new(),
+        errors: Vec::new(),
+        in_macro: false,
+        cfg_depth: 0,
+        skipped: BTreeSet::new(),
+        untranslated: None,
+    };
+    let unit = parser.parse_program();
+    if parser.errors.is_empty() {
+        Ok(unit)
+    } else {
+        Err(ParseErrors {
+            errors: parser.errors,
+            unit,
+        })
     }
 }
 
-impl Clone for CStructMem {
-    fn clone(&self) -> CStructMem {
-        CStructMem {
-            name: self.name.clone(),
-            member_type: self.member_type,
-            identifier: self.identifier.clone(),
-        }
+impl<'a> Parser<'a> {
+    /**
+     * peek_nth:
+     * returns the nth token from the head, skipping comments.
+     * comments are only significant at statement boundaries and
+     * are collected there with take_comments and take_trailing
+     */
+    fn peek_nth(&self, n: usize) -> Option<&'a Token> {
+        let lexeme = self.lexeme;
+        lexeme[self.head.min(lexeme.len())..]
+            .iter()
+            .filter(|t| t.get_token_kind() != TokenKind::Comments)
+            .nth(n)
     }
-}
 
-struct Parser {
-    from: usize,
-    //for symbol table
-    once_warned: bool,
-    //default false
-    in_block_stmnt: bool,
-    //default false
-    in_expr: bool,
-    //default false
-    in_switch: bool,
-    //defalt false
-    strict: bool,
-    //default true
-    in_main: bool,
-    sym_tab: Vec<SymbolTable>,
-    // structure book keeping
-    struct_mem: Vec<CStructMem>,
-    typde_def_table: Vec<String>,
-}
+    fn peek(&self) -> Option<&'a Token> {
+        self.peek_nth(0)
+    }
 
-pub fn init_parser(lexeme: &Vec<Token>, strict_parser: bool) -> Vec<String> {
-    let mut stream: Vec<String> = Vec::new();
-    stream.push(CRUST.get_doc().to_string());
+    fn nth_type(&self, n: usize) -> Option<TokenType> {
+        self.peek_nth(n).map(|t| t.get_token_type())
+    }
 
-    let mut parser = Parser {
-        from: 0,
-        once_warned: false,
-        in_block_stmnt: false,
-        in_expr: false,
-        in_switch: false,
-        strict: strict_parser,
-        in_main: false,
-        sym_tab: Vec::new(),
-        struct_mem: Vec::new(),
-        typde_def_table: Vec::new(),
-    };
-    stream.append(&mut parser.parse_program(&lexeme));
-    stream
-}
+    fn check(&self, typ: TokenType) -> bool {
+        self.nth_type(0) == Some(typ)
+    }
 
-impl Parser {
-    fn parse_program(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head: usize = 0;
-        let mut lookahead: usize;
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        while head < lexeme.len() {
-            lookahead = head;
-            //match over token kind and token type
-
-            match lexeme[head].get_type() {
-                (TokenKind::DataTypes, TokenType::Typedef) => {
-                    //typedef STRUCT struct_t;
-                    while lexeme[head].get_token_type() != Semicolon {
-                        temp_lexeme.push(lexeme[head].clone());
-                        head += 1;
-                    }
-                    temp_lexeme.push(lexeme[head].clone());
-                    stream.append(&mut self.parse_typdef(&temp_lexeme));
-                    head += 1;
-                    // println!("{}", lexeme[head].get_token_value());
-                    temp_lexeme.clear();
-                }
-                // matches any datatype
-                (TokenKind::DataTypes, _) => {
-                    //if token is modifiers , move lookahead pointer to next lexeme
-                    if lexeme[head].get_token_type() == Signed
-                        || lexeme[head].get_token_type() == Unsigned
-                    {
-                        lookahead += 1;
-                    }
-                    //To see whats after the given identifier
-                    //ex : int a = 0; int a;
-                    //     int foo(){}
-                    lookahead += 2;
-                    //A quick hack to prevent parser going into infinite loop on processing
-                    if lexeme[lookahead].get_token_type() == ScopeResolution {
-                        while lexeme[lookahead].get_token_type() != LeftBracket {
-                            lookahead += 1;
-                        }
-                    }
-                    match lexeme[lookahead].get_token_type() {
-                        // function declaration
-                        LeftBracket => {
-                            //inside the function
-                            self.in_block_stmnt = true;
-                            //move till end of function argument declaration
-                            while lexeme[lookahead].get_token_type() != RightBracket {
-                                lookahead += 1;
-                            }
-                            //move ahead of )
-                            lookahead += 1;
-
-                            // skip function body declaration
-                            if lexeme[lookahead].get_token_type() != LeftCurlyBrace {
-                                lookahead += 1;
-                                // head = lookahead;
-                                //FIXME : Why is continue here ?
-                                //continue; ??
-                            }
-                            // advance lookahead to end of block
-                            lookahead = skip_block(&lexeme, lookahead + 1);
-                            // collect entire function block
-                            while head < lookahead {
-                                let l: Token = lexeme[head].clone();
-                                temp_lexeme.push(l);
-                                head += 1;
-                            }
-
-                            //parse function block
-                            stream.append(&mut self.parse_function(&temp_lexeme));
-                            temp_lexeme.clear();
-
-                            self.in_block_stmnt = false;
-                        }
-
-                        //array declaration found
-                        LeftSquareBracket => {
-                            lookahead = skip_stmt(&lexeme, lookahead);
-
-                            // collect variable declaration
-                            while head != lookahead {
-                                let l: Token = lexeme[head].clone();
-                                temp_lexeme.push(l);
-                                head += 1;
-                            }
-                            // parse declaration
-                            stream.append(&mut self.parse_array_declaration(&temp_lexeme));
-                            temp_lexeme.clear();
-                        }
-
-                        // variable declaration or declaration + assignment
-                        Semicolon | Comma | Assignment => {
-                            lookahead = skip_stmt(&lexeme, lookahead);
-
-                            // collect variable declaration
-                            while head != lookahead {
-                                let l: Token = lexeme[head].clone();
-                                temp_lexeme.push(l);
-                                head += 1;
-                            }
-                            //  println!(" {:?}", temp_lexeme);
-                            // parse declaration
-                            stream.append(&mut self.parse_declaration(&temp_lexeme));
-                            temp_lexeme.clear();
-                        }
-                        Identifier => {
-                            //in case of pointer declaration : int *a;
-                            while lexeme[head].get_token_type() != Semicolon {
-                                temp_lexeme.push(lexeme[head].clone());
-                                head += 1;
-                            }
-                            temp_lexeme.push(lexeme[head].clone());
-                            stream.append(&mut self.parse_declaration(&temp_lexeme));
-                            head += 1;
-                            // println!("{}", lexeme[head].get_token_value());
-                            temp_lexeme.clear();
-                        }
-
-                        _ => {}
-                    };
-                }
+    fn check_nth(&self, n: usize, typ: TokenType) -> bool {
+        self.nth_type(n) == Some(typ)
+    }
 
-                // matches if statement
-                (TokenKind::Keyword, KeywordIf) => {
-                    // let mut temp_lexeme: Vec<Token> = Vec::new();
+    fn at_end(&self) -> bool {
+        self.peek().is_none()
+    }
 
-                    // move lookahead past conditon
-                    while lexeme[lookahead].get_token_type() != RightBracket {
-                        lookahead += 1;
-                    }
-                    lookahead += 1;
+    /// consumes the next significant token
+    fn bump(&mut self) -> Option<&'a Token> {
+        while self.head < self.lexeme.len() {
+            let token = &self.lexeme[self.head];
+            self.head += 1;
+            if token.get_token_kind() != TokenKind::Comments {
+                return Some(token);
+            }
+            self.skipped.insert(self.head - 1);
+        }
+        None
+    }
 
-                    // move lookahead past block
-                    if lexeme[lookahead].get_token_type() == LeftCurlyBrace {
-                        lookahead = skip_block(&lexeme, lookahead + 1);
-                    }
-                    // move lookahead past block for 'if' without braces
-                    else {
-                        lookahead = skip_stmt(&lexeme, lookahead);
-                    }
-                    // collect if block
-                    while head < lookahead {
-                        let l: Token = lexeme[head].clone();
-                        temp_lexeme.push(l);
-                        head += 1;
-                    }
+    fn eat(&mut self, typ: TokenType) -> bool {
+        if self.check(typ) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
 
-                    // parse if
-                    stream.append(&mut self.parse_if(&temp_lexeme));
-                    temp_lexeme.clear();
-                }
+    /// consumes the next token, which must be of the given type
+    fn expect(&mut self, typ: TokenType) -> PResult<&'a Token> {
+        match self.peek() {
+            Some(token) if token.get_token_type() == typ => {
+                self.bump();
+                Ok(token)
+            }
+            // the last statement of a macro body leaves out the `;`
+            Some(token)
+                if typ == Semicolon && self.in_macro && token.get_token_type() == DirectiveEnd =>
+            {
+                Ok(token)
+            }
+            _ => Err(self.error_expected(&token_text(typ))),
+        }
+    }
 
-                (TokenKind::Keyword, KeywordElse) => {
-                    stream.push("else".to_string());
-                    head += 1;
-                    lookahead = head;
-                    if lexeme[head].get_token_type() == KeywordIf {
-                        continue;
-                    } else {
-                        if lexeme[lookahead].get_token_type() == LeftCurlyBrace {
-                            head += 1;
-                            lookahead = skip_block(&lexeme, head) - 1;
-                        } else {
-                            lookahead = skip_stmt(&lexeme, lookahead);
-                        }
-
-                        while head < lookahead {
-                            let l: Token = lexeme[head].clone();
-                            temp_lexeme.push(l);
-                            head += 1;
-                        }
-                        //** parse else body
-                        stream.push("{".to_string());
-                        stream.append(&mut self.parse_program(&temp_lexeme));
-                        temp_lexeme.clear();
-                        stream.push("}".to_string());
-                    }
-                }
+    /// error at the head, `expected ..., found ...`
+    fn error_expected(&self, expected: &str) -> ParseError {
+        match self.peek() {
+            Some(token) => ParseError::new(
+                format!("expected {}, found `{}`", expected, token.get_token_value()),
+                token.get_span(),
+            ),
+            None => ParseError::new(
+                format!("expected {}, found end of file", expected),
+                self.end_span(),
+            ),
+        }
+    }
 
-                (TokenKind::Keyword, KeywordSwitch) => {
-                    while lexeme[lookahead].get_token_type() != LeftCurlyBrace {
-                        lookahead += 1;
-                    }
-                    lookahead += 1;
+    /// empty span right after the last token
+    fn end_span(&self) -> Span {
+        match self.lexeme.last() {
+            Some(token) => {
+                let end = token.get_span().end;
+                Span::new(end, end)
+            }
+            None => Span::default(),
+        }
+    }
 
-                    lookahead = skip_block(&lexeme, lookahead);
-                    while head < lookahead {
-                        let l: Token = lexeme[head].clone();
-                        temp_lexeme.push(l);
-                        head += 1;
-                    }
-                    self.in_switch = true;
+    /// collects the comments found right at the head
+    fn take_comments(&mut self) -> Vec<Comment> {
+        let mut comments = Vec::new();
+        while self.head < self.lexeme.len()
+            && self.lexeme[self.head].get_token_kind() == TokenKind::Comments
+        {
+            self.skipped.remove(&self.head);
+            comments.push(Comment {
+                text: self.lexeme[self.head].get_token_value(),
+                trailing: false,
+            });
+            self.head += 1;
+        }
+        comments
+    }
 
-                    stream.append(&mut self.parse_switch(&temp_lexeme));
-                    temp_lexeme.clear();
-                    self.in_switch = false;
+    /**
+     * take_trailing:
+     * collects the comments passed over in the construct which begins
+     * at `start`, and the ones following it on its last line
+     */
+    fn take_trailing(&mut self, start: usize) -> Vec<Comment> {
+        let mut indices: Vec<usize> = self.skipped.range(start..self.head).copied().collect();
+        let line = match self.head.checked_sub(1) {
+            Some(last) => self.lexeme[last].get_span().end.line,
+            None => return Vec::new(),
+        };
+        while self.head < self.lexeme.len()
+            && self.lexeme[self.head].get_token_kind() == TokenKind::Comments
+            && self.lexeme[self.head].get_span().start.line == line
+        {
+            indices.push(self.head);
+            self.head += 1;
+        }
+        indices
+            .into_iter()
+            .map(|index| {
+                self.skipped.remove(&index);
+                Comment {
+                    text: self.lexeme[index].get_token_value(),
+                    trailing: true,
                 }
+            })
+            .collect()
+    }
 
-                (TokenKind::Keyword, KeywordWhile) => {
-                    // let mut temp_lexeme: Vec<Token> = Vec::new();
+    /// source text of the tokens in [start, end), used for constructs kept as is
+    fn text_between(&self, start: usize, end: usize) -> String {
+        self.lexeme[start..end]
+            .iter()
+            .filter(|t| t.get_token_kind() != TokenKind::Comments)
+            .map(|t| t.get_token_value())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
 
-                    // move lookahead past conditon
-                    while lexeme[lookahead].get_token_type() != RightBracket {
-                        lookahead += 1;
-                    }
-                    lookahead += 1;
+    /// span of the significant tokens in [start, end)
+    fn span_between(&self, start: usize, end: usize) -> Span {
+        let mut tokens = self.lexeme[start..end.min(self.lexeme.len())]
+            .iter()
+            .filter(|t| t.get_token_kind() != TokenKind::Comments)
+            .map(|t| t.get_span());
+        match tokens.next() {
+            Some(first) => tokens.fold(first, Span::to),
+            None => Span::default(),
+        }
+    }
 
-                    // move lookahead past block
-                    if lexeme[lookahead].get_token_type() == LeftCurlyBrace {
-                        lookahead = skip_block(&lexeme, lookahead + 1);
-                    }
-                    // move lookahead past block for 'if' without braces
-                    else {
-                        lookahead = skip_stmt(&lexeme, lookahead);
-                    }
-                    // collect if block
-                    while head < lookahead {
-                        let l: Token = lexeme[head].clone();
-                        temp_lexeme.push(l);
-                        head += 1;
-                    }
+    /// span of the tokens consumed since `start`
+    fn span_since(&self, start: usize) -> Span {
+        self.span_between(start, self.head)
+    }
 
-                    let was_in_switch: bool;
-                    was_in_switch = self.in_switch;
-                    self.in_switch = false;
+    /// declares `name` in the innermost scope, declared from `start` to the head
+    fn declare(&mut self, name: &str, kind: SymbolKind, ty: Type, start: usize) {
+        if name.is_empty() {
+            return;
+        }
+        let span = self.span_since(start);
+        self.symbols.declare(Symbol {
+            name: name.to_string(),
+            kind,
+            ty,
+            span,
+        });
+    }
 
-                    // parse if
-                    stream.append(&mut self.parse_while(&temp_lexeme));
-                    self.in_switch = was_in_switch;
-                    temp_lexeme.clear();
-                }
+    /// runs `parse` in a new scope, closed whether it succeeds or not
+    fn scoped<T>(
+        &mut self,
+        kind: ScopeKind,
+        parse: impl FnOnce(&mut Self) -> PResult<T>,
+    ) -> PResult<T> {
+        self.symbols.enter(kind);
+        let result = parse(self);
+        self.symbols.leave();
+        result
+    }
 
-                // matches do while statement
-                (TokenKind::Keyword, KeywordDo) => {
-                    // move lookahead past block
-                    lookahead = skip_block(&lexeme, lookahead + 2);
-                    lookahead = skip_stmt(&lexeme, lookahead);
-
-                    // collect while block
-                    while head < lookahead {
-                        let l: Token = lexeme[head].clone();
-                        temp_lexeme.push(l);
-                        head += 1;
-                    }
-                    // parse while
-                    let was_in_switch: bool;
-                    was_in_switch = self.in_switch;
-                    self.in_switch = false;
+    /// identifier which must be at the head
+    fn expect_ident(&mut self) -> PResult<String> {
+        match self.peek() {
+            Some(token) if is_identifier(token) => {
+                self.bump();
+                Ok(token.get_token_value())
+            }
+            _ => Err(self.error_expected("identifier")),
+        }
+    }
 
-                    stream.append(&mut self.parse_dowhile(&temp_lexeme));
-                    temp_lexeme.clear();
+    fn parse_ident(&mut self) -> String {
+        match self.peek() {
+            Some(token) if is_identifier(token) => {
+                self.bump();
+                token.get_token_value()
+            }
+            _ => String::new(),
+        }
+    }
 
-                    self.in_switch = was_in_switch;
-                }
-                (TokenKind::Keyword, Using) => {
-                    stream
-                        .push("//FIXME: Convert the below statement manually,\n/**\n".to_string());
-                    while lexeme[head].get_token_type() != Semicolon {
-                        stream.push(lexeme[head].get_token_value());
-                        head += 1;
+    /// skips till the end of the statement, braces included
+    fn skip_stmt(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.bump() {
+            match token.get_token_type() {
+                LeftCurlyBrace => depth += 1,
+                RightCurlyBrace => {
+                    depth -= 1;
+                    if depth == 0 && !self.check(Semicolon) {
+                        return;
                     }
-                    stream.push(lexeme[head].get_token_value());
-                    stream.push("\n */".to_string());
-                    head += 1;
                 }
-                // matches for statement
-                (_, KeywordFor) => {
-                    while lexeme[lookahead].get_token_type() != LeftCurlyBrace {
-                        lookahead += 1;
-                    }
-                    lookahead += 1;
-                    lookahead = skip_block(&lexeme, lookahead);
+                Semicolon if depth <= 0 => return,
+                _ => {}
+            }
+        }
+    }
 
-                    while head < lookahead {
-                        let l: Token = lexeme[head].clone();
-                        temp_lexeme.push(l);
-                        head += 1;
+    /// skips a brace enclosed block, the head must be at the opening brace
+    fn skip_block(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.bump() {
+            match token.get_token_type() {
+                LeftCurlyBrace => depth += 1,
+                RightCurlyBrace => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
                     }
-
-                    let was_in_switch: bool;
-                    was_in_switch = self.in_switch;
-                    self.in_switch = false;
-
-                    stream.append(&mut self.parse_for(&temp_lexeme));
-                    temp_lexeme.clear();
-                    self.in_switch = was_in_switch;
-                }
-
-                // matches single and multi-line comment
-                (TokenKind::Comments, _) => {
-                    stream.push(lexeme[head].get_token_value() + "\n");
-                    head += 1;
-                }
-
-                // assignment statements
-                (_, Identifier) => {
-                    // let mut temp_lexeme: Vec<Token> = Vec::new();
-                    //identifier = expr
-                    //identifier()
-                    //identifier+expr
-                    //identifier OP_INC|OP_DEC; =>postfix
-
-                    match lexeme[head + 1].get_type() {
-                        (TokenKind::Identifiers, Identifier) => {
-                            if self
-                                .typde_def_table
-                                .contains(&lexeme[head].get_token_value())
-                            {
-                                lookahead = skip_stmt(&lexeme, lookahead);
-
-                                // collect variable declaration
-                                while head != lookahead {
-                                    let l: Token = lexeme[head].clone();
-                                    temp_lexeme.push(l);
-                                    head += 1;
-                                }
-                                //  println!(" {:?}", temp_lexeme);
-                                // parse declaration
-                                stream.append(&mut self.parse_declaration(&temp_lexeme));
-                            } else {
-                                while lexeme[head].get_token_type() != Semicolon {
-                                    temp_lexeme.push(lexeme[head].clone());
-                                    head += 1;
-                                }
-                                temp_lexeme.push(lexeme[head].clone());
-                                head += 1;
-                                stream.append(&mut self.parse_class_decl(&temp_lexeme));
-                            }
-                            temp_lexeme.clear();
-                        }
-
-                        (TokenKind::AssignmentOperators, Assignment) => {
-                            // move lookahead past statement
-                            if lexeme[head + 3].get_token_type() == Comma {
-                                lookahead = head + 3;
-                                while head < lookahead + 1 {
-                                    let l: Token = lexeme[head].clone();
-                                    temp_lexeme.push(l);
-                                    head += 1;
-                                }
-                                stream.append(&mut self.parse_assignment(&temp_lexeme));
-                                temp_lexeme.clear();
-                            } else {
-                                lookahead = skip_stmt(&lexeme, lookahead);
-                                // collect statement
-                                while head < lookahead {
-                                    let l: Token = lexeme[head].clone();
-                                    temp_lexeme.push(l);
-                                    head += 1;
-                                }
-
-                                // parse assignment
-                                stream.append(&mut self.parse_assignment(&temp_lexeme));
-                                temp_lexeme.clear();
-                            }
-                        }
-                        (TokenKind::UnaryOperators, _) => {
-                            if self.in_expr != true {
-                                stream.push(lexeme[head].get_token_value());
-                                stream.push(match lexeme[head + 1].get_token_type() {
-                                    Increment => "+=1".to_string(),
-                                    Decrement => "-=1".to_string(),
-                                    _ => " ;".to_string(),
-                                });
-                                head += 2;
-                            } else {
-                                head += 2;
-                            }
-                        }
-                        (TokenKind::BinaryOperators, _) => {
-                            lookahead = skip_stmt(&lexeme, lookahead);
-
-                            //check if overloaded operators is in effect like << >>
-                            if lexeme[head + 2].get_token_type() == StringValue
-                                || lexeme[head + 2].get_token_type() == CharValue
-                            {
-                                stream.push(
-                                    "\n//This statement need to be handled manually \n".to_string(),
-                                );
-                                while head < lookahead {
-                                    stream.push(lexeme[head].get_token_value());
-                                    head += 1;
-                                }
-                            } else {
-                                // move lookahead past statement
-                                // collect statement
-                                while head < lookahead {
-                                    let l: Token = lexeme[head].clone();
-                                    temp_lexeme.push(l);
-                                    head += 1;
-                                }
-
-                                // parse assignment
-                                stream.append(&mut self.parse_expr(&temp_lexeme));
-                                temp_lexeme.clear();
-                            }
-                        }
-                        (TokenKind::SpecialChars, LeftBracket) => {
-                            while lexeme[head].get_token_type() != RightBracket {
-                                stream.push(lexeme[head].get_token_value());
-                                head += 1;
-                            }
-                            stream.push(lexeme[head].get_token_value());
-                            head += 1;
-                        }
-                        // a -> x->y(); ==> a.x.y()
-                        (TokenKind::SpecialChars, Arrow) => {
-                            //insert the previous identifier token
-                            stream.push(lexeme[head].get_token_value());
-                            stream.push(".".to_string());
-                            head += 2;
-                        }
-                        // (_, LEFT_SBRACKET) => {
-                        //     while lexeme[head].get_token_type() != RIGHT_SBRACKET {
-                        //         stream.push(lexeme[head].get_token_value());
-                        //         head += 1;
-                        //     }
-                        //     stream.push(lexeme[head].get)
-                        // }
-                        (_, _) => {
-                            if lexeme[head].get_token_type() != RightCurlyBrace {
-                                stream.push(lexeme[head].get_token_value());
-                            }
-                            head += 1;
-                        }
-                    };
-                }
-
-                (TokenKind::UnaryOperators, _) => {
-                    stream.push(lexeme[head + 1].get_token_value());
-                    stream.push(match lexeme[head].get_token_type() {
-                        Increment => "+=1".to_string(),
-                        Decrement => "-=1".to_string(),
-                        _ => " ;".to_string(),
-                    });
-                    head += 2;
                 }
+                _ => {}
+            }
+        }
+    }
 
-                (_, KeywordStruct) => {
-                    if lexeme[head + 2].get_token_type() == LeftCurlyBrace {
-                        //struct A{};
-                        while lexeme[head].get_token_type() != RightCurlyBrace {
-                            temp_lexeme.push(lexeme[head].clone());
-                            head += 1;
-                        }
-                        //push the right curly brace
-                        temp_lexeme.push(lexeme[head].clone());
-                        stream.append(&mut self.parse_struct(&temp_lexeme));
-                        temp_lexeme.clear();
-                        head += 2; //skip semicolon
-                    } else {
-                        //struct variable declaration
-
-                        while lexeme[head].get_token_type() != Semicolon {
-                            temp_lexeme.push(lexeme[head].clone());
-                            head += 1;
-                        }
-                        temp_lexeme.push(lexeme[head].clone());
-                        head += 1;
-                        stream.append(&mut self.parse_struct_decl(&temp_lexeme));
-                        temp_lexeme.clear();
+    /// skips a bracket enclosed list if one is at the head, noexcept(..), alignas(..)
+    fn skip_brackets(&mut self) {
+        if !self.check(LeftBracket) {
+            return;
+        }
+        let mut depth = 0;
+        while let Some(token) = self.bump() {
+            match token.get_token_type() {
+                LeftBracket => depth += 1,
+                RightBracket => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
                     }
                 }
+                _ => {}
+            }
+        }
+    }
 
-                (_, KeywordUnion) => {
-                    if lexeme[head + 2].get_token_type() == LeftCurlyBrace {
-                        stream.push(UNION.get_doc().to_string());
-                        //struct A{};
-                        while lexeme[head].get_token_type() != RightCurlyBrace {
-                            temp_lexeme.push(lexeme[head].clone());
-                            head += 1;
-                        }
-                        //push the right curly brace
-                        temp_lexeme.push(lexeme[head].clone());
-                        stream.append(&mut self.parse_union(&temp_lexeme));
-                        temp_lexeme.clear();
-                        head += 2; //skip semicolon
-                    } else {
-                        //struct variable declaration
-
-                        while lexeme[head].get_token_type() != Semicolon {
-                            temp_lexeme.push(lexeme[head].clone());
-                            head += 1;
-                        }
-                        temp_lexeme.push(lexeme[head].clone());
-                        head += 1;
-                        stream.append(&mut self.parse_union_decl(&temp_lexeme));
-                        temp_lexeme.clear();
-                    }
-                }
+    fn parse_program(&mut self) -> TranslationUnit {
+        let mut unit = TranslationUnit::default();
+        self.parse_items(&mut unit.items, false);
+        unit
+    }
 
-                (_, KeywordClass) => {
-                    if lexeme[head + 2].get_token_type() == LeftCurlyBrace {
-                        //struct A{};
-                        while lexeme[head].get_token_type() != RightCurlyBrace
-                            || lexeme[head + 1].get_token_type() != Semicolon
-                        {
-                            temp_lexeme.push(lexeme[head].clone());
-                            head += 1;
-                        }
-
-                        //push the right curly brace
-                        temp_lexeme.push(lexeme[head].clone());
-                        stream.append(&mut self.parse_class(&temp_lexeme));
-                        temp_lexeme.clear();
-                        head += 2; //skip semicolon
+    /**
+     * parse_items:
+     * parse top level constructs till the end of input, or till the
+     * closing brace of the enclosing block when `nested` is set
+     */
+    fn parse_items(&mut self, items: &mut Vec<Item>, nested: bool) {
+        loop {
+            let comments = self.take_comments();
+            if self.at_end()
+                || (nested && self.check(RightCurlyBrace))
+                || (self.cfg_depth > 0 && self.check(HeaderIfDefineEnd))
+            {
+                items.extend(comments.into_iter().map(Item::Comment));
+                break;
+            }
+            let start = self.head;
+            let first = items.len();
+            self.untranslated = None;
+            if let Err(error) = self.parse_item(items) {
+                match self.recover_untranslated(start) {
+                    Some((doc, found)) => {
+                        items.truncate(first);
+                        items.push(Item::Unsupported {
+                            doc,
+                            text: found.text,
+                            span: found.span,
+                        })
                     }
+                    None => items.push(Item::Error(self.recover(start, error))),
                 }
-
-                (_, KeywordEnum) => {
-                    while lexeme[head].get_token_type() != Semicolon {
-                        stream.push(lexeme[head].get_token_value());
-                        head += 1;
-                    }
-                    head += 1;
+            }
+            // functions, records and enums keep their doc
+            match items.get_mut(first) {
+                Some(Item::Function(function)) => {
+                    function.comments.splice(0..0, comments);
                 }
-                (_, KeywordReturn) => {
-                    let mut t = head;
-                    stream.push(NO_RETURN.get_doc().to_string());
-
-                    while lexeme[t].get_token_type() != Semicolon {
-                        t += 1;
-                    }
-
-                    if t != lexeme.len() - 1 {
-                        while lexeme[head].get_token_type() != Semicolon {
-                            // println!("{:?}", lexeme[head]);
-
-                            stream.push(lexeme[head].get_token_value());
-                            head += 1;
-                        }
-                        stream.push(lexeme[head].get_token_value());
-                        head += 1;
-                    } else {
-                        //convert to shorthand notation
-                        head += 1;
-                        if self.in_main {
-                            stream.push("std::process::exit(".to_string());
-                            while lexeme[head].get_token_type() != Semicolon {
-                                stream.push(lexeme[head].get_token_value());
-                                head += 1;
-                            }
-                            stream.push(");".to_string());
-                        } else {
-                            while lexeme[head].get_token_type() != Semicolon {
-                                stream.push(lexeme[head].get_token_value());
-                                head += 1;
-                            }
-                        }
-                        head += 1;
-                    }
+                Some(Item::Record(record)) => {
+                    record.comments.splice(0..0, comments);
                 }
-                (_, HeaderInclude) => {
-                    if self.once_warned == false {
-                        stream.push(INCLUDE_STMT.get_doc().to_string());
-                    } else {
-                        stream.pop();
-                        stream.push("* >>>>>>>>".to_string());
-                    }
-
-                    while lexeme[head].get_token_type() != GreaterThan {
-                        stream.push(lexeme[head].get_token_value());
-                        head += 1;
-                    }
-                    stream.push(lexeme[head].get_token_value() + "\n");
-                    stream.push("**/\n".to_string());
-                    head += 1;
-
-                    self.once_warned = true;
+                Some(Item::Enum(found)) => {
+                    found.comments.splice(0..0, comments);
                 }
-                // if all fails
-                (_, _) => {
-                    if lexeme[head].get_token_type() != RightCurlyBrace {
-                        if lexeme[head].get_token_type() == Comma {
-                            stream.push(";".to_string());
-                        } else if lexeme[head].get_token_type() == KeywordBreak {
-                            if !self.in_switch {
-                                stream.push(lexeme[head].get_token_value());
-                            }
-                        } else {
-                            stream.push(lexeme[head].get_token_value());
-                        }
-                    }
-                    head += 1;
+                _ => {
+                    items.splice(first..first, comments.into_iter().map(Item::Comment));
                 }
-            };
+            }
+            let trailing = self.take_trailing(start);
+            items.extend(trailing.into_iter().map(Item::Comment));
         }
-        //return the rust lexeme to main
-        stream
     }
 
     /**
-     * print_lexemes: DEBUG_ONLY
-     * prints the lexemes in the lexeme vector
-     * from index start to end
+     * recover:
+     * records the error and skips the statement or declaration
+     * which begins at `start`, the skipped source text is returned
      */
-    fn print_lexemes(lexeme: &Vec<Token>, start: usize, end: usize) {
-        println!("----------lexeme-start------------");
-        for i in start..end {
-            println!("{}", lexeme[i]);
-        }
-        println!("----------lexeme-end------------");
+    fn recover(&mut self, start: usize, error: ParseError) -> SourceText {
+        self.errors.push(error);
+        self.skip_from(start)
     }
 
     /**
-     * parse_function:
-     * parse c/c++ function into rust equivalent function
+     * recover_untranslated:
+     * when the construct which begins at `start` failed on a keyword crust
+     * does not translate, skips it and returns its source text with the doc
+     * to report it with
      */
-    fn parse_function(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        let mut head: usize;
-        let mut lookahead: usize = 1;
-        let mut stream: Vec<String> = Vec::new();
-        let mut in_impl: bool = false;
-        //if the function has scope resolution we need build a impl `class|struct`
-
-        let mut fucntion_name: String = "".to_string();
-        if lexeme[2].get_token_type() == ScopeResolution {
-            stream.push("impl ".to_string());
-            stream.push(lexeme[1].get_token_value());
-            stream.push("{".to_string());
-            in_impl = true;
-            lookahead = 3;
-        }
-        //move lookahead to functiion arguments
-        //FIXME : i will f up when overloading operator() in cpp
-        let mut warn_operator_overload = false;
-        while lexeme[lookahead].get_token_type() != LeftBracket {
-            match lexeme[lookahead].get_token_kind() {
-                TokenKind::UnaryOperators
-                | TokenKind::BinaryOperators
-                | TokenKind::AssignmentOperators => {
-                    warn_operator_overload = true;
-                    fucntion_name.push_str(get_operator_as_fucn_name(&lexeme[lookahead]))
+    fn recover_untranslated(&mut self, start: usize) -> Option<(DocType, SourceText)> {
+        let doc = self.untranslated.take()?;
+        Some((doc, self.skip_from(start)))
+    }
+
+    /// skips the statement or declaration which begins at `start`
+    fn skip_from(&mut self, start: usize) -> SourceText {
+        self.head = start;
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token.get_token_type() {
+                // closing brace of the enclosing block
+                RightCurlyBrace if depth == 0 => break,
+                RightCurlyBrace => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.bump();
+                        self.eat(Semicolon);
+                        break;
+                    }
+                }
+                LeftCurlyBrace => depth += 1,
+                Semicolon | DirectiveEnd if depth == 0 => {
+                    self.bump();
+                    break;
                 }
-                _ => fucntion_name.push_str(lexeme[lookahead].get_token_value().as_str()),
+                // the #if branch kept as cfg is left for its caller
+                HeaderIfDefineStart | HeaderIfDefineEnd if depth == 0 => break,
+                _ => {}
             }
-            lookahead += 1
+            self.bump();
         }
-        lookahead += 1;
-        head = lookahead;
-
-        if warn_operator_overload {
-            stream.push(DocType::OPERATOR_OVERLOAD.get_doc().to_string());
+        // always make progress, a stray `}` at the top level
+        if self.head == start {
+            self.bump();
         }
+        SourceText {
+            text: self.text_between(start, self.head),
+            span: self.span_since(start),
+        }
+    }
 
-        stream.push("fn".to_string());
-        stream.push(fucntion_name);
-        stream.push("(".to_string());
-
-        // parse arguments differently for functions that are not main
-        // since rust does not have arguments or return type for main
-        if lexeme[1].get_token_type() != Main {
-            // collect arguments
-            while lexeme[lookahead].get_token_type() != RightBracket {
-                lookahead += 1;
+    /**
+     * parse_define:
+     * parse a macro the preprocessor kept for the translation, a constant
+     * `#define NAME value` or a function-like macro, whose parameters
+     * follow the name without a space. Macros pasting tokens are refused
+     */
+    fn parse_define(&mut self) -> PResult<Item> {
+        let start = self.head;
+        self.bump();
+        let name = self.expect_ident()?;
+        let function_like = self.lexeme.get(self.head).is_some_and(|open| {
+            open.get_token_type() == LeftBracket
+                && self.lexeme[self.head - 1].get_span().end.offset == open.get_span().start.offset
+        });
+        if !function_like {
+            let value = self.parse_expr()?;
+            self.expect(DirectiveEnd)?;
+            return Ok(Item::Constant(Constant {
+                name,
+                value,
+                span: self.span_since(start),
+            }));
+        }
+        self.expect(LeftBracket)?;
+        let mut params = Vec::new();
+        let mut variadic = false;
+        while !self.eat(RightBracket) {
+            if self.eat(Ellipsis) {
+                variadic = true;
+            } else {
+                params.push(self.expect_ident()?);
             }
-            while head < lookahead {
-                let l: Token = lexeme[head].clone();
-                temp_lexeme.push(l);
-                head += 1;
+            if !self.check(RightBracket) {
+                self.expect(Comma)?;
             }
-            // parse arguments
-            stream.append(&mut self.parse_arguments(&temp_lexeme));
-            temp_lexeme.clear();
-
-            stream.push(")".to_string());
+        }
+        let body_start = self.head;
+        while !self.check(DirectiveEnd) && !self.at_end() {
+            self.bump();
+        }
+        let pasted = self.lexeme[body_start..self.head]
+            .iter()
+            .any(|t| t.get_token_type() == TokenPaste);
+        if pasted {
+            let text = self.text_between(start, self.head);
+            self.bump();
+            return Ok(Item::Unsupported {
+                doc: TOKEN_PASTE,
+                text,
+                span: self.span_since(start),
+            });
+        }
+        self.head = body_start;
+        let body = self.parse_macro_body()?;
+        self.expect(DirectiveEnd)?;
+        Ok(Item::Macro(Macro {
+            name,
+            params,
+            variadic,
+            body,
+            span: self.span_since(start),
+        }))
+    }
 
-            // parse return type
-            if let Some(rust_type) = parse_type(lexeme[0].get_token_type(), Modifier::Default) {
-                if rust_type != "void".to_string() {
-                    stream.push("->".to_string());
-                    stream.push(rust_type);
+    /// an expression, or else statements, up to the end of the #define
+    fn parse_macro_body(&mut self) -> PResult<MacroBody> {
+        let start = self.head;
+        self.in_macro = true;
+        let expr = self.parse_expr();
+        let body = match expr {
+            Ok(expr) if self.check(DirectiveEnd) => Ok(MacroBody::Expr(expr)),
+            _ => {
+                self.head = start;
+                let mut stmts = Vec::new();
+                let mut result = Ok(());
+                while result.is_ok() && !self.check(DirectiveEnd) && !self.at_end() {
+                    result = self.parse_stmt().map(|stmt| stmts.push(stmt));
                 }
+                result.map(|_| MacroBody::Stmts(stmts))
             }
+        };
+        self.in_macro = false;
+        body
+    }
 
-            stream.push("{".to_string());
-        }
-        // declare argc and argv inside main, if required
-        else {
-            //parsing main function
-            self.in_main = true;
-            stream.push(")".to_string());
-            stream.push("{".to_string());
-            if lexeme[head].get_token_type() != RightBracket {
-                if self.strict == false {
-                    stream.push(NO_STRICT.get_doc().to_string());
-                    stream.push("let mut argv: Vec<_> = std::env::args().collect();".to_string());
-                    stream.push("let mut argc = argv.len();".to_string());
+    /**
+     * parse_item:
+     * parse one top level construct, typedef of a struct
+     * yields more than one item
+     */
+    fn parse_item(&mut self, items: &mut Vec<Item>) -> PResult<()> {
+        let token = match self.peek() {
+            Some(token) => token,
+            None => return Ok(()),
+        };
+        match token.get_type() {
+            (_, HeaderInclude) => {
+                let start = self.head;
+                let line = token.get_token_line_num();
+                let mut parts: Vec<String> = Vec::new();
+                while let Some(t) = self.peek() {
+                    if t.get_token_line_num() != line {
+                        break;
+                    }
+                    parts.push(t.get_token_value());
+                    self.bump();
+                }
+                let directive = parts.get(1).cloned().unwrap_or_default();
+                let rest = if directive == "include" {
+                    parts[2..].concat()
                 } else {
-                    stream.push(STRICT.get_doc().to_string());
-
-                    stream.push("let argv: Vec<_> = std::env::args().collect();".to_string());
-                    stream.push("let argc = argv.len();".to_string());
+                    parts.get(2..).unwrap_or(&[]).join(" ")
+                };
+                items.push(Item::Directive(SourceText {
+                    text: format!("#{} {}", directive, rest),
+                    span: self.span_since(start),
+                }));
+            }
+            // #define NAME value ; declared by the preprocessor
+            (_, HeaderDefine) => items.push(self.parse_define()?),
+            // #if branch kept as cfg by the preprocessor
+            (_, HeaderIfDefineStart) => {
+                let predicate = token.get_token_value();
+                let mut cfg_items = Vec::new();
+                self.bump();
+                self.cfg_depth += 1;
+                self.parse_items(&mut cfg_items, false);
+                self.cfg_depth -= 1;
+                self.expect(HeaderIfDefineEnd)?;
+                items.push(Item::Cfg {
+                    predicate,
+                    items: cfg_items,
+                });
+            }
+            // header translated into a module
+            (_, HeaderModule) => {
+                items.push(Item::Include {
+                    header: token.get_token_value(),
+                    span: token.get_span(),
+                });
+                self.bump();
+            }
+            // left by the preprocessor for the translation
+            (_, Directive) => {
+                items.push(Item::Directive(SourceText {
+                    text: token.get_token_value(),
+                    span: token.get_span(),
+                }));
+                self.bump();
+            }
+            (_, Using) => {
+                let start = self.head;
+                self.skip_stmt();
+                items.push(Item::Unsupported {
+                    doc: USING_STMT,
+                    text: self.text_between(start, self.head),
+                    span: self.span_since(start),
+                });
+            }
+            (_, Typedef) => self.parse_typedef(items)?,
+            // extern "C" {..} and extern "C" int f();
+            (_, KeywordExtern) if self.check_nth(1, StringValue) => {
+                self.bump();
+                self.bump();
+                if self.eat(LeftCurlyBrace) {
+                    self.parse_items(items, true);
+                    self.expect(RightCurlyBrace)?;
+                } else {
+                    self.parse_item(items)?;
                 }
             }
+            (_, KeywordEnum) if self.is_enum_def() => {
+                let start = self.head;
+                let found = self.parse_enum()?;
+                let base = BaseType::Named(found.name.clone());
+                items.push(Item::Enum(found));
+                // enum Color {..} c;
+                if !self.check(Semicolon) {
+                    let declarators = self.parse_declarators(&Type::new(base))?;
+                    items.push(Item::Global(Declaration {
+                        declarators,
+                        span: self.span_since(start),
+                    }));
+                }
+                self.expect(Semicolon)?;
+            }
+            (_, KeywordStruct) | (_, KeywordUnion) | (_, KeywordClass) if self.is_record_def() => {
+                let start = self.head;
+                let record = self.parse_record()?;
+                let name = record.name.clone();
+                let base = match record.kind {
+                    RecordKind::Union => BaseType::Union(name),
+                    RecordKind::Struct => BaseType::Struct(name),
+                    RecordKind::Class => BaseType::Named(name),
+                };
+                items.push(Item::Record(record));
+                // struct A {..} a, b;
+                if !self.check(Semicolon) {
+                    let declarators = self.parse_declarators(&Type::new(base))?;
+                    items.push(Item::Global(Declaration {
+                        declarators,
+                        span: self.span_since(start),
+                    }));
+                }
+                self.expect(Semicolon)?;
+            }
+            _ if self.is_decl_start() => self.parse_function_or_global(items)?,
+            // A::A() {..} out of class constructor
+            (_, Identifier)
+                if self.check_nth(1, ScopeResolution) && self.check_nth(3, LeftBracket) =>
+            {
+                let start = self.head;
+                let ret = Type::primitive(Void);
+                let (owner, name) = self.parse_decl_name()?;
+                let function = self.parse_function_rest(start, ret, owner, name)?;
+                items.push(Item::Function(function));
+            }
+            _ => items.push(Item::Stmt(self.parse_stmt()?)),
         }
-        while lexeme[head].get_token_type() != LeftCurlyBrace {
-            head += 1
-        }
-        head += 1;
+        Ok(())
+    }
+
+    /// struct|class|union [name] {
+    fn is_record_def(&self) -> bool {
+        self.check_nth(1, LeftCurlyBrace)
+            || (self.peek_nth(1).is_some_and(is_identifier) && self.check_nth(2, LeftCurlyBrace))
+    }
 
-        // collect function body
-        // len - 1  so that '}' is excluded
-        while head < lexeme.len() - 1 {
-            let l: Token = lexeme[head].clone();
-            temp_lexeme.push(l);
-            head += 1;
+    /// enum {, enum Color {, enum class Color : uint8_t {
+    fn is_enum_def(&self) -> bool {
+        let mut n = 1;
+        while let Some(typ) = self.nth_type(n) {
+            match typ {
+                LeftCurlyBrace => return true,
+                Semicolon | LeftBracket | RightBracket | Assignment | Comma => return false,
+                _ => n += 1,
+            }
         }
-        // parse function body
-        stream.append(&mut self.parse_program(&temp_lexeme));
-        stream.push("}".to_string());
-        if in_impl {
-            stream.push("} //end impl".to_string());
+        false
+    }
+
+    /**
+     * is_decl_start:
+     * true if the statement at head is a declaration
+     * int a; A a; struct A a; std::string s; A *a;
+     */
+    fn is_decl_start(&self) -> bool {
+        let token = match self.peek() {
+            Some(token) => token,
+            None => return false,
+        };
+        if is_type_specifier(token) {
+            return true;
+        }
+        match token.get_token_type() {
+            KeywordStruct | KeywordUnion | KeywordClass | KeywordEnum => true,
+            Identifier => {
+                // skip qualified name std::string
+                let mut n = 0;
+                while self.check_nth(n + 1, ScopeResolution) && self.check_nth(n + 2, Identifier) {
+                    n += 2;
+                }
+                match self.nth_type(n + 1) {
+                    Some(Identifier) | Some(Main) | Some(KeywordOperator) => true,
+                    Some(Multiplication) | Some(BitwiseAnd) => {
+                        self.symbols.is_type(&token.get_token_value())
+                            && self.check_nth(n + 2, Identifier)
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
         }
-        self.in_main = false;
-        stream
     }
 
     /**
-     * parse-arguments:
-     * parse c/c++ formal arguments in the function signature
-     * into rust equivalent arguments
+     * parse_type_specifier:
+     * parse the type part of a declaration
+     * const unsigned long int, struct A, std::string
      */
-    fn parse_arguments(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head: usize = 0;
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        while head < lexeme.len() {
-            if lexeme[head].get_token_type() == Comma {
-                let mut arg_decl = self.parse_argument_declaration(&temp_lexeme);
-
-                stream.append(&mut arg_decl);
-                stream.push(",".to_string());
-                temp_lexeme.clear();
-                head += 1;
-                continue;
+    fn parse_type_specifier(&mut self) -> Type {
+        let mut ty = Type::primitive(Integer);
+        let mut base: Option<BaseType> = None;
+        let mut signed = false;
+        while let Some(token) = self.peek() {
+            match token.get_type() {
+                (_, KeywordConst) | (_, KeywordConstexpr) => ty.is_const = true,
+                (_, KeywordStatic) => ty.is_static = true,
+                // typename T::type
+                (_, KeywordTypename) => {}
+                (_, KeywordAlignas) => {
+                    self.bump();
+                    self.skip_brackets();
+                    continue;
+                }
+                (_, typ) if is_ignored_specifier(typ) => {}
+                (_, Signed) => {
+                    ty.modifier = Modifier::Signed;
+                    signed = true;
+                }
+                (_, Unsigned) => {
+                    ty.modifier = Modifier::Unsigned;
+                    signed = true;
+                }
+                // long long, long int, short int
+                (TokenKind::DataTypes, Integer) if base.is_some() => {}
+                (TokenKind::DataTypes, Long) | (TokenKind::DataTypes, Short) => {
+                    base = Some(BaseType::Primitive(token.get_token_type()))
+                }
+                (TokenKind::DataTypes, typ) => {
+                    if base.is_some() {
+                        break;
+                    }
+                    base = Some(BaseType::Primitive(typ));
+                }
+                (_, KeywordStruct) | (_, KeywordUnion) | (_, KeywordClass) if base.is_none() => {
+                    let typ = token.get_token_type();
+                    self.bump();
+                    let name = self.parse_ident();
+                    base = Some(match typ {
+                        KeywordUnion => BaseType::Union(name),
+                        KeywordStruct => BaseType::Struct(name),
+                        _ => BaseType::Named(name),
+                    });
+                    continue;
+                }
+                // enum Color, enum class Color : int
+                (_, KeywordEnum) if base.is_none() => {
+                    self.bump();
+                    if matches!(self.nth_type(0), Some(KeywordClass) | Some(KeywordStruct)) {
+                        self.bump();
+                    }
+                    base = Some(BaseType::Named(self.parse_ident()));
+                    if self.eat(Colon) {
+                        self.parse_type_specifier();
+                    }
+                    continue;
+                }
+                // ::std::string, the global namespace is the crate root
+                (_, ScopeResolution) if base.is_none() && self.check_nth(1, Identifier) => {}
+                (_, Identifier) if base.is_none() && !signed => {
+                    self.bump();
+                    let mut name = token.get_token_value();
+                    while self.check(ScopeResolution) && self.check_nth(1, Identifier) {
+                        self.bump();
+                        name.push_str("::");
+                        name.push_str(&self.parse_ident());
+                    }
+                    base = Some(BaseType::Named(name));
+                    continue;
+                }
+                _ => break,
             }
-            temp_lexeme.push(lexeme[head].clone());
-            head += 1;
+            self.bump();
         }
-        if !temp_lexeme.is_empty() {
-            let mut arg_decl = self.parse_argument_declaration(&temp_lexeme);
-            stream.append(&mut arg_decl);
+        if let Some(base) = base {
+            ty.base = base;
         }
-        stream
+        ty
     }
-    fn parse_argument_declaration(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut is_const = false;
-        let mut typ_index = 0;
-        if lexeme[0].get_token_type() == KeywordConst {
-            is_const = true;
-            typ_index = 1;
-        }
-        let arg_type;
-        // get the rust type
-        if let Some(rust_type) = parse_type(lexeme[typ_index].get_token_type(), Modifier::Default) {
-            arg_type = rust_type;
-        } else {
-            // if type parser dint return Some type, then it must be user defined type.
-            //TODO : should check the typedef table
-            arg_type = lexeme[typ_index].get_token_value();
-        }
-        let mut identifier_idx = 1;
 
-        let mut reference = false;
-        let reference_idx = 1 + typ_index;
-        if lexeme[reference_idx].get_token_kind() != Identifiers {
-            if lexeme[reference_idx].get_token_type() == BitwiseAnd
-                || lexeme[reference_idx].get_token_type() == Multiplication
+    /// pointer and reference part of a declarator, `* const *`, `&`
+    fn parse_pointers(&mut self, ty: &mut Type) {
+        loop {
+            if self.eat(Multiplication) {
+                ty.pointer += 1;
+            } else if self.eat(BitwiseAnd) || self.eat(LogicalAnd) {
+                ty.reference = true;
+            } else if !self.eat(KeywordConst)
+                && !self.eat(KeywordVolatile)
+                && !self.eat(KeywordRestrict)
             {
-                reference = true;
+                break;
             }
-            identifier_idx = reference_idx + 1;
         }
-        let identifier = lexeme[identifier_idx].get_token_value();
-
-        stream.push(identifier);
-        stream.push(":".to_string());
+    }
 
-        if reference {
-            stream.push("&".to_string());
-            if !self.strict && !is_const {
-                stream.push("mut".to_string());
+    /**
+     * parse_decl_name:
+     * name of the declared entity with the optional class it belongs to
+     * foo, A::foo, operator==, A::operator+
+     */
+    fn parse_decl_name(&mut self) -> PResult<(Option<String>, FunctionName)> {
+        let mut owner = None;
+        let mut name = self.parse_ident();
+        while self.check(ScopeResolution) {
+            self.bump();
+            owner = Some(name);
+            if self.check(BitwiseNegate) {
+                // destructor A::~A
+                self.bump();
+                name = format!("drop_{}", self.parse_ident());
+            } else {
+                name = self.parse_ident();
             }
         }
-        stream.push(arg_type);
-        stream
+        if self.eat(KeywordOperator) {
+            if let Some(token) = self.bump() {
+                let mut op = token.get_token_type();
+                // operator() and operator[]
+                if op == LeftBracket && self.check(RightBracket) {
+                    self.bump();
+                    op = RightBracket;
+                } else if op == LeftSquareBracket {
+                    self.expect(RightSquareBracket)?;
+                }
+                return Ok((owner, FunctionName::Operator(op)));
+            }
+        }
+        Ok((owner, FunctionName::Ident(name)))
     }
-    /**
-     * parse_declaration:
-     * parse c/c++ declaration into rust
-     * equivalent statements */
-    fn parse_declaration(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-
-        //  let mut sym_tab: Vec<SymbolTable> = Vec::new();
-        //self.sym_tab.clear();
-        let mut sym: SymbolTable = SymbolTable {
-            symbol_type: Others,
-            symbol_modifier: Modifier::Default,
-            id_name: "undefined_var".to_string(),
-            is_assigned: false,
-            is_ptr: false,
-            assigned_val: "NONE".to_string(),
-        };
-
-        //check if there is any modifier present
 
-        let (token_kind, token_type) = lexeme[0].get_type();
+    /**
+     * parse_function_or_global:
+     * parse either a function definition/prototype or a global declaration
+     */
+    fn parse_function_or_global(&mut self, items: &mut Vec<Item>) -> PResult<()> {
+        let start = self.head;
+        let base = self.parse_type_specifier();
+        let save = self.head;
+
+        let mut ret = base.clone();
+        self.parse_pointers(&mut ret);
+        let (owner, name) = self.parse_decl_name()?;
+        if self.check(LeftBracket) {
+            if let (None, FunctionName::Ident(ref ident)) = (&owner, &name) {
+                self.declare(ident, SymbolKind::Function, ret.clone(), start);
+            }
+            let function = self.parse_function_rest(start, ret, owner, name)?;
+            items.push(Item::Function(function));
+            return Ok(());
+        }
+
+        self.head = save;
+        let declarators = self.parse_declarators(&base)?;
+        self.expect(Semicolon)?;
+        items.push(Item::Global(Declaration {
+            declarators,
+            span: self.span_since(start),
+        }));
+        Ok(())
+    }
 
-        let mut type_index = 0;
-        if token_kind == TokenKind::Modifiers {
-            //type name can be found in next lexeme
-            type_index = 1;
-            match token_type {
-                Signed => {
-                    sym.symbol_modifier = Modifier::Signed;
-                }
-                Unsigned => {
-                    sym.symbol_modifier = Modifier::Unsigned;
+    /**
+     * parse_function_rest:
+     * parse parameters and body of a function whose name is already consumed
+     * `start` is where the function definition begins
+     */
+    fn parse_function_rest(
+        &mut self,
+        start: usize,
+        ret: Type,
+        owner: Option<String>,
+        name: FunctionName,
+    ) -> PResult<Function> {
+        self.expect(LeftBracket)?;
+        let (params, variadic) = self.parse_params()?;
+        self.expect(RightBracket)?;
+
+        // trailing qualifiers, int get() const noexcept override
+        let mut is_const = false;
+        loop {
+            match self.peek() {
+                Some(token) if matches!(token.get_token_type(), KeywordConst | KeywordVolatile) => {
+                    is_const |= token.get_token_type() == KeywordConst;
+                    self.bump();
                 }
-                KeywordStatic => {
-                    sym.symbol_modifier = Modifier::Static;
+                Some(token) if matches!(token.get_token_type(), KeywordNoexcept | KeywordThrow) => {
+                    self.bump();
+                    self.skip_brackets();
                 }
-                KeywordConst => {
-                    sym.symbol_modifier = Modifier::Const;
+                Some(token)
+                    if is_identifier(token)
+                        && matches!(token.get_token_value().as_str(), "override" | "final") =>
+                {
+                    self.bump();
                 }
-                _ => {}
+                _ => break,
             }
         }
 
-        let type_token = &lexeme[type_index];
-        let typdef_type = type_token.get_token_value(); //get the type name
-
-        let mut head: usize = type_index + 1;
-        //let sym_idx:usize=0;
-        while head < lexeme.len() {
-            match lexeme[head].get_token_type() {
-                Identifier => sym.id_name = lexeme[head].get_token_value(),
-
-                Assignment => {
-                    sym.is_assigned = true;
-                    sym.assigned_val = "".to_string();
-                    head += 1;
-                    let mut br = 0;
-                    if sym.is_ptr == true {
-                        if lexeme[head].get_token_type() == Null {
-                            while lexeme[head].get_token_type() != Semicolon
-                                && lexeme[head].get_token_type() != Comma
-                            {
-                                head += 1;
-                            }
-                            sym.is_assigned = false;
-                        } else {
-                            head += 1;
-                        }
-                    }
-                    let mut temp_lex: Vec<Token> = Vec::new();
-                    while lexeme[head].get_token_type() != Semicolon
-                        && !(br == 0 && lexeme[head].get_token_type() == Comma)
-                    {
-                        if lexeme[head].get_token_type() == LeftBracket {
-                            br += 1;
-                        }
-                        if lexeme[head].get_token_type() == RightBracket {
-                            br -= 1;
-                        }
-                        temp_lex.push(lexeme[head].clone());
-                        //parse assigned value for expression
-
-                        head += 1;
+        let body = if self.check(LeftCurlyBrace) {
+            Some(self.scoped(ScopeKind::Function, |parser| {
+                for param in &params {
+                    if let Some(ref name) = param.name {
+                        parser.declare(name, SymbolKind::Variable, param.ty.clone(), start);
                     }
-                    temp_lex.push(lexeme[head].clone());
-                    let a_val = self.parse_expr(&temp_lex);
-                    let mut a_value = String::new();
-                    for val in a_val {
-                        a_value = a_value + &val;
-                    }
-                    sym.assigned_val.push_str(a_value.as_str());
-
-                    continue;
                 }
+                parser.parse_block()
+            })?)
+        } else {
+            self.skip_stmt();
+            None
+        };
+        // virtual int area() const = 0;
+        let is_pure = body.is_none()
+            && self.lexeme[start..self.head]
+                .iter()
+                .filter(|t| t.get_token_kind() != TokenKind::Comments)
+                .rev()
+                .take(3)
+                .map(|t| t.get_token_value())
+                .eq(vec![";", "0", "="]);
+        Ok(Function {
+            ret,
+            owner,
+            name,
+            params,
+            body,
+            is_const,
+            is_pure,
+            variadic,
+            comments: Vec::new(),
+            span: self.span_since(start),
+        })
+    }
 
-                Semicolon | Comma => {
-                    // used enum value in the symbol table
-                    sym.symbol_type = type_token.get_token_type();
-                    //       println!("SYM TYPE {}",sym.typ);
-                    self.sym_tab.push(sym.clone());
-                }
-                //int * a ;
-                Multiplication => {
-                    sym.is_ptr = true;
-                }
-                _ => {
-                    sym.assigned_val.push_str(&lexeme[head].get_token_value());
-                }
+    /**
+     * parse_params:
+     * parse formal arguments of a function till the closing bracket, with
+     * the span of `...` taking variadic arguments
+     */
+    fn parse_params(&mut self) -> PResult<(Vec<Param>, Option<Span>)> {
+        let mut params = Vec::new();
+        let mut variadic = None;
+        if self.check(Void) && self.check_nth(1, RightBracket) {
+            self.bump();
+            return Ok((params, variadic));
+        }
+        while !self.at_end() && !self.check(RightBracket) {
+            // the closing bracket is missing
+            if matches!(
+                self.nth_type(0),
+                Some(Semicolon) | Some(LeftCurlyBrace) | Some(RightCurlyBrace)
+            ) {
+                return Err(self.error_expected("`)`"));
+            }
+            // variadic arguments are left out, rust functions can not take them
+            let at = self.head;
+            if self.eat(Ellipsis) {
+                variadic = Some(self.span_since(at));
+                continue;
+            }
+            let mut comments = self.take_comments();
+            let start = self.head;
+            let mut ty = self.parse_type_specifier();
+            self.parse_pointers(&mut ty);
+            let name = match self.peek() {
+                Some(token) if is_identifier(token) => Some(self.parse_ident()),
+                _ => None,
             };
-            head += 1;
-        }
-
-        if self.strict == false {
-            stream.push(NO_STRICT.get_doc().to_string());
-        } else {
-            stream.push(STRICT.get_doc().to_string());
-        }
-
-        //from `from` start declaration statement generation
-        let (_, sym_table_right) = self.sym_tab.split_at(self.from);
-        for i in sym_table_right {
-            // get identifier
-            //for declaration out of any blocks(global)
-            self.from += 1;
-            match i.symbol_modifier {
-                Modifier::Const => {
-                    stream.push("const".to_string());
-                }
-                _ => {
-                    if self.strict == false {
-                        if self.in_block_stmnt == true {
-                            stream.push("let mut".to_string());
-                        } else {
-                            stream.push("static mut".to_string());
-                        }
-                    } else {
-                        if self.in_block_stmnt == true {
-                            stream.push("let".to_string());
-                        } else {
-                            stream.push("static".to_string());
-                        }
-                    }
+            // int argv[] is passed as pointer
+            while self.eat(LeftSquareBracket) {
+                while !self.at_end() && !self.eat(RightSquareBracket) {
+                    self.bump();
                 }
+                ty.pointer += 1;
             }
-            stream.push(i.id_name.clone());
-            stream.push(":".to_string());
-
-            if i.is_ptr == true {
-                stream.push("&".to_string());
-
-                if self.strict == false {
-                    stream.push("mut".to_string());
+            // default value is not supported in rust
+            if self.eat(Assignment) {
+                self.parse_assign_expr()?;
+            }
+            comments.append(&mut self.take_trailing(start));
+            if self.eat(Comma) {
+                // a comment ending the line of the comma is the parameter's,
+                // one followed by the next parameter is that one's
+                let line = self.lexeme[self.head - 1].get_span().end.line;
+                if self
+                    .peek()
+                    .is_none_or(|next| next.get_span().start.line > line)
+                {
+                    comments.append(&mut self.take_trailing(self.head));
                 }
+            } else if self.head == start {
+                self.bump();
             }
-            // get the rust type
-            if let Some(rust_type) = parse_type(i.symbol_type, i.symbol_modifier) {
-                if rust_type == "_".to_string() {
-                    //not able to find the type, let the rust compiler do the type inference.
-                    stream.pop();
+            params.push(Param { name, ty, comments });
+        }
+        Ok((params, variadic))
+    }
+
+    /**
+     * parse_declarators:
+     * parse comma separated declarators sharing the base type
+     * int *a = &b, c[10], d = 5
+     */
+    fn parse_declarators(&mut self, base: &Type) -> PResult<Vec<Declarator>> {
+        let mut declarators = Vec::new();
+        loop {
+            let start = self.head;
+            let mut ty = base.clone();
+            self.parse_pointers(&mut ty);
+            let name = self.parse_ident();
+            while self.eat(LeftSquareBracket) {
+                if self.eat(RightSquareBracket) {
+                    ty.array.push(None);
                 } else {
-                    stream.push(rust_type);
+                    ty.array.push(Some(self.parse_expr()?));
+                    self.expect(RightSquareBracket)?;
                 }
+            }
+            let init = if self.eat(Assignment) {
+                Some(self.parse_initializer()?)
+            } else if self.check(LeftBracket) {
+                // constructor call: A a(1, 2); int a(5);
+                self.bump();
+                let args = self.parse_args()?;
+                let init = match ty.base {
+                    BaseType::Named(ref type_name) => Expr::Call {
+                        callee: Box::new(Expr::Ident(type_name.clone())),
+                        args,
+                    },
+                    _ => args
+                        .into_iter()
+                        .next()
+                        .unwrap_or(Expr::Verbatim(String::new())),
+                };
+                Some(Initializer::Expr(init))
             } else {
-                // if type parser dint return Some type, then it must be user defined type.
-                //TODO : should check the typedef table
-                stream.push(typdef_type.clone());
+                None
+            };
+            self.declare(&name, SymbolKind::Variable, ty.clone(), start);
+            declarators.push(Declarator {
+                name,
+                ty,
+                init,
+                span: self.span_since(start),
+            });
+            if !self.eat(Comma) {
+                break;
             }
+        }
+        Ok(declarators)
+    }
 
-            // take care of assignment
-            if i.is_assigned {
-                stream.push("=".to_string());
-                if i.is_ptr == true {
-                    stream.push("&".to_string());
-                }
-                if self.strict == false && i.is_ptr == true {
-                    stream.push("mut".to_string());
-                }
-
-                stream.push((&i.assigned_val).to_string());
+    fn parse_initializer(&mut self) -> PResult<Initializer> {
+        if !self.eat(LeftCurlyBrace) {
+            return Ok(Initializer::Expr(self.parse_assign_expr()?));
+        }
+        let mut list = Vec::new();
+        while !self.at_end() && !self.eat(RightCurlyBrace) {
+            list.push(self.parse_assign_expr()?);
+            if !self.eat(Comma) {
+                self.expect(RightCurlyBrace)?;
+                break;
             }
-            stream.push(";".to_string());
         }
-        stream
+        Ok(Initializer::List(list))
+    }
+
+    fn parse_declaration(&mut self) -> PResult<Declaration> {
+        let start = self.head;
+        let base = self.parse_type_specifier();
+        let declarators = self.parse_declarators(&base)?;
+        self.expect(Semicolon)?;
+        Ok(Declaration {
+            declarators,
+            span: self.span_since(start),
+        })
     }
 
     /* parse simple typedef definition of form
      * typedef typename newtype;
+     * typedef struct {..} newtype;
      */
-    fn parse_typdef(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        stream.push("type".to_string());
-        stream.push(lexeme[2].get_token_value() + "=");
-        self.typde_def_table.push(lexeme[2].get_token_value());
-        if let Some(typ) = parse_type(lexeme[1].get_token_type(), Modifier::Default) {
-            stream.push(typ);
+    fn parse_typedef(&mut self, items: &mut Vec<Item>) -> PResult<()> {
+        let start = self.head;
+        self.bump();
+        let ty = if matches!(
+            self.nth_type(0),
+            Some(KeywordStruct) | Some(KeywordUnion) | Some(KeywordClass)
+        ) && self.is_record_def()
+        {
+            let record = self.parse_record()?;
+            let ty = Type::new(BaseType::Named(record.name.clone()));
+            items.push(Item::Record(record));
+            ty
+        } else if self.check(KeywordEnum) && self.is_enum_def() {
+            let found = self.parse_enum()?;
+            let ty = Type::new(BaseType::Named(found.name.clone()));
+            items.push(Item::Enum(found));
+            ty
         } else {
-            stream.push(lexeme[1].get_token_value());
-        }
-        stream.push(";".to_string());
-        return stream;
+            self.parse_type_specifier()
+        };
+
+        let mut ty = ty;
+        self.parse_pointers(&mut ty);
+        let name = self.parse_ident();
+        self.skip_stmt();
+        self.declare(&name, SymbolKind::Type, ty.clone(), start);
+
+        // typedef struct {..} A; names the anonymous struct
+        match items.last_mut() {
+            Some(Item::Record(record)) if record.name.is_empty() => {
+                record.name = name;
+                return Ok(());
+            }
+            Some(Item::Enum(found)) if found.name.is_empty() => {
+                found.name = name;
+                return Ok(());
+            }
+            _ => {}
+        }
+        items.push(Item::Typedef(TypeAlias {
+            name,
+            ty,
+            span: self.span_since(start),
+        }));
+        Ok(())
     }
 
     /**
-     * parse_if:
-     * parse c/c++ if statements into rust
-     * equivalent statements
+     * parse_enum:
+     * parse enum and enum class definitions, with their underlying type
+     * the trailing declarators and semicolon are left for the caller
      */
-    fn parse_if(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head: usize = 0;
-
-        stream.push("if".to_string());
-        stream.push("(".to_string());
-        head += 1;
-
-        //skip '('
-        head += 1;
-
-        // condition
-        while lexeme[head].get_token_type() != RightBracket {
-            stream.push(lexeme[head].get_token_value());
-            head += 1;
-        }
-        head += 1;
-        stream.push(")".to_string());
-        stream.push("== true".to_string());
-        stream.push("{".to_string());
-
-        if lexeme[head].get_token_type() == LeftCurlyBrace {
-            head += 1;
-        }
-
-        // collect if body
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        while head < lexeme.len() {
-            let l: Token = lexeme[head].clone();
-            temp_lexeme.push(l);
-            head += 1;
+    fn parse_enum(&mut self) -> PResult<Enum> {
+        let start = self.head;
+        self.expect(KeywordEnum)?;
+        let scoped = matches!(self.nth_type(0), Some(KeywordClass) | Some(KeywordStruct));
+        if scoped {
+            self.bump();
+        }
+        let name = self.parse_ident();
+        let underlying = if self.eat(Colon) {
+            let head = self.head;
+            let underlying = self.parse_type_specifier();
+            // enum E : { A }
+            if self.head == head {
+                return Err(self.error_expected("the underlying type of the enum"));
+            }
+            Some(underlying)
+        } else {
+            None
+        };
+        let ty = Type::new(BaseType::Named(name.clone()));
+        self.declare(&name, SymbolKind::Type, ty.clone(), start);
+        self.expect(LeftCurlyBrace)?;
+        let mut enumerators = Vec::new();
+        loop {
+            let comments = self.take_comments();
+            if self.at_end() || self.check(RightCurlyBrace) {
+                break;
+            }
+            let item_start = self.head;
+            let name = self.expect_ident()?;
+            let value = if self.eat(Assignment) {
+                Some(self.parse_assign_expr()?)
+            } else {
+                None
+            };
+            let span = self.span_since(item_start);
+            let more = self.eat(Comma);
+            let mut comments = comments;
+            comments.append(&mut self.take_trailing(item_start));
+            // the enumerators of an unscoped enum are named without it
+            if !scoped {
+                let mut ty = ty.clone();
+                ty.is_const = true;
+                self.declare(&name, SymbolKind::Variable, ty, item_start);
+            }
+            enumerators.push(Enumerator {
+                name,
+                value,
+                comments,
+                span,
+            });
+            if !more {
+                break;
+            }
         }
-        // parse if body
-        stream.append(&mut self.parse_program(&temp_lexeme));
+        self.expect(RightCurlyBrace)?;
+        Ok(Enum {
+            name,
+            scoped,
+            underlying,
+            enumerators,
+            comments: Vec::new(),
+            span: self.span_since(start),
+        })
+    }
 
-        stream.push("}".to_string());
-        stream
+    /**
+     * parse_record:
+     * parse struct, class and union definitions
+     * the trailing declarators and semicolon are left for the caller
+     */
+    fn parse_record(&mut self) -> PResult<Record> {
+        let start = self.head;
+        let kind = match self.bump().map(|t| t.get_token_type()) {
+            Some(KeywordClass) => RecordKind::Class,
+            Some(KeywordUnion) => RecordKind::Union,
+            _ => RecordKind::Struct,
+        };
+        let name = self.parse_ident();
+        if !name.is_empty() {
+            let ty = Type::new(match kind {
+                RecordKind::Union => BaseType::Union(name.clone()),
+                RecordKind::Struct => BaseType::Struct(name.clone()),
+                RecordKind::Class => BaseType::Named(name.clone()),
+            });
+            self.declare(&name, SymbolKind::Type, ty, start);
+        }
+        let mut record = Record {
+            kind,
+            name,
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constructors: Vec::new(),
+            pure_methods: Vec::new(),
+            comments: Vec::new(),
+            span: Span::default(),
+        };
+        self.expect(LeftCurlyBrace)?;
+        self.scoped(ScopeKind::Class, |parser| parser.parse_members(&mut record))?;
+        self.expect(RightCurlyBrace)?;
+        record.span = self.span_since(start);
+        Ok(record)
     }
 
     /**
-     * parse_while:
-     * parse c/c++ while statements into rust
-     * equivalent statements
+     * parse_members:
+     * parse the members of a record till its closing brace, they are
+     * declared in the scope of the record
      */
-    fn parse_while(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head: usize = 0;
-        let mut no_cond = false;
-        head += 1;
-
-        //skip '('
-        head += 1;
-        // condition
-        let mut cond_stream: Vec<String> = Vec::new();
-        while lexeme[head].get_token_type() != RightBracket {
-            cond_stream.push(lexeme[head].get_token_value());
-            head += 1;
-        }
-        if cond_stream.len() == 1
-            && (cond_stream[0] == "1".to_string() || cond_stream[0] == "true".to_string())
-        {
-            no_cond = true;
-        }
-        head += 1;
+    fn parse_members(&mut self, record: &mut Record) -> PResult<()> {
+        let mut access = if record.kind == RecordKind::Class {
+            Access::Private
+        } else {
+            Access::Public
+        };
 
-        if lexeme[head].get_token_type() == LeftCurlyBrace {
-            head += 1;
-        }
+        // predicates of the #if branches kept as cfg
+        let mut cfg = Vec::new();
+        // comments before the next member
+        let mut comments = Vec::new();
+        while !self.at_end() && !self.check(RightCurlyBrace) {
+            comments.append(&mut self.take_comments());
+            let token = match self.peek() {
+                Some(token) => token,
+                None => break,
+            };
+            let start = self.head;
 
-        // collect while body
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        while head < lexeme.len() {
-            let l: Token = lexeme[head].clone();
-            temp_lexeme.push(l);
-            head += 1;
-        }
-        // parse while body
-        let mut body_stream = &mut self.parse_program(&temp_lexeme);
+            match token.get_token_type() {
+                HeaderIfDefineStart => {
+                    cfg.push(token.get_token_value());
+                    self.bump();
+                    continue;
+                }
+                HeaderIfDefineEnd => {
+                    cfg.pop();
+                    self.bump();
+                    continue;
+                }
+                _ => {}
+            }
 
-        if no_cond == true {
-            stream.push("loop".to_string());
-        } else {
-            stream.push("while".to_string());
-            stream.push("(".to_string());
-            stream.append(&mut cond_stream);
-            stream.push(")".to_string());
-            stream.push("== true".to_string());
-        }
-        stream.push("{".to_string());
-        stream.append(&mut body_stream);
+            // friends need no translation, rust has module privacy
+            if token.get_token_type() == KeywordFriend {
+                self.skip_stmt();
+                continue;
+            }
+            if token.get_token_type() == KeywordTemplate {
+                self.skip_stmt();
+                self.errors.push(ParseError::new(
+                    "template members are not translated".to_string(),
+                    self.span_since(start),
+                ));
+                continue;
+            }
 
-        stream.push("}".to_string());
-        stream
-    }
+            if is_access_specifier(token) && self.check_nth(1, Colon) {
+                access = match token.get_token_type() {
+                    KeywordPublic => Access::Public,
+                    KeywordProtected => Access::Protected,
+                    _ => Access::Private,
+                };
+                self.bump();
+                self.bump();
+                continue;
+            }
 
-    /**
-     * parse_dowhile:
-     * parse c/c++ do while statements into rust
-     * equivalent statements
-     */
-    fn parse_dowhile(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut temp_stream: Vec<String> = Vec::new();
-        let mut head: usize = 0;
-        let mut lookahead: usize;
-
-        head += 2;
-        lookahead = head;
-
-        lookahead = skip_block(&lexeme, lookahead) - 1;
-        // collect while body
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        while head < lookahead {
-            let l: Token = lexeme[head].clone();
-            temp_lexeme.push(l);
-            head += 1;
-        }
-        // parse while body
-
-        temp_stream.append(&mut self.parse_program(&temp_lexeme));
-        temp_lexeme.clear();
-
-        head += 3;
-        if (lexeme[head].get_token_value() == String::from("1")
-            || lexeme[head].get_token_value() == String::from("true"))
-            && lexeme[head + 1].get_token_type() == RightBracket
-        {
-            stream.push("loop".to_string());
-            stream.push("{".to_string());
-            stream.append(&mut temp_stream);
+            // explicit A(int); virtual ~A();
+            while matches!(
+                self.nth_type(0),
+                Some(KeywordExplicit) | Some(KeywordVirtual) | Some(KeywordInline)
+            ) {
+                self.bump();
+            }
+            let token = match self.peek() {
+                Some(token) => token,
+                None => break,
+            };
 
-            stream.push("}".to_string());
-        } else {
-            stream.push("while".to_string());
-            stream.push("{".to_string());
-            stream.append(&mut temp_stream);
-            stream.push("(".to_string());
-            while lexeme[head].get_token_type() != RightBracket {
-                stream.push(lexeme[head].get_token_value());
-                head += 1;
-            }
-            stream.push(")".to_string());
-            stream.push("== true".to_string());
-            stream.push("}".to_string());
-            stream.push("{".to_string());
-            stream.push("}".to_string());
-        }
-        stream.push(";".to_string());
-        stream
-    }
-
-    fn parse_switch(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut head: usize = 2;
-        let mut lookahead: usize = 2;
-        let mut stream: Vec<String> = Vec::new();
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-
-        stream.push("match".to_string());
-
-        // find starting of switch block
-        while lexeme[lookahead].get_token_type() != LeftCurlyBrace {
-            lookahead += 1;
-        }
-        // {
-        // move back to find the variable/result to be matched
-        lookahead -= 1;
-        // single variable
-        if lookahead - head == 1 {
-            stream.push(lexeme[lookahead - 1].get_token_value());
-        }
-        // expression
-        else {
-            while head < lookahead {
-                let l: Token = lexeme[head].clone();
-                temp_lexeme.push(l);
-                head += 1;
-            }
-            head -= 1;
-            stream.append(&mut self.parse_program(&temp_lexeme));
-
-            temp_lexeme.clear();
-        }
-        // move forward to the starting of the block
-        head += 3;
-        stream.push("{".to_string());
-
-        //head is at case
-        lookahead = skip_block(&lexeme, head) - 1;
-        while head < lookahead {
-            let l: Token = lexeme[head].clone();
-            temp_lexeme.push(l);
-            head += 1;
-        }
-        stream.append(&mut self.parse_case(&temp_lexeme));
-        stream.push("}".to_string());
-        stream
-    }
-
-    fn parse_case(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        //head is at case
-        let mut head: usize = 0;
-        let mut lookahead: usize;
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        let mut def: bool = false;
-        //look whether default case is handled for exaustive search
-        while head < lexeme.len() {
-            if lexeme[head].get_token_type() == KeywordDefault {
-                stream.push("_".to_string());
-                def = true;
-            } else {
-                head += 1; //head is at matching value
-                stream.push(lexeme[head].get_token_value());
+            // constructor A() {..} and destructor ~A() {..}
+            let is_ctor = token.get_token_value() == record.name && self.check_nth(1, LeftBracket);
+            let is_dtor = token.get_token_type() == BitwiseNegate && self.check_nth(2, LeftBracket);
+            if is_ctor || is_dtor {
+                while !self.at_end() && !self.check(LeftCurlyBrace) && !self.check(Semicolon) {
+                    self.bump();
+                }
+                if self.check(LeftCurlyBrace) {
+                    self.skip_block();
+                } else {
+                    self.bump();
+                }
+                // the comments of the constructor are kept with its source
+                let mut text: Vec<String> = comments.drain(..).map(|c| c.text).collect();
+                text.push(self.text_between(start, self.head));
+                record.constructors.push(SourceText {
+                    text: text.join("\n"),
+                    span: self.span_since(start),
+                });
+                continue;
             }
 
-            head += 1; // head is at :
-            stream.push("=>".to_string());
+            if token.get_token_type() == Semicolon {
+                self.bump();
+                continue;
+            }
 
-            // either brace or no brace
-            head += 1;
-            if lexeme[head].get_token_type() == LeftCurlyBrace {
-                head += 1;
-                lookahead = skip_block(&lexeme, head) - 1;
+            let base = self.parse_type_specifier();
+            let save = self.head;
+            let mut ret = base.clone();
+            self.parse_pointers(&mut ret);
+            let (_, name) = self.parse_decl_name()?;
+            if self.check(LeftBracket) {
+                let mut function = self.parse_function_rest(start, ret, None, name)?;
+                // kept with its comments, like constructors
+                if function.is_pure {
+                    let mut text: Vec<String> = comments.drain(..).map(|c| c.text).collect();
+                    text.push(self.text_between(start, self.head));
+                    record.pure_methods.push(SourceText {
+                        text: text.join("\n"),
+                        span: self.span_since(start),
+                    });
+                    continue;
+                }
+                function.comments = std::mem::take(&mut comments);
+                function.comments.append(&mut self.take_trailing(start));
+                record.methods.push(Method {
+                    access,
+                    function,
+                    cfg: cfg.clone(),
+                });
             } else {
-                lookahead = head;
-                while lookahead < lexeme.len()
-                    && lexeme[lookahead].get_token_type() != KeywordCase
-                    && lexeme[lookahead].get_token_type() != KeywordDefault
-                {
-                    lookahead += 1;
+                self.head = save;
+                let first = record.fields.len();
+                for decl in self.parse_declarators(&base)? {
+                    record.fields.push(Field {
+                        access,
+                        decl,
+                        cfg: cfg.clone(),
+                        comments: Vec::new(),
+                    });
+                }
+                self.expect(Semicolon)?;
+                // the doc goes to the first declarator, the comment of the line to the last
+                if let Some(field) = record.fields.get_mut(first) {
+                    field.comments = std::mem::take(&mut comments);
+                }
+                let mut trailing = self.take_trailing(start);
+                if let Some(field) = record.fields.last_mut() {
+                    field.comments.append(&mut trailing);
                 }
             }
-            while head < lookahead {
-                let l: Token = lexeme[head].clone();
-                temp_lexeme.push(l);
-                head += 1;
-            }
-            stream.push("{".to_string());
-            stream.append(&mut self.parse_program(&temp_lexeme));
-            stream.push("}".to_string());
-
-            if head < lexeme.len() && lexeme[head].get_token_type() == RightCurlyBrace {
-                head += 1;
+            if self.head == start {
+                self.bump();
             }
-            temp_lexeme.clear();
-        }
-        if def == false {
-            stream.push("_".to_string());
-            stream.push("=>".to_string());
-            stream.push("{".to_string());
-            stream.push("}".to_string());
         }
-        stream
+        comments.append(&mut self.take_comments());
+        record
+            .comments
+            .extend(comments.into_iter().map(|comment| Comment {
+                trailing: true,
+                ..comment
+            }));
+        Ok(())
     }
 
     /**
-     * parse_for:
-     * parse c/c++ do while statements into rust
-     * equivalent statements
-     *
-     * Identify infinite loops and replace for with loop{}
+     * parse_block:
+     * parse brace enclosed statements
      */
-    fn parse_for(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head: usize = 0;
-        let mut lookahead: usize;
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-
-        while lexeme[head].get_token_type() != LeftBracket {
-            head += 1;
-        }
-        head += 1;
-        lookahead = head;
-
-        //for (int i =0; )
-        let decl: bool = if lexeme[head].get_token_kind() == TokenKind::DataTypes {
-            true
-        } else {
-            false
-        };
-        // let mut no_init:bool; //no initialization
-        let mut no_cond: bool = false; //if no condition to terminate
-        let mut no_updation: bool = false; //no inc/dec of loop counter
-
-        let mut body: Vec<String> = Vec::new();
-        let mut updation: Vec<String> = Vec::new();
-        let mut term_cond: Vec<String> = Vec::new();
-        // initial assignment
-        lookahead = skip_stmt(&lexeme, lookahead);
-
-        //incase of initialization expressio for (;i<10;i++) ; common case
-        if head + 1 < lookahead {
-            while head < lookahead {
-                let l: Token = lexeme[head].clone();
-                temp_lexeme.push(l);
-                head += 1;
-            }
-
-            if decl == true {
-                stream.append(&mut self.parse_declaration(&temp_lexeme));
-            } else {
-                stream.append(&mut self.parse_assignment(&temp_lexeme));
+    fn parse_block(&mut self) -> PResult<Block> {
+        let mut block = Block::default();
+        self.expect(LeftCurlyBrace)?;
+        self.symbols.enter(ScopeKind::Block);
+        loop {
+            for comment in self.take_comments() {
+                block.stmts.push(Stmt::Comment(comment));
             }
-        } else {
-            head += 1;
-            // no_init = true;
-        }
-        temp_lexeme.clear();
-
-        // terminating condition
-        lookahead = skip_stmt(&lexeme, lookahead);
-
-        if head + 1 < lookahead {
-            while head < lookahead - 1 {
-                term_cond.push(lexeme[head].get_token_value());
-                head += 1;
+            if self.at_end() || self.check(RightCurlyBrace) {
+                break;
             }
-        } else {
-            no_cond = true;
-        }
-        head += 1;
-        temp_lexeme.clear();
-
-        lookahead = head;
-        // update expression
-        while lexeme[lookahead].get_token_type() != RightBracket {
-            let l: Token = lexeme[lookahead].clone();
-            temp_lexeme.push(l);
-            lookahead += 1;
+            self.parse_stmt_into(&mut block.stmts);
         }
-        //no_updation
-        if head == lookahead {
-            no_updation = true;
-        } else {
-            temp_lexeme.push(Token::new(
-                String::from(";"),
-                TokenKind::SpecialChars,
-                Semicolon,
-                0,
-                0,
-            ));
-            updation.append(&mut self.parse_program(&temp_lexeme));
-            temp_lexeme.clear();
-        }
-        head = lookahead;
-        head += 1;
-        if lexeme[head].get_token_type() == LeftCurlyBrace {
-            head += 1;
-            lookahead = skip_block(&lexeme, head);
-        } else {
-            lookahead = skip_stmt(&lexeme, head);
+        self.symbols.leave();
+        // an unclosed block is reported and kept
+        if let Err(error) = self.expect(RightCurlyBrace) {
+            self.errors.push(error);
         }
+        Ok(block)
+    }
 
-        // lookahead = skip_block(&lexeme, lookahead);
-        while head < lookahead {
-            let l: Token = lexeme[head].clone();
-            temp_lexeme.push(l);
-            head += 1;
-        }
-        body.append(&mut self.parse_program(&temp_lexeme));
+    /// statement followed by its trailing comments
+    fn parse_stmt_into(&mut self, stmts: &mut Vec<Stmt>) {
+        let start = self.head;
+        stmts.push(self.parse_stmt_or_recover());
+        let trailing = self.take_trailing(start);
+        stmts.extend(trailing.into_iter().map(Stmt::Comment));
+    }
 
-        if no_cond == true {
-            stream.push("loop".to_string());
-        } else {
-            stream.push("while".to_string());
-            stream.append(&mut term_cond); //append termianating condition
-        }
-        stream.push("{".to_string());
-        stream.append(&mut body);
-        if no_updation != true {
-            stream.append(&mut updation);
+    /// statement, or the skipped source when it could not be parsed
+    fn parse_stmt_or_recover(&mut self) -> Stmt {
+        let start = self.head;
+        self.untranslated = None;
+        match self.parse_stmt() {
+            Ok(stmt) => stmt,
+            Err(error) => match self.recover_untranslated(start) {
+                Some((doc, found)) => Stmt::Unsupported {
+                    doc,
+                    text: found.text,
+                    span: found.span,
+                },
+                None => Stmt::Error(self.recover(start, error)),
+            },
         }
+    }
 
-        stream.push("}".to_string());
-
-        stream
+    /// statements of an #if branch kept as cfg
+    fn parse_cfg_stmts(&mut self) -> PResult<Stmt> {
+        let predicate = self.bump().map(|t| t.get_token_value()).unwrap_or_default();
+        let mut stmts = Vec::new();
+        loop {
+            for comment in self.take_comments() {
+                stmts.push(Stmt::Comment(comment));
+            }
+            if self.at_end() || self.check(HeaderIfDefineEnd) || self.check(RightCurlyBrace) {
+                break;
+            }
+            self.parse_stmt_into(&mut stmts);
+        }
+        self.expect(HeaderIfDefineEnd)?;
+        Ok(Stmt::Cfg { predicate, stmts })
     }
 
-    /* parse_assignment:
-     * parse c/c++ assignment statements into rust equivalent code
-     * compound assignments must be converted to declarations
-     * as rust doesnt support compound assignment
+    /**
+     * parse_body:
+     * parse the body of if, else and loops, which is either a block
+     * or a single statement. Comments before a single statement are kept with it
      */
-    fn parse_assignment(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        // let mut lookahead = lexeme.len();
-        let mut thead: usize = 2;
-        let mut lexeme1: Vec<Token> = Vec::new();
-
-        let mut n = 2;
-        let m = 3;
-
-        let mut tstream: Vec<String> = Vec::new();
-        if lexeme[n].get_token_kind() == TokenKind::UnaryOperators {
-            while lexeme[thead].get_token_type() != Semicolon {
-                lexeme1.push(lexeme[thead].clone());
-                thead += 1;
-            }
-            lexeme1.push(lexeme[thead].clone());
-            stream.push(lexeme[0].get_token_value());
-            stream.push(lexeme[1].get_token_value());
-            stream.append(&mut self.parse_expr(&lexeme1));
-        } else if lexeme[m].get_token_kind() == TokenKind::UnaryOperators {
-            while lexeme[thead].get_token_type() != Semicolon {
-                lexeme1.push(lexeme[thead].clone());
-                thead += 1;
-            }
-            lexeme1.push(lexeme[thead].clone());
-            stream.push(lexeme[0].get_token_value());
-            stream.push(lexeme[1].get_token_value());
-            stream.append(&mut self.parse_expr(&lexeme1));
-        } else if lexeme[m].get_token_kind() == TokenKind::BinaryOperators {
-            while lexeme[thead].get_token_type() != Semicolon {
-                lexeme1.push(lexeme[thead].clone());
-                thead += 1;
-            }
-            lexeme1.push(lexeme[thead].clone());
-            stream.push(lexeme[0].get_token_value());
-            stream.push(lexeme[1].get_token_value());
-            stream.append(&mut self.parse_expr(&lexeme1));
-        } else if lexeme[n].get_token_type() == BitwiseAnd {
-            stream.push(lexeme[0].get_token_value());
-            stream.push(lexeme[1].get_token_value());
-
-            while lexeme[thead].get_token_type() != Semicolon {
-                stream.push(lexeme[thead].get_token_value());
-                thead += 1;
+    fn parse_body(&mut self) -> PResult<Stmt> {
+        let comments = self.take_comments();
+        let stmt = self.parse_stmt()?;
+        if comments.is_empty() {
+            return Ok(stmt);
+        }
+        let mut stmts: Vec<Stmt> = comments.into_iter().map(Stmt::Comment).collect();
+        match stmt {
+            Stmt::Block(block) => stmts.extend(block.stmts),
+            stmt => stmts.push(stmt),
+        }
+        Ok(Stmt::Block(Block { stmts }))
+    }
+
+    fn parse_stmt(&mut self) -> PResult<Stmt> {
+        let typ = match self.nth_type(0) {
+            Some(typ) => typ,
+            None => return Ok(Stmt::Empty),
+        };
+        match typ {
+            LeftCurlyBrace => Ok(Stmt::Block(self.parse_block()?)),
+            Directive => {
+                let value = self.bump().map(|t| t.get_token_value());
+                Ok(Stmt::Comment(Comment {
+                    text: format!("// {}", value.unwrap_or_default()),
+                    trailing: false,
+                }))
             }
-        } else {
-            if lexeme[m].get_token_type() == Assignment {
-                while lexeme[thead].get_token_type() != Semicolon
-                    && lexeme[thead].get_token_type() != Comma
-                {
-                    lexeme1.push(lexeme[thead].clone());
-                    thead += 1;
+            HeaderIfDefineStart => self.parse_cfg_stmts(),
+            KeywordIf => self.parse_if(),
+            KeywordWhile => self.parse_while(),
+            KeywordDo => self.parse_dowhile(),
+            // the declaration of the init ends with the loop
+            KeywordFor => self.scoped(ScopeKind::Block, Self::parse_for),
+            KeywordSwitch => self.parse_switch(),
+            KeywordReturn => {
+                let start = self.head;
+                self.bump();
+                if self.eat(Semicolon) {
+                    return Ok(Stmt::Return(None, self.span_since(start)));
                 }
-                lexeme1.push(lexeme[thead].clone());
-                stream.append(&mut self.parse_program(&lexeme1));
+                let value = self.parse_expr()?;
+                self.expect(Semicolon)?;
+                Ok(Stmt::Return(Some(value), self.span_since(start)))
             }
-            stream.push(lexeme[0].get_token_value());
-            stream.push(lexeme[1].get_token_value());
-            if lexeme[n].get_token_kind() == TokenKind::UnaryOperators {
-                stream.push(lexeme[m].get_token_value());
-            } else {
-                stream.push(lexeme[n].get_token_value());
-                n += 1;
-                if lexeme[n].get_token_type() == LeftBracket
-                    || lexeme[n].get_token_type() == LeftSquareBracket
-                {
-                    while lexeme[n].get_token_type() != Semicolon {
-                        stream.push(lexeme[n].get_token_value());
-                        n += 1;
-                    }
+            KeywordBreak => {
+                let start = self.head;
+                self.bump();
+                self.expect(Semicolon)?;
+                Ok(Stmt::Break(self.span_since(start)))
+            }
+            KeywordContinue => {
+                let start = self.head;
+                self.bump();
+                self.expect(Semicolon)?;
+                Ok(Stmt::Continue(self.span_since(start)))
+            }
+            Semicolon => {
+                self.bump();
+                Ok(Stmt::Empty)
+            }
+            Using => self.parse_unsupported(USING_STMT),
+            KeywordNamespace => self.parse_unsupported(NAMESPACE),
+            KeywordTemplate => self.parse_unsupported(TEMPLATE),
+            KeywordThrow => self.parse_unsupported(EXCEPTION),
+            KeywordGoto => self.parse_unsupported(GOTO),
+            KeywordDelete | KeywordAsm | KeywordStaticAssert | KeywordExport => {
+                self.parse_unsupported(KEYWORD)
+            }
+            KeywordTry => {
+                let start = self.head;
+                self.bump();
+                self.skip_block();
+                while self.eat(KeywordCatch) {
+                    self.skip_brackets();
+                    self.skip_block();
                 }
-                stream.push(";".to_string());
+                Ok(Stmt::Unsupported {
+                    doc: EXCEPTION,
+                    text: self.text_between(start, self.head),
+                    span: self.span_since(start),
+                })
             }
-        }
-        if tstream.len() > 0 {
-            stream.append(&mut tstream);
-        }
-        stream
-    }
-
-    /* parse_expr:
-     * parse c/c++ expression statements into rust equivalent code
-     */
-    fn parse_expr(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        // let mut lookahead = lexeme.len();
-        let mut tstream: Vec<String> = Vec::new();
-        let mut thead: usize = 0;
-
-        let mut prev_id = " ".to_string();
-        let mut typ = Others;
-        //a=b+c++;
-
-        while lexeme[thead].get_token_type() != Semicolon {
-            if lexeme[thead].get_token_kind() == TokenKind::UnaryOperators {
-                if lexeme[thead].get_token_type() == SizeOf {
-                    //println!(" {:?} ",lexeme);
-                    stream.push("std::mem::size_of(".to_string());
-                    thead += 2;
-                    if lexeme[thead].get_token_kind() == TokenKind::DataTypes {
-                        if let Some(t) =
-                            parse_type(lexeme[thead].get_token_type(), Modifier::Default)
-                        {
-                            stream.push(t)
-                        }
-                    } else {
-                        stream.push(lexeme[thead].get_token_value());
-                    }
-                    stream.push(")".to_string());
-                    thead += 1;
+            // label of goto, `end:`
+            Identifier if self.check_nth(1, Colon) => {
+                let start = self.head;
+                self.bump();
+                self.bump();
+                Ok(Stmt::Unsupported {
+                    doc: GOTO,
+                    text: self.text_between(start, self.head),
+                    span: self.span_since(start),
+                })
+            }
+            _ if self.is_decl_start() => Ok(Stmt::Decl(self.parse_declaration()?)),
+            _ => {
+                let start = self.head;
+                let expr = self.parse_expr()?;
+                self.expect(Semicolon)?;
+                //check if overloaded operators is in effect like << >>
+                if is_stream_op(&expr) {
+                    Ok(Stmt::Unsupported {
+                        doc: STREAM_OP,
+                        text: self.text_between(start, self.head),
+                        span: self.span_since(start),
+                    })
                 } else {
-                    //println!(" 1542 :unop");
-                    //incase of post
-                    if typ == Identifier {
-                        tstream.push(prev_id.clone());
-                        tstream.push(match lexeme[thead].get_token_type() {
-                            Increment => "+=1".to_string(),
-                            Decrement => "-=1".to_string(),
-                            _ => " ;".to_string(),
-                        });
-                        tstream.push(";".to_string());
-
-                        thead += 1;
-                    //continue;
-                    }
-                    // incase of pre
-                    else {
-                        stream.push("(".to_string());
-                        stream.push(lexeme[thead + 1].get_token_value());
-                        stream.push(match lexeme[thead].get_token_type() {
-                            Increment => "+=1".to_string(),
-                            Decrement => "-=1".to_string(),
-                            _ => " ;".to_string(),
-                        });
-                        stream.push(")".to_string());
-                        thead += 1;
-                    }
+                    Ok(Stmt::Expr(expr, self.span_since(start)))
                 }
-            } else {
-                stream.push(lexeme[thead].get_token_value());
             }
-
-            typ = lexeme[thead].get_token_type();
-            prev_id = lexeme[thead].get_token_value();
-
-            thead += 1;
         }
-        stream.push(";".to_string());
-        if tstream.len() > 0 {
-            stream.append(&mut tstream);
-        }
-        stream
     }
 
-    fn parse_array_declaration(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut typ: String = " ".to_string();
+    /// statement kept as is and reported with `doc`
+    fn parse_unsupported(&mut self, doc: DocType) -> PResult<Stmt> {
+        let start = self.head;
+        self.skip_stmt();
+        Ok(Stmt::Unsupported {
+            doc,
+            text: self.text_between(start, self.head),
+            span: self.span_since(start),
+        })
+    }
 
-        //int a[10];
-        if let Some(t) = parse_type(lexeme[0].get_token_type(), Modifier::Default) {
-            typ = t;
-        }
-        if self.strict == true {
-            stream.push(STRICT.get_doc().to_string());
-            stream.push("let".to_string());
-        } else {
-            stream.push(NO_STRICT.get_doc().to_string());
-            stream.push("let mut".to_string());
-        }
-
-        let mut head = 0;
-        stream.push(lexeme[head + 1].get_token_value());
-        stream.push(":".to_string());
-        stream
-            .push("[".to_string() + &typ[..] + ";" + &lexeme[head + 3].get_token_value()[..] + "]");
-        head = 5;
-        let mut lookahead = head;
-        while lexeme[lookahead].get_token_type() != Semicolon {
-            lookahead += 1;
-        }
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        if lexeme[head].get_token_type() == Comma {
-            temp_lexeme.push(lexeme[0].clone());
-            //move to next
-            head += 1;
-            while lexeme[head].get_token_type() != Semicolon {
-                temp_lexeme.push(lexeme[head].clone());
-                head += 1;
-            }
-            stream.push(";".to_string());
-            temp_lexeme.push(lexeme[head].clone());
-            stream.append(&mut self.parse_program(&temp_lexeme));
-        } else if lexeme[head].get_token_type() == Assignment {
-            while lexeme[head].get_token_type() != Semicolon
-                && lexeme[head].get_token_type() != RightCurlyBrace
-            {
-                stream.push(match lexeme[head].get_token_type() {
-                    LeftCurlyBrace => "[".to_string(),
+    /// `( expr )` of if, while and switch
+    fn parse_condition(&mut self) -> PResult<Expr> {
+        self.expect(LeftBracket)?;
+        let cond = self.parse_expr()?;
+        self.expect(RightBracket)?;
+        Ok(cond)
+    }
 
-                    _ => lexeme[head].get_token_value(),
-                });
-                head += 1;
-            }
-            stream.push("]".to_string());
-            stream.push(";".to_string());
+    fn parse_if(&mut self) -> PResult<Stmt> {
+        let start = self.head;
+        self.bump();
+        let cond = self.parse_condition()?;
+        let then = Box::new(self.parse_body()?);
+        let otherwise = if self.eat(KeywordElse) {
+            Some(Box::new(self.parse_body()?))
         } else {
-            stream.push(";".to_string());
-        }
-
-        stream
-    }
-
-    // not tested
-    fn parse_struct(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head: usize = 0;
-        stream.push(lexeme[head].get_token_value()); //push the keyword struct
-        head += 1;
-        //push the struct id_name
-        stream.push(lexeme[head].get_token_value()); //push the struct name
-        let name = lexeme[head].get_token_value();
-        stream.push("{".to_string());
-        head += 2;
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        while lexeme[head].get_token_type() != RightCurlyBrace {
-            while lexeme[head].get_token_type() != Semicolon {
-                temp_lexeme.push(lexeme[head].clone());
-                head += 1
-            }
-            temp_lexeme.push(lexeme[head].clone());
-            head += 1;
-            stream.append(&mut self.parse_struct_inbody_decl(&temp_lexeme, &name));
-            temp_lexeme.clear();
-        }
-        stream.push(lexeme[head].get_token_value() + "\n");
-
-        stream
-    }
-
-    // not tested
-    fn parse_struct_inbody_decl(&mut self, lexeme: &Vec<Token>, name: &String) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head = 0;
-        //push the identifier
-        stream.push(lexeme[head + 1].get_token_value());
-        stream.push(":".to_string());
-        let mut struct_memt = CStructMem {
-            identifier: "NONE".to_string(),
-            name: name.clone(),
-            member_type: TokenType::Others,
+            None
         };
+        Ok(Stmt::If {
+            cond,
+            then,
+            otherwise,
+            span: self.span_since(start),
+        })
+    }
 
-        let mut rust_type = "RUST_TYPE".to_string();
-        if let Some(rust_typ) = parse_type(lexeme[head].get_token_type(), Modifier::Default) {
-            rust_type = rust_typ.clone();
-            stream.push(rust_typ);
-            struct_memt.member_type = lexeme[head].get_token_type();
-            struct_memt.identifier = lexeme[head + 1].get_token_value();
-        }
-        head += 2;
-        stream.push(",".to_string());
-        self.struct_mem.push(struct_memt.clone());
-        while lexeme[head].get_token_type() != Semicolon {
-            if lexeme[head].get_token_type() == Comma {
-                head += 1;
-            }
-            struct_memt.identifier = lexeme[head].get_token_value();
-            stream.push(lexeme[head].get_token_value());
-            stream.push(":".to_string());
-            stream.push(rust_type.clone());
-            head += 1;
-            stream.push(",".to_string());
-            self.struct_mem.push(struct_memt.clone());
-        }
-        stream
-    }
-
-    // not tested
-    fn parse_struct_decl(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-
-        stream.push(STRUCT_INIT.get_doc().to_string());
-        stream.push("let".to_string());
-        let mut head = 1;
-        //struct FilePointer fp;
-        let struct_name = lexeme[head].get_token_value();
-        head += 1;
-        stream.push(lexeme[head].get_token_value()); //push the identifer => let a
-        stream.push("=".to_string());
-        stream.push(struct_name.clone());
-        stream.push("{".to_string());
-
-        for row in &self.struct_mem {
-            if row.name == struct_name {
-                stream.push(row.identifier.clone());
-                stream.push(":".to_string());
-                stream.push(get_default_value_for(row.member_type));
-                stream.push(",".to_string());
-            }
-        }
-        stream.push("};".to_string());
-
-        stream
-    }
-
-    //parse tagged union
-    fn parse_union(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head: usize = 0;
-        stream.push("enum".to_string()); //push the keyword union
-        head += 1;
-        //push the struct id_name
-        stream.push(lexeme[head].get_token_value()); //push the struct name
-        let name = lexeme[head].get_token_value();
-        stream.push("{".to_string());
-        head += 2;
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        while lexeme[head].get_token_type() != RightCurlyBrace {
-            while lexeme[head].get_token_type() != Semicolon {
-                temp_lexeme.push(lexeme[head].clone());
-                head += 1
-            }
-            temp_lexeme.push(lexeme[head].clone());
-            head += 1;
-            stream.append(&mut self.parse_union_inbody_decl(&temp_lexeme, &name));
-            temp_lexeme.clear();
-        }
-        stream.push(lexeme[head].get_token_value() + "\n");
-
-        stream
-    }
-
-    /* parse union type declarations
-     * input : union tag_name var [;= ...]
-     * output : let [mut] variant_name = Sometype_variant
-     */
-    fn parse_union_decl(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head: usize = 0;
-
-        stream.push(UNION_DECL.get_doc().to_string());
-
-        //push the keyword let
-        stream.push("let".to_string());
-        if !self.strict {
-            stream.push("mut".to_string());
-        }
-
-        stream.push(lexeme[head + 2].get_token_value());
-        head += 3;
-        while lexeme[head].get_token_type() != Semicolon {
-            stream.push(lexeme[head].get_token_value());
-            head += 1;
-        }
+    fn parse_while(&mut self) -> PResult<Stmt> {
+        let start = self.head;
+        self.bump();
+        let cond = self.parse_condition()?;
+        let body = Box::new(self.parse_body()?);
+        Ok(Stmt::While {
+            cond,
+            body,
+            span: self.span_since(start),
+        })
+    }
 
-        stream.push(";".to_string());
+    fn parse_dowhile(&mut self) -> PResult<Stmt> {
+        let start = self.head;
+        self.bump();
+        let body = Box::new(self.parse_body()?);
+        self.expect(KeywordWhile)?;
+        let cond = self.parse_condition()?;
+        self.expect(Semicolon)?;
+        Ok(Stmt::DoWhile {
+            body,
+            cond,
+            span: self.span_since(start),
+        })
+    }
 
-        stream
+    fn parse_for(&mut self) -> PResult<Stmt> {
+        let start = self.head;
+        self.bump();
+        self.expect(LeftBracket)?;
+        let init = if self.eat(Semicolon) {
+            None
+        } else if self.is_decl_start() {
+            Some(Box::new(Stmt::Decl(self.parse_declaration()?)))
+        } else {
+            let at = self.head;
+            let init = self.parse_expr()?;
+            self.expect(Semicolon)?;
+            Some(Box::new(Stmt::Expr(init, self.span_since(at))))
+        };
+        let cond = if self.check(Semicolon) {
+            None
+        } else {
+            Some(self.parse_expr()?)
+        };
+        self.expect(Semicolon)?;
+        let step = if self.check(RightBracket) {
+            None
+        } else {
+            Some(self.parse_expr()?)
+        };
+        self.expect(RightBracket)?;
+        let body = Box::new(self.parse_body()?);
+        Ok(Stmt::For {
+            init,
+            cond,
+            step,
+            body,
+            span: self.span_since(start),
+        })
     }
-    /* parse union body into Some type body
-     * return rust stream
+
+    /**
+     * parse_switch:
+     * consecutive case labels without statements share the body
+     * case 1: case 2: x++; break;
      */
-    fn parse_union_inbody_decl(&mut self, lexeme: &Vec<Token>, name: &String) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head = 0;
-        //push the identifier
-        stream.push(lexeme[head + 1].get_token_value());
-        stream.push("(".to_string());
-        let mut struct_memt = CStructMem {
-            identifier: "NONE".to_string(),
-            name: name.clone(),
-            member_type: TokenType::Others,
-        };
-        let mut rust_type = "RUST_TYPE".to_string();
-        //push the type
-        if let Some(rust_typ) = parse_type(lexeme[head].get_token_type(), Modifier::Default) {
-            rust_type = rust_typ.clone();
-            stream.push(rust_typ);
-            struct_memt.member_type = lexeme[head].get_token_type();
-            struct_memt.identifier = lexeme[head + 1].get_token_value();
-        }
-        head += 2;
-        stream.push("),".to_string());
-        //update struct member table (may require for analysis
-
-        self.struct_mem.push(struct_memt.clone());
-        while lexeme[head].get_token_type() != Semicolon {
-            if lexeme[head].get_token_type() == Comma {
-                head += 1;
-            }
-            struct_memt.identifier = lexeme[head].get_token_value();
-            stream.push(lexeme[head].get_token_value());
-            stream.push("(".to_string());
-            stream.push(rust_type.clone());
-            head += 1;
-            stream.push("),".to_string());
-            self.struct_mem.push(struct_memt.clone());
-        }
-        stream
-    }
-
-    // not tested
-    fn parse_class(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head: usize = 0;
-        let mut method_stream: Vec<String> = Vec::new();
-        stream.push("struct".to_string()); //push the keyword struct
-        head += 1;
-        //push the struct id_name
-        let class_name = lexeme[head].get_token_value();
-        stream.push(class_name.clone()); //push the class name
-        let name = lexeme[head].get_token_value();
-        stream.push("{".to_string());
-        head += 2;
-        let mut modifier: String = " ".to_string();
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        let mut tstream: Vec<String> = Vec::new();
-
-        while lexeme[head].get_token_type() != RightCurlyBrace
-            && lexeme[head + 1].get_token_type() != Semicolon
-        {
-            match lexeme[head].get_type() {
-                (TokenKind::Modifiers, _) => {
-                    match lexeme[head].get_token_type() {
-                        KeywordPublic => {
-                            head += 2;
-                            modifier = "pub".to_string();
-                        }
-                        KeywordProtected | keywordPrivate => {
-                            head += 2;
-                            modifier = "".to_string();
-                        }
-                        _ => {}
-                    };
+    fn parse_switch(&mut self) -> PResult<Stmt> {
+        let start = self.head;
+        self.bump();
+        let scrutinee = self.parse_condition()?;
+        let mut cases: Vec<Case> = Vec::new();
+        self.expect(LeftCurlyBrace)?;
+        loop {
+            let comments = self.take_comments();
+            if let Some(case) = cases.last_mut() {
+                case.body.extend(comments.into_iter().map(Stmt::Comment));
+            }
+            if self.at_end() || self.check(RightCurlyBrace) {
+                break;
+            }
+            let label = match self.nth_type(0) {
+                Some(KeywordCase) => {
+                    self.bump();
+                    Some(Some(self.parse_expr()?))
                 }
-                (_, Identifier) => {
-                    if lexeme[head].get_token_value() == class_name {
-                        tstream.push(CONSTRUCTOR.get_doc().to_string());
-                        let mut lookahead = head;
-                        while lexeme[lookahead].get_token_type() != LeftCurlyBrace {
-                            lookahead += 1;
-                        }
-                        lookahead += 1;
-                        lookahead = skip_block(lexeme, lookahead);
-                        while head < lookahead {
-                            tstream.push(lexeme[head].get_token_value());
-                            head += 1;
-                        }
-                        tstream.push("\n **/\n".to_string());
-                        continue;
-                    }
+                Some(KeywordDefault) => {
+                    self.bump();
+                    Some(None)
                 }
-
-                _ => {}
-            }
-
-            if lexeme[head + 2].get_token_type() == LeftBracket {
-                while lexeme[head].get_token_type() != RightCurlyBrace {
-                    temp_lexeme.push(lexeme[head].clone());
-                    head += 1;
+                _ => None,
+            };
+            match label {
+                Some(label) => {
+                    self.expect(Colon)?;
+                    match cases.last_mut() {
+                        Some(case) if case.body.is_empty() => case.labels.push(label),
+                        _ => cases.push(Case {
+                            labels: vec![label],
+                            body: Vec::new(),
+                        }),
+                    }
                 }
-                temp_lexeme.push(lexeme[head].clone());
-                head += 1;
-                method_stream.append(&mut self.parse_method_decl(&temp_lexeme, &modifier));
-                temp_lexeme.clear();
-            } else {
-                while lexeme[head].get_token_type() != RightCurlyBrace
-                    && lexeme[head].get_token_kind() != TokenKind::Modifiers
-                {
-                    while lexeme[head].get_token_type() != Semicolon {
-                        temp_lexeme.push(lexeme[head].clone());
-                        head += 1
+                None => {
+                    if cases.is_empty() {
+                        cases.push(Case {
+                            labels: Vec::new(),
+                            body: Vec::new(),
+                        });
+                    }
+                    if let Some(case) = cases.last_mut() {
+                        self.parse_stmt_into(&mut case.body);
                     }
-                    temp_lexeme.push(lexeme[head].clone());
-                    head += 1;
-                    stream.append(&mut self.parse_class_inbody_decl(
-                        &temp_lexeme,
-                        &name,
-                        &modifier,
-                    ));
-                    temp_lexeme.clear();
                 }
             }
         }
-        stream.push(lexeme[head].get_token_value());
-        stream.push(
-            "\n\n/**Method declarations are wrapped inside the impl block \
-    \n * Which implements the corresponding structure\
-    \n **/\n"
-                .to_string(),
-        );
-        stream.push("impl".to_string());
-        stream.push(name.clone());
-        stream.push("{\n".to_string());
-        if tstream.len() > 0 {
-            stream.append(&mut tstream);
-        }
-        stream.append(&mut method_stream);
-
-        stream.push("}\n".to_string());
-        stream
+        self.expect(RightCurlyBrace)?;
+        Ok(Stmt::Switch {
+            scrutinee,
+            cases,
+            span: self.span_since(start),
+        })
     }
 
-    // not tested
-    fn parse_method_decl(&mut self, lexeme: &Vec<Token>, modifier: &String) -> Vec<String> {
-        let mut temp_lexeme: Vec<Token> = Vec::new();
-        let mut head: usize = 3;
-        let mut lookahead: usize = head;
-        let mut stream: Vec<String> = Vec::new();
-        if modifier.len() > 1 {
-            stream.push(modifier.clone());
-        }
-        stream.push("fn".to_string());
-        stream.push(lexeme[1].get_token_value());
-        stream.push("(".to_string());
-        stream.push("&self".to_string()); //first argument of method must be self, for sefety we consider reference/borrow
-                                          // parse arguments differenly for functions that are not main
-                                          // collect arguments
-        while lexeme[lookahead].get_token_type() != RightBracket {
-            lookahead += 1;
-        }
-        if head < lookahead {
-            stream.push(",".to_string());
+    /* parse_expr:
+     * parse c/c++ expression including the comma operator
+     */
+    fn parse_expr(&mut self) -> PResult<Expr> {
+        let first = self.parse_assign_expr()?;
+        if !self.check(Comma) {
+            return Ok(first);
         }
-        while head < lookahead {
-            let l: Token = lexeme[head].clone();
-            temp_lexeme.push(l);
-            head += 1;
+        let mut list = vec![first];
+        while self.eat(Comma) {
+            list.push(self.parse_assign_expr()?);
         }
+        Ok(Expr::Comma(list))
+    }
 
-        // parse arguments
-        stream.append(&mut self.parse_arguments(&temp_lexeme));
-        temp_lexeme.clear();
+    /* parse_assign_expr:
+     * assignment is right associative, a = b = c
+     */
+    fn parse_assign_expr(&mut self) -> PResult<Expr> {
+        let target = self.parse_conditional()?;
+        if let Some(op) = self.nth_type(0).and_then(AssignOp::from_token) {
+            self.bump();
+            let value = self.parse_assign_expr()?;
+            return Ok(Expr::Assign {
+                op,
+                target: Box::new(target),
+                value: Box::new(value),
+            });
+        }
+        Ok(target)
+    }
 
-        stream.push(")".to_string());
+    /* parse_conditional:
+     * ternary operator is right associative, a ? b : c ? d : e
+     * the middle operand is parsed as if it were in brackets
+     */
+    fn parse_conditional(&mut self) -> PResult<Expr> {
+        let cond = self.parse_binary(0)?;
+        if !self.eat(TernaryOpetator) {
+            return Ok(cond);
+        }
+        let then = self.parse_expr()?;
+        self.expect(Colon)?;
+        let otherwise = self.parse_assign_expr()?;
+        Ok(Expr::Conditional {
+            cond: Box::new(cond),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+        })
+    }
 
-        // parse return type
-        if let Some(rust_type) = parse_type(lexeme[0].get_token_type(), Modifier::Default) {
-            if rust_type != "void".to_string() {
-                stream.push("->".to_string());
-                stream.push(rust_type);
+    /* parse_binary:
+     * precedence climbing over the binary operators,
+     * operators binding weaker than `min_prec` are left to the caller
+     */
+    fn parse_binary(&mut self, min_prec: u8) -> PResult<Expr> {
+        let mut lhs = self.parse_unary()?;
+        while let Some(op) = self.nth_type(0).and_then(BinaryOp::from_token) {
+            if op.precedence() < min_prec {
+                break;
             }
+            self.bump();
+            // all binary operators are left associative
+            let rhs = self.parse_binary(op.precedence() + 1)?;
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
         }
+        Ok(lhs)
+    }
 
-        stream.push("{".to_string());
-        while lexeme[head].get_token_type() != LeftCurlyBrace {
-            head += 1
-        }
-        head += 1;
+    fn parse_unary(&mut self) -> PResult<Expr> {
+        if self.check(SizeOf) {
+            self.bump();
+            return Ok(Expr::SizeOf(self.parse_sizeof_arg()?));
+        }
+        if self.eat(KeywordAlignof) {
+            return Ok(Expr::AlignOf(self.parse_type_in_brackets()?));
+        }
+        if self.is_type_in_brackets() {
+            let ty = self.parse_type_in_brackets()?;
+            let expr = self.parse_unary()?;
+            return Ok(Expr::Cast {
+                ty,
+                expr: Box::new(expr),
+            });
+        }
+        if let Some(op) = self.nth_type(0).and_then(UnaryOp::from_token) {
+            self.bump();
+            let operand = self.parse_unary()?;
+            return Ok(Expr::Unary {
+                op,
+                operand: Box::new(operand),
+            });
+        }
+        self.parse_postfix()
+    }
 
-        // collect function body
-        // len - 1  so that '}' is excluded
-        while head < lexeme.len() - 1 {
-            let l: Token = lexeme[head].clone();
-            temp_lexeme.push(l);
-            head += 1;
+    /// (int), (unsigned char *), (struct A), (A) where A is a known type
+    fn is_type_in_brackets(&self) -> bool {
+        if !self.check(LeftBracket) {
+            return false;
+        }
+        match self.peek_nth(1) {
+            Some(token) if is_type_specifier(token) => true,
+            Some(token) => {
+                matches!(
+                    token.get_token_type(),
+                    KeywordStruct | KeywordUnion | KeywordEnum
+                ) || self.symbols.is_type(&token.get_token_value())
+            }
+            None => false,
         }
-        // parse function body
-        stream.append(&mut self.parse_program(&temp_lexeme));
-        stream.push("}".to_string());
-        stream
     }
 
-    // not tested
-    fn parse_class_inbody_decl(
-        &mut self,
-        lexeme: &Vec<Token>,
-        name: &String,
-        modifier: &String,
-    ) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-        let mut head = 0;
-        //push the identifier
-        if modifier.len() > 1 {
-            stream.push(modifier.clone());
-        }
-        stream.push(lexeme[head + 1].get_token_value());
-        stream.push(":".to_string());
-        let mut struct_memt = CStructMem {
-            identifier: "NONE".to_string(),
-            name: name.clone(),
-            member_type: TokenType::Others,
-        };
+    fn parse_type_in_brackets(&mut self) -> PResult<Type> {
+        self.expect(LeftBracket)?;
+        let mut ty = self.parse_type_specifier();
+        self.parse_pointers(&mut ty);
+        self.expect(RightBracket)?;
+        Ok(ty)
+    }
 
-        let mut rust_type: String = " ".to_string();
-        if let Some(rust_typ) = parse_type(lexeme[0].get_token_type(), Modifier::Default) {
-            rust_type = rust_typ.clone();
-            stream.push(rust_typ);
-            struct_memt.member_type = lexeme[0].get_token_type();
-            struct_memt.identifier = lexeme[1].get_token_value();
+    /// sizeof(int), sizeof(struct A), sizeof a
+    fn parse_sizeof_arg(&mut self) -> PResult<SizeOfArg> {
+        if self.is_type_in_brackets() {
+            return Ok(SizeOfArg::Type(self.parse_type_in_brackets()?));
         }
+        Ok(SizeOfArg::Expr(Box::new(self.parse_unary()?)))
+    }
 
-        stream.push(",".to_string());
-        self.struct_mem.push(struct_memt.clone());
-        head += 2;
-        while lexeme[head].get_token_type() != Semicolon {
-            if lexeme[head].get_token_type() == Comma {
-                head += 1;
-            }
-            stream.push(lexeme[head].get_token_value());
-            stream.push(":".to_string());
-            stream.push(rust_type.clone());
-
-            struct_memt.identifier = lexeme[head].get_token_value();
-            self.struct_mem.push(struct_memt.clone());
-            head += 1;
+    fn parse_postfix(&mut self) -> PResult<Expr> {
+        let mut expr = self.parse_primary()?;
+        while let Some(token) = self.peek() {
+            expr = match token.get_token_type() {
+                LeftBracket => {
+                    self.bump();
+                    let args = self.parse_args()?;
+                    Expr::Call {
+                        callee: Box::new(expr),
+                        args,
+                    }
+                }
+                LeftSquareBracket => {
+                    self.bump();
+                    let index = self.parse_expr()?;
+                    self.expect(RightSquareBracket)?;
+                    Expr::Index {
+                        base: Box::new(expr),
+                        index: Box::new(index),
+                    }
+                }
+                Arrow | Dot => {
+                    self.bump();
+                    Expr::Member {
+                        base: Box::new(expr),
+                        field: self.parse_ident(),
+                        arrow: token.get_token_type() == Arrow,
+                    }
+                }
+                // rust has no pointers to members
+                DotStar | ArrowStar => {
+                    return Err(ParseError::new(
+                        format!(
+                            "pointer to member access `{}` is not translated",
+                            token.get_token_value()
+                        ),
+                        token.get_span(),
+                    ))
+                }
+                Increment | Decrement => {
+                    self.bump();
+                    let op = if token.get_token_type() == Increment {
+                        PostfixOp::Increment
+                    } else {
+                        PostfixOp::Decrement
+                    };
+                    Expr::Postfix {
+                        op,
+                        operand: Box::new(expr),
+                    }
+                }
+                _ => break,
+            };
         }
-        stream
+        Ok(expr)
     }
 
-    // not tested
-    fn parse_class_decl(&mut self, lexeme: &Vec<Token>) -> Vec<String> {
-        let mut stream: Vec<String> = Vec::new();
-
-        stream.push(STRUCT_INIT.get_doc().to_string());
-        stream.push("let".to_string());
-        let mut head = 0;
-        //struct FilePointer fp;
-        let struct_name = lexeme[head].get_token_value();
-        head += 1;
-        stream.push(lexeme[head].get_token_value()); //push the identifer => let a
-        stream.push("=".to_string());
-        stream.push(struct_name.clone());
-        stream.push("{".to_string());
+    /// call arguments, the opening bracket is already consumed
+    fn parse_args(&mut self) -> PResult<Vec<Expr>> {
+        let mut args = Vec::new();
+        while !self.at_end() && !self.eat(RightBracket) {
+            args.push(self.parse_assign_expr()?);
+            if !self.eat(Comma) {
+                self.expect(RightBracket)?;
+                break;
+            }
+        }
+        Ok(args)
+    }
 
-        for row in &self.struct_mem {
-            if row.name == struct_name {
-                stream.push(row.identifier.clone());
-                stream.push(":".to_string());
-                stream.push(get_default_value_for(row.member_type));
-                stream.push(",".to_string());
+    fn parse_primary(&mut self) -> PResult<Expr> {
+        let token = match self.peek() {
+            Some(token) if !is_expr_end(token.get_token_type()) => token,
+            _ => return Err(self.error_expected("expression")),
+        };
+        self.bump();
+        let value = token.get_token_value();
+        let literal = |kind| Expr::Literal {
+            kind,
+            value: value.clone(),
+        };
+        match token.get_type() {
+            (_, Identifier) | (_, Main) | (_, ScopeResolution) => {
+                let mut path = Vec::new();
+                if token.get_token_type() == ScopeResolution {
+                    path.push(String::new());
+                }
+                path.push(if token.get_token_type() == ScopeResolution {
+                    self.parse_ident()
+                } else {
+                    value.clone()
+                });
+                while self.check(ScopeResolution) && self.peek_nth(1).is_some_and(is_identifier) {
+                    self.bump();
+                    path.push(self.parse_ident());
+                }
+                if path.len() == 1 {
+                    Ok(Expr::Ident(value))
+                } else {
+                    Ok(Expr::Path(path))
+                }
+            }
+            (_, NumberInteger) => Ok(literal(LiteralKind::Integer(
+                token.get_number().unwrap_or_default(),
+            ))),
+            (_, NumberFloat) => Ok(literal(LiteralKind::Float(
+                token.get_number().unwrap_or_default(),
+            ))),
+            (_, CharValue) => Ok(literal(LiteralKind::Char(
+                token.get_prefix().unwrap_or_default(),
+            ))),
+            (TokenKind::Values, StringValue) => {
+                // "a" "b" is a single literal
+                let mut prefix = token.get_prefix().unwrap_or_default();
+                let mut value = value.clone();
+                while let Some(next) = self.peek() {
+                    if next.get_type() != (TokenKind::Values, StringValue) {
+                        break;
+                    }
+                    prefix = prefix.concat(next.get_prefix().unwrap_or_default());
+                    value.push(' ');
+                    value.push_str(&next.get_token_value());
+                    self.bump();
+                }
+                Ok(Expr::Literal {
+                    kind: LiteralKind::Str(prefix),
+                    value,
+                })
             }
+            (_, True) | (_, False) => Ok(literal(LiteralKind::Bool)),
+            (_, Null) => Ok(literal(LiteralKind::Null)),
+            (_, KeywordThis) => Ok(Expr::This),
+            // static_cast<int>(x)
+            (_, KeywordStaticCast)
+            | (_, KeywordConstCast)
+            | (_, KeywordDynamicCast)
+            | (_, KeywordReinterpretCast) => {
+                self.expect(LessThan)?;
+                let mut ty = self.parse_type_specifier();
+                self.parse_pointers(&mut ty);
+                self.expect(GreaterThan)?;
+                let expr = self.parse_condition()?;
+                Ok(Expr::Cast {
+                    ty,
+                    expr: Box::new(expr),
+                })
+            }
+            // the statement holding them is kept as is
+            (_, KeywordThrow)
+            | (_, KeywordDelete)
+            | (_, KeywordNew)
+            | (_, KeywordTypeid)
+            | (_, KeywordDecltype)
+            | (_, KeywordGeneric)
+            | (_, KeywordNoexcept) => {
+                self.untranslated = Some(if token.get_token_type() == KeywordThrow {
+                    EXCEPTION
+                } else {
+                    KEYWORD
+                });
+                Err(ParseError::new(
+                    format!("`{}` is not translated", value),
+                    token.get_span(),
+                ))
+            }
+            // #x in a macro body
+            (_, HeaderInclude) if self.in_macro && self.peek().is_some_and(is_identifier) => {
+                Ok(Expr::Stringize(self.parse_ident()))
+            }
+            (_, LeftBracket) => {
+                let inner = self.parse_expr()?;
+                self.expect(RightBracket)?;
+                Ok(inner)
+            }
+            // functional cast int(x)
+            (TokenKind::DataTypes, typ) if self.check(LeftBracket) => {
+                self.bump();
+                let expr = self.parse_expr()?;
+                self.expect(RightBracket)?;
+                Ok(Expr::Cast {
+                    ty: Type::primitive(typ),
+                    expr: Box::new(expr),
+                })
+            }
+            _ => Ok(Expr::Verbatim(value)),
         }
-        stream.push("};".to_string());
+    }
+}
+
+/// tokens which can not begin an expression
+fn is_expr_end(typ: TokenType) -> bool {
+    matches!(
+        typ,
+        Semicolon
+            | DirectiveEnd
+            | Comma
+            | Colon
+            | RightBracket
+            | RightSquareBracket
+            | LeftCurlyBrace
+            | RightCurlyBrace
+    )
+}
 
-        stream
+/// cout << "hello" << x; cin >> x;
+fn is_stream_op(expr: &Expr) -> bool {
+    match expr {
+        Expr::Binary { op, lhs, rhs } if *op == BinaryOp::Shl || *op == BinaryOp::Shr => {
+            rhs.is_text_literal() || is_stream_op(lhs) || is_stream_name(lhs)
+        }
+        _ => false,
     }
 }
+
+fn is_stream_name(expr: &Expr) -> bool {
+    let name = match expr {
+        Expr::Ident(name) => name,
+        Expr::Path(path) => path.last().unwrap(),
+        _ => return false,
+    };
+    matches!(name.as_str(), "cout" | "cin" | "cerr" | "clog")
+}
@@ -1,5 +1,480 @@
-#[cfg(test)]
+use crate::library::ast::*;
+use crate::library::doc::DocType;
+use crate::library::lexeme::definition::TokenType;
+use crate::library::lexer::tokenizer::Tokenizer;
+use crate::library::parser::parser::init_parser;
+use crate::library::parser::rust_type::Modifier;
+
+fn parse(text: &str) -> TranslationUnit {
+    init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap()
+}
+
+fn function_body(unit: &TranslationUnit) -> &[Stmt] {
+    match unit.items.first() {
+        Some(Item::Function(Function {
+            body: Some(body), ..
+        })) => &body.stmts,
+        item => panic!("expected function, found {:?}", item),
+    }
+}
+
+#[test]
+fn test_that_function_is_parsed_into_ast() {
+    let unit = parse("int add(int a, const int &b) { return a + b; }");
+    let function = match &unit.items[0] {
+        Item::Function(function) => function,
+        item => panic!("expected function, found {:?}", item),
+    };
+    assert_eq!(function.name, FunctionName::Ident("add".to_string()));
+    assert_eq!(function.ret, Type::primitive(TokenType::Integer));
+    assert_eq!(function.params.len(), 2);
+    assert_eq!(function.params[1].name, Some("b".to_string()));
+    assert!(function.params[1].ty.reference);
+    assert!(function.params[1].ty.is_const);
+    let (value, span) = match function_body(&unit) {
+        [Stmt::Return(Some(value), span)] => (value, span),
+        stmts => panic!("expected return, found {:?}", stmts),
+    };
+    assert_eq!(
+        value,
+        &Expr::Binary {
+            op: BinaryOp::Add,
+            lhs: Box::new(Expr::ident("a")),
+            rhs: Box::new(Expr::ident("b")),
+        }
+    );
+    // the statement runs from `return` to its `;`
+    assert_eq!((span.start.offset, span.end.offset), (31, 44));
+}
+
+#[test]
+fn test_that_declarators_share_base_type() {
+    let unit = parse("int main() { unsigned int *p = &x, a[10], c; }");
+    let decl = match &function_body(&unit)[0] {
+        Stmt::Decl(decl) => decl,
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    };
+    let names: Vec<&str> = decl.declarators.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, vec!["p", "a", "c"]);
+    assert_eq!(decl.declarators[0].ty.pointer, 1);
+    assert_eq!(decl.declarators[1].ty.array.len(), 1);
+    assert_eq!(decl.declarators[2].ty.pointer, 0);
+    assert!(decl
+        .declarators
+        .iter()
+        .all(|d| d.ty.base == BaseType::Primitive(TokenType::Integer)));
+}
+
+#[test]
+fn test_that_user_defined_type_starts_declaration() {
+    let unit = parse("typedef int number; int main() { number n; A a = A(); a.get(); }");
+    assert!(matches!(unit.items[0], Item::Typedef(_)));
+    let body = match &unit.items[1] {
+        Item::Function(Function {
+            body: Some(body), ..
+        }) => &body.stmts,
+        item => panic!("expected function, found {:?}", item),
+    };
+    assert!(matches!(body[0], Stmt::Decl(_)));
+    assert!(matches!(body[1], Stmt::Decl(_)));
+    assert!(matches!(body[2], Stmt::Expr(Expr::Call { .. }, _)));
+}
+
+#[test]
+fn test_that_class_members_are_collected() {
+    let unit = parse("class A { int a; public: int get() { return a; } A() { a = 1; } };");
+    let record = match &unit.items[0] {
+        Item::Record(record) => record,
+        item => panic!("expected record, found {:?}", item),
+    };
+    assert_eq!(record.kind, RecordKind::Class);
+    assert_eq!(record.fields.len(), 1);
+    assert_eq!(record.fields[0].access, Access::Private);
+    assert_eq!(record.methods.len(), 1);
+    assert_eq!(record.methods[0].access, Access::Public);
+    assert_eq!(record.constructors.len(), 1);
+}
+
+#[test]
+fn test_that_switch_labels_without_body_are_grouped() {
+    let unit = parse("int main() { switch (x) { case 1: case 2: y = 1; break; default: y = 0; } }");
+    let cases = match &function_body(&unit)[0] {
+        Stmt::Switch { cases, .. } => cases,
+        stmt => panic!("expected switch, found {:?}", stmt),
+    };
+    assert_eq!(cases.len(), 2);
+    assert_eq!(cases[0].labels.len(), 2);
+    assert_eq!(cases[0].body.len(), 2);
+    assert_eq!(cases[1].labels, vec![None]);
+}
+
+#[test]
+fn test_that_stream_output_is_unsupported() {
+    let unit = parse("int main() { cout << \"hello\" << x; }");
+    assert!(matches!(function_body(&unit)[0], Stmt::Unsupported { .. }));
+}
+
+#[test]
+fn test_that_binary_operators_follow_precedence() {
+    let unit = parse("int main() { x = a + b * c - d; }");
+    let add = Expr::Binary {
+        op: BinaryOp::Add,
+        lhs: Box::new(Expr::ident("a")),
+        rhs: Box::new(Expr::Binary {
+            op: BinaryOp::Mul,
+            lhs: Box::new(Expr::ident("b")),
+            rhs: Box::new(Expr::ident("c")),
+        }),
+    };
+    let expr = match function_body(&unit) {
+        [Stmt::Expr(expr, _)] => expr,
+        stmts => panic!("expected expression, found {:?}", stmts),
+    };
+    assert_eq!(
+        expr,
+        &Expr::Assign {
+            op: AssignOp::Assign,
+            target: Box::new(Expr::ident("x")),
+            value: Box::new(Expr::Binary {
+                op: BinaryOp::Sub,
+                lhs: Box::new(add),
+                rhs: Box::new(Expr::ident("d")),
+            }),
+        }
+    );
+}
+
+#[test]
+fn test_that_ternary_and_cast_are_parsed() {
+    let unit = parse("int main() { x = a ? (float) b : c ? d : e; }");
+    let value = match &function_body(&unit)[0] {
+        Stmt::Expr(Expr::Assign { value, .. }, _) => value,
+        stmt => panic!("expected assignment, found {:?}", stmt),
+    };
+    match &**value {
+        Expr::Conditional {
+            then, otherwise, ..
+        } => {
+            assert!(matches!(**then, Expr::Cast { .. }));
+            assert!(matches!(**otherwise, Expr::Conditional { .. }));
+        }
+        expr => panic!("expected conditional, found {:?}", expr),
+    }
+}
+
+#[test]
+fn test_that_spans_are_carried_into_ast() {
+    let unit = parse("int x = 1;\nint main() {\n    int *p = &x, q;\n}");
+    let global = match &unit.items[0] {
+        Item::Global(decl) => decl,
+        item => panic!("expected global, found {:?}", item),
+    };
+    assert_eq!((global.span.start.column, global.span.end.column), (1, 11));
+    let function = match &unit.items[1] {
+        Item::Function(function) => function,
+        item => panic!("expected function, found {:?}", item),
+    };
+    assert_eq!((function.span.start.line, function.span.end.line), (2, 4));
+    let decl = match &function.body.as_ref().unwrap().stmts[0] {
+        Stmt::Decl(decl) => decl,
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    };
+    let p = decl.declarators[0].span;
+    assert_eq!((p.start.line, p.start.column, p.end.column), (3, 9, 16));
+    let q = decl.declarators[1].span;
+    assert_eq!((q.start.column, q.end.column), (18, 19));
+}
+
+#[test]
+fn test_that_bad_statement_is_skipped_till_the_next_one() {
+    let failure = init_parser(
+        &Tokenizer::new("int f() {\n    x = (1 + ;\n    return 2;\n}")
+            .tokenize()
+            .unwrap(),
+    )
+    .unwrap_err();
+    assert_eq!(failure.errors.len(), 1);
+    assert_eq!(failure.errors[0].message, "expected expression, found `;`");
+    let at = failure.errors[0].span.start;
+    assert_eq!((at.line, at.column), (2, 14));
+    match function_body(&failure.unit) {
+        [Stmt::Error(error), Stmt::Return(Some(_), _)] => assert_eq!(error.text, "x = ( 1 + ;"),
+        stmts => panic!("expected error and return, found {:?}", stmts),
+    }
+}
+
+#[test]
+fn test_that_bad_declaration_does_not_abort_the_file() {
+    let text = "int f( { }\nint g() { return 1; }\nint y = ;";
+    let failure = init_parser(&Tokenizer::new(text).tokenize().unwrap()).unwrap_err();
+    assert_eq!(failure.errors.len(), 2);
+    assert!(matches!(failure.unit.items[0], Item::Error(_)));
+    assert!(matches!(failure.unit.items[1], Item::Function(_)));
+    assert!(matches!(failure.unit.items[2], Item::Error(_)));
+}
+
+#[test]
+fn test_that_missing_brace_is_reported_at_end_of_file() {
+    let failure = init_parser(
+        &Tokenizer::new("int f() {\n    return 1;")
+            .tokenize()
+            .unwrap(),
+    )
+    .unwrap_err();
+    assert_eq!(failure.errors[0].message, "expected `}`, found end of file");
+    assert!(matches!(failure.unit.items[0], Item::Function(_)));
+}
+
+#[test]
+fn test_that_specifiers_without_rust_equivalent_are_dropped() {
+    let unit = parse(
+        "extern \"C\" {\nstatic inline constexpr int n = 1;\n}\n\
+         struct A { virtual int f() const noexcept override; explicit A(int); friend class B; };",
+    );
+    let decl = match &unit.items[0] {
+        Item::Global(decl) => decl,
+        item => panic!("expected global, found {:?}", item),
+    };
+    assert!(decl.declarators[0].ty.is_const);
+    assert!(decl.declarators[0].ty.is_static);
+    let record = match &unit.items[1] {
+        Item::Record(record) => record,
+        item => panic!("expected record, found {:?}", item),
+    };
+    assert_eq!(record.methods.len(), 1);
+    assert_eq!(record.constructors[0].text, "explicit A ( int ) ;");
+    assert!(record.fields.is_empty());
+}
+
+#[test]
+fn test_that_unsupported_keywords_are_refused() {
+    let unit = parse(
+        "namespace n { int x; }\ntemplate <class T> T id(T t) { return t; }\n\
+         int main() { try { f(); } catch (...) { } goto end; end: delete p; }",
+    );
+    let docs: Vec<&str> = unit
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Stmt(Stmt::Unsupported { doc, .. }) => Some(doc.code()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(docs, vec!["CR0015", "CR0016"]);
+    let body = match &unit.items[2] {
+        Item::Function(Function {
+            body: Some(body), ..
+        }) => &body.stmts,
+        item => panic!("expected function, found {:?}", item),
+    };
+    let docs: Vec<&str> = body
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Unsupported { doc, .. } => Some(doc.code()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(docs, vec!["CR0017", "CR0018", "CR0018", "CR0019"]);
+
+    // in expressions they are reported as well, rather than as parse errors
+    let unit = parse(
+        "int *g = new int(3);\n\
+         class Shape { public: virtual int area() const = 0; int sides; };\n\
+         int main() { decltype(x) y = 2; int s = sizeof(typeid(x)); bool b = noexcept(x); \
+         int c = x ? 1 : throw 2; return 0; }",
+    );
+    assert!(matches!(
+        unit.items[0],
+        Item::Unsupported {
+            doc: DocType::KEYWORD,
+            ..
+        }
+    ));
+    match &unit.items[1] {
+        Item::Record(record) => {
+            assert!(record.methods.is_empty());
+            assert_eq!(
+                record.pure_methods[0].text,
+                "virtual int area ( ) const = 0 ;"
+            );
+        }
+        item => panic!("expected record, found {:?}", item),
+    }
+    let body = match &unit.items[2] {
+        Item::Function(Function {
+            body: Some(body), ..
+        }) => &body.stmts,
+        item => panic!("expected function, found {:?}", item),
+    };
+    let docs: Vec<&str> = body
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Unsupported { doc, .. } => Some(doc.code()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(docs, vec!["CR0019", "CR0019", "CR0019", "CR0017"]);
+}
+
+#[test]
+fn test_that_comments_are_attached_to_their_construct() {
+    let unit = parse(
+        "/** a point */\nstruct P {\n  /** abscissa */\n  int x; // horizontal\n  // end\n};\n\
+         int f() {\n  int s = 1 /* one */ + 2; // sum\n  return s;\n}",
+    );
+    let record = match &unit.items[0] {
+        Item::Record(record) => record,
+        item => panic!("expected record, found {:?}", item),
+    };
+    let texts = |comments: &[Comment]| -> Vec<(String, bool)> {
+        comments
+            .iter()
+            .map(|comment| (comment.text.clone(), comment.trailing))
+            .collect()
+    };
+    assert_eq!(
+        texts(&record.comments),
+        vec![
+            ("/** a point */".to_string(), false),
+            ("// end".to_string(), true)
+        ]
+    );
+    assert_eq!(
+        texts(&record.fields[0].comments),
+        vec![
+            ("/** abscissa */".to_string(), false),
+            ("// horizontal".to_string(), true)
+        ]
+    );
+    let body = match &unit.items[1] {
+        Item::Function(Function {
+            body: Some(body), ..
+        }) => &body.stmts,
+        item => panic!("expected function, found {:?}", item),
+    };
+    let comments: Vec<Comment> = body
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Comment(comment) => Some(comment.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        texts(&comments),
+        vec![
+            ("/* one */".to_string(), true),
+            ("// sum".to_string(), true)
+        ]
+    );
+    assert!(matches!(body[0], Stmt::Decl(_)));
+
+    // comments of a parameter list go to the parameter they are written by
+    let unit = parse("int g(int a, // first\n      /* the b */ int b /* second */) { return a; }");
+    let params = match &unit.items[0] {
+        Item::Function(function) => &function.params,
+        item => panic!("expected function, found {:?}", item),
+    };
+    assert_eq!(
+        texts(&params[0].comments),
+        vec![("// first".to_string(), true)]
+    );
+    assert_eq!(
+        texts(&params[1].comments),
+        vec![
+            ("/* the b */".to_string(), false),
+            ("/* second */".to_string(), true)
+        ]
+    );
+}
+
+#[test]
+fn test_that_local_variables_shadow_types_in_their_scope() {
+    let unit = parse(
+        "typedef int A; int f() { int A = 2, b = 3; A * b; return (A) - b; } int g() { A * b; }",
+    );
+    let bodies: Vec<&[Stmt]> = unit
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Function(Function {
+                body: Some(body), ..
+            }) => Some(&body.stmts[..]),
+            _ => None,
+        })
+        .collect();
+    assert!(matches!(
+        bodies[0][1],
+        Stmt::Expr(
+            Expr::Binary {
+                op: BinaryOp::Mul,
+                ..
+            },
+            _
+        )
+    ));
+    assert!(matches!(
+        bodies[0][2],
+        Stmt::Return(
+            Some(Expr::Binary {
+                op: BinaryOp::Sub,
+                ..
+            }),
+            _
+        )
+    ));
+    // the variable is gone with the body of f
+    assert!(matches!(bodies[1][0], Stmt::Decl(_)));
+}
+
 #[test]
-fn test_that_works() {
-    assert_eq!(1, 1);
+fn test_that_enums_are_parsed_with_their_values() {
+    let unit = parse(
+        "enum class Mode : unsigned char { Off, On = 3 }; typedef enum { LOW, HIGH } Level; \
+         enum Color { RED } c; int f(enum Color a) { Level l = HIGH; }",
+    );
+    let mode = match &unit.items[0] {
+        Item::Enum(found) => found,
+        item => panic!("expected enum, found {:?}", item),
+    };
+    assert!(mode.scoped);
+    assert_eq!(
+        mode.underlying.as_ref().unwrap().modifier,
+        Modifier::Unsigned
+    );
+    let names: Vec<&str> = mode.enumerators.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(names, vec!["Off", "On"]);
+    assert!(mode.enumerators[0].value.is_none());
+    assert!(mode.enumerators[1].value.is_some());
+    assert!(matches!(&unit.items[1], Item::Enum(found) if found.name == "Level" && !found.scoped));
+    assert!(matches!(unit.items[3], Item::Global(_)));
+    let function = match &unit.items[4] {
+        Item::Function(function) => function,
+        item => panic!("expected function, found {:?}", item),
+    };
+    assert_eq!(
+        function.params[0].ty.base,
+        BaseType::Named("Color".to_string())
+    );
+    assert!(matches!(
+        function.body.as_ref().unwrap().stmts[0],
+        Stmt::Decl(_)
+    ));
+
+    let failure = init_parser(&Tokenizer::new("enum E : { A };").tokenize().unwrap()).unwrap_err();
+    assert_eq!(
+        failure.errors[0].message,
+        "expected the underlying type of the enum, found `{`"
+    );
+
+    // the only way c has to convert an integer to an enum
+    let unit = parse("enum E { A }; int f(int v) { return (enum E)(v & 1); }");
+    match &unit.items[1] {
+        Item::Function(Function {
+            body: Some(body), ..
+        }) => assert!(matches!(
+            &body.stmts[0],
+            Stmt::Return(Some(Expr::Cast { ty, .. }), _) if ty.base == BaseType::Named("E".to_string())
+        )),
+        item => panic!("expected function, found {:?}", item),
+    }
 }
@@ -1,6 +1,6 @@
 use crate::library::lexeme::definition::TokenType;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Modifier {
     Unsigned,
     Signed,
@@ -29,6 +29,8 @@ pub fn parse_type(c_type: TokenType, modifier: Modifier) -> Option<String> {
         (_, TokenType::Float) => Some("f32".to_string()),
         (_, TokenType::Double) => Some("f64".to_string()),
         (_, TokenType::Character) => Some("char".to_string()),
+        (_, TokenType::WideChar) | (_, TokenType::Char32) => Some("char".to_string()),
+        (_, TokenType::Char16) => Some("u16".to_string()),
         (_, TokenType::Boolean) => Some("bool".to_string()),
         (_, TokenType::Void) => Some("void".to_string()),
         (_, TokenType::Auto) => Some("_".to_string()),
@@ -0,0 +1,38 @@
+use std::fmt;
+
+use crate::library::diagnostics::Diagnostic;
+use crate::library::doc::DocType;
+use crate::library::lexeme::span::Span;
+use crate::library::lexeme::token::Token;
+
+/// A directive or macro invocation the preprocessor could not carry out,
+/// `span` covers the directive or the invocation
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreprocessError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl PreprocessError {
+    pub fn new(message: String, span: Span) -> PreprocessError {
+        PreprocessError { message, span }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(DocType::PREPROCESS_ERROR, self.span).with_message(self.message.clone())
+    }
+}
+
+impl fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.span.start, self.message)
+    }
+}
+
+/// Errors found while preprocessing, along with the tokens expanded
+/// around them. A failed directive is left out of the tokens
+#[derive(Debug)]
+pub struct PreprocessErrors {
+    pub errors: Vec<PreprocessError>,
+    pub tokens: Vec<Token>,
+}
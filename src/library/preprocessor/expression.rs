@@ -0,0 +1,305 @@
+use crate::library::ast::BinaryOp;
+use crate::library::lexeme::definition::TokenType::*;
+use crate::library::lexeme::definition::{TokenKind, TokenType};
+use crate::library::lexeme::literal::{decode, Unit};
+use crate::library::lexeme::number::{Number, Radix, Suffix};
+use crate::library::lexeme::token::Token;
+
+/**
+ * evaluate:
+ * value of the controlling expression of #if and #elif, macros are
+ * already expanded and `defined` replaced, identifiers left are 0.
+ * Values are intmax_t, or uintmax_t from an unsigned operand on, read
+ * back as i64. The error describes why the expression could not be evaluated
+ */
+pub fn evaluate(tokens: &[Token]) -> Result<i64, String> {
+    let tokens: Vec<&Token> = tokens
+        .iter()
+        .filter(|t| t.get_token_kind() != TokenKind::Comments)
+        .collect();
+    if tokens.is_empty() {
+        return Err("#if with no expression".to_string());
+    }
+    let mut evaluator = Evaluator { tokens, head: 0 };
+    let value = evaluator.conditional()?;
+    match evaluator.tokens.get(evaluator.head) {
+        Some(token) => Err(format!(
+            "missing binary operator before `{}`",
+            token.get_token_value()
+        )),
+        None => Ok(value.bits),
+    }
+}
+
+/// value of a preprocessor expression, the bits of an intmax_t or uintmax_t
+#[derive(Debug, Clone, Copy)]
+struct Value {
+    bits: i64,
+    unsigned: bool,
+}
+
+impl Value {
+    fn signed(bits: i64) -> Value {
+        Value {
+            bits,
+            unsigned: false,
+        }
+    }
+
+    fn is_true(self) -> bool {
+        self.bits != 0
+    }
+}
+
+struct Evaluator<'a> {
+    tokens: Vec<&'a Token>,
+    head: usize,
+}
+
+impl<'a> Evaluator<'a> {
+    fn peek(&self) -> Option<TokenType> {
+        self.tokens.get(self.head).map(|t| t.get_token_type())
+    }
+
+    fn eat(&mut self, typ: TokenType) -> bool {
+        if self.peek() == Some(typ) {
+            self.head += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, typ: TokenType, text: &str) -> Result<(), String> {
+        if self.eat(typ) {
+            Ok(())
+        } else {
+            Err(format!("expected `{}` in preprocessor expression", text))
+        }
+    }
+
+    fn conditional(&mut self) -> Result<Value, String> {
+        let cond = self.binary(0)?;
+        if !self.eat(TernaryOpetator) {
+            return Ok(cond);
+        }
+        let then = self.conditional()?;
+        self.expect(Colon, ":")?;
+        let otherwise = self.conditional()?;
+        // both branches have the type of the unsigned one
+        let value = if cond.is_true() { then } else { otherwise };
+        Ok(Value {
+            unsigned: then.unsigned || otherwise.unsigned,
+            ..value
+        })
+    }
+
+    /// precedence climbing, as in the parser
+    fn binary(&mut self, min_prec: u8) -> Result<Value, String> {
+        let mut lhs = self.unary()?;
+        while let Some(op) = self.peek().and_then(BinaryOp::from_token) {
+            if op.precedence() < min_prec {
+                break;
+            }
+            self.head += 1;
+            let rhs = self.binary(op.precedence() + 1)?;
+            lhs = apply_value(op, lhs, rhs)?;
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Value, String> {
+        let token = match self.tokens.get(self.head) {
+            Some(token) => *token,
+            None => return Err("expected value in preprocessor expression".to_string()),
+        };
+        self.head += 1;
+        match token.get_token_type() {
+            Plus => self.unary(),
+            Minus => {
+                let value = self.unary()?;
+                Ok(Value {
+                    bits: value.bits.wrapping_neg(),
+                    ..value
+                })
+            }
+            LogicalNot => Ok(Value::signed(!self.unary()?.is_true() as i64)),
+            BitwiseNegate => {
+                let value = self.unary()?;
+                Ok(Value {
+                    bits: !value.bits,
+                    ..value
+                })
+            }
+            LeftBracket => {
+                let value = self.conditional()?;
+                self.expect(RightBracket, ")")?;
+                Ok(value)
+            }
+            NumberInteger => {
+                let text = token.get_token_value();
+                let (_, number) = Number::parse(&text)?;
+                let bits = integer_value(&text)?;
+                // a constant beyond intmax_t is a uintmax_t
+                let unsigned = bits < 0
+                    || matches!(
+                        number.suffix,
+                        Suffix::Unsigned | Suffix::UnsignedLong | Suffix::UnsignedLongLong
+                    );
+                Ok(Value { bits, unsigned })
+            }
+            NumberFloat => Err("floating constant in preprocessor expression".to_string()),
+            CharValue => {
+                let literal = decode(&token.get_token_value())?;
+                Ok(Value::signed(match literal.units.first() {
+                    Some(Unit::Char(c)) => *c as i64,
+                    Some(Unit::Escape(value)) => *value as i64,
+                    None => 0,
+                }))
+            }
+            True => Ok(Value::signed(1)),
+            // identifiers which are not macros
+            _ if is_word(token) => Ok(Value::signed(0)),
+            _ => Err(format!(
+                "token `{}` is not valid in preprocessor expressions",
+                token.get_token_value()
+            )),
+        }
+    }
+}
+
+/**
+ * apply_value:
+ * `op` on the values as on uintmax_t when one of them is unsigned,
+ * -1 < 0u is false. Comparisons are signed ints, a shift has the type
+ * of its left operand
+ */
+fn apply_value(op: BinaryOp, lhs: Value, rhs: Value) -> Result<Value, String> {
+    let unsigned = match op {
+        BinaryOp::Shl | BinaryOp::Shr => lhs.unsigned,
+        _ => lhs.unsigned || rhs.unsigned,
+    };
+    let (l, r) = (lhs.bits as u64, rhs.bits as u64);
+    let bits = match op {
+        BinaryOp::Div | BinaryOp::Rem if r == 0 => {
+            return Err("division by zero in #if".to_string())
+        }
+        BinaryOp::Div if unsigned => (l / r) as i64,
+        BinaryOp::Rem if unsigned => (l % r) as i64,
+        BinaryOp::Shr if unsigned => l.wrapping_shr(r as u32) as i64,
+        BinaryOp::Lt if unsigned => (l < r) as i64,
+        BinaryOp::Le if unsigned => (l <= r) as i64,
+        BinaryOp::Gt if unsigned => (l > r) as i64,
+        BinaryOp::Ge if unsigned => (l >= r) as i64,
+        op => apply(op, lhs.bits, rhs.bits)?,
+    };
+    let unsigned = unsigned && !op.is_comparison() && !matches!(op, BinaryOp::And | BinaryOp::Or);
+    Ok(Value { bits, unsigned })
+}
+
+pub fn apply(op: BinaryOp, lhs: i64, rhs: i64) -> Result<i64, String> {
+    let value = match op {
+        BinaryOp::Add => lhs.wrapping_add(rhs),
+        BinaryOp::Sub => lhs.wrapping_sub(rhs),
+        BinaryOp::Mul => lhs.wrapping_mul(rhs),
+        BinaryOp::Div | BinaryOp::Rem if rhs == 0 => {
+            return Err("division by zero in #if".to_string())
+        }
+        BinaryOp::Div => lhs.wrapping_div(rhs),
+        BinaryOp::Rem => lhs.wrapping_rem(rhs),
+        BinaryOp::BitAnd => lhs & rhs,
+        BinaryOp::BitOr => lhs | rhs,
+        BinaryOp::BitXor => lhs ^ rhs,
+        BinaryOp::Shl => lhs.wrapping_shl(rhs as u32),
+        BinaryOp::Shr => lhs.wrapping_shr(rhs as u32),
+        BinaryOp::And => (lhs != 0 && rhs != 0) as i64,
+        BinaryOp::Or => (lhs != 0 || rhs != 0) as i64,
+        BinaryOp::Eq => (lhs == rhs) as i64,
+        BinaryOp::Ne => (lhs != rhs) as i64,
+        BinaryOp::Lt => (lhs < rhs) as i64,
+        BinaryOp::Le => (lhs <= rhs) as i64,
+        BinaryOp::Gt => (lhs > rhs) as i64,
+        BinaryOp::Ge => (lhs >= rhs) as i64,
+    };
+    Ok(value)
+}
+
+/// value of an integer literal, 0x1F, 017, 10UL
+pub fn integer_value(text: &str) -> Result<i64, String> {
+    let (_, number) = Number::parse(text)?;
+    let digits: String = text
+        .chars()
+        .filter(|&c| c != '\'')
+        .collect::<String>()
+        .trim_end_matches(['u', 'U', 'l', 'L'])
+        .to_string();
+    let (body, radix) = match number.radix {
+        Radix::Hexadecimal => (&digits[2..], 16),
+        Radix::Binary => (&digits[2..], 2),
+        Radix::Octal => (&digits[1..], 8),
+        Radix::Decimal => (&digits[..], 10),
+    };
+    u64::from_str_radix(body, radix)
+        .map(|value| value as i64)
+        .map_err(|_| format!("integer constant `{}` is too large", text))
+}
+
+/// identifiers and keywords, every name is a valid macro name
+pub fn is_word(token: &Token) -> bool {
+    token
+        .get_token_value()
+        .starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && !matches!(
+            token.get_token_type(),
+            StringValue | CharValue | NumberInteger | NumberFloat
+        )
+}
+
+#[cfg(test)]
+mod test {
+    use super::evaluate;
+    use crate::library::lexer::tokenizer::Tokenizer;
+
+    fn eval(text: &str) -> Result<i64, String> {
+        evaluate(&Tokenizer::new(text).tokenize().unwrap())
+    }
+
+    #[test]
+    fn test_that_conditions_are_evaluated() {
+        assert_eq!(eval("1 + 2 * 3 == 7"), Ok(1));
+        assert_eq!(eval("(0x10 >> 2) | 1"), Ok(5));
+        assert_eq!(eval("UNDEFINED || 'a' == 97"), Ok(1));
+        assert_eq!(eval("!0 ? -1 : 2"), Ok(-1));
+        assert_eq!(eval("2 > 1 and not 0"), Ok(1));
+        assert_eq!(eval("10UL % 4 ^ 017"), Ok(13));
+    }
+
+    #[test]
+    fn test_that_unsigned_operands_make_unsigned_operations() {
+        assert_eq!(eval("18446744073709551615u > 0"), Ok(1));
+        assert_eq!(eval("0xFFFFFFFFFFFFFFFF > 0"), Ok(1));
+        assert_eq!(eval("-1 < 0u"), Ok(0));
+        assert_eq!(eval("-1 < 0"), Ok(1));
+        assert_eq!(eval("(0u - 1) / 2 > 0"), Ok(1));
+        assert_eq!(eval("(1 ? -1 : 0u) > 0"), Ok(1));
+        // comparisons are signed again
+        assert_eq!(eval("(1u < 2) - 2 < 0"), Ok(1));
+    }
+
+    #[test]
+    fn test_that_invalid_conditions_are_described() {
+        assert_eq!(eval("1 / 0"), Err("division by zero in #if".to_string()));
+        assert_eq!(
+            eval("(1"),
+            Err("expected `)` in preprocessor expression".to_string())
+        );
+        assert_eq!(
+            eval("1 2"),
+            Err("missing binary operator before `2`".to_string())
+        );
+        assert_eq!(
+            eval("1.5"),
+            Err("floating constant in preprocessor expression".to_string())
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod error;
+pub mod expression;
+#[allow(clippy::module_inception)]
+pub mod preprocessor;
@@ -0,0 +1,1795 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::library::ast::{BinaryOp, Expr, Item, MacroBody};
+use crate::library::lexeme::definition::{TokenKind, TokenType};
+use crate::library::lexeme::span::Span;
+use crate::library::lexeme::token::Token;
+use crate::library::lexer::tokenizer::Tokenizer;
+use crate::library::parser::parser::init_parser;
+use crate::library::preprocessor::error::{PreprocessError, PreprocessErrors};
+use crate::library::preprocessor::expression::{evaluate, is_word};
+
+/// #include nested deeper than this is taken to be an include cycle
+const MAX_INCLUDE_DEPTH: usize = 200;
+
+/// included files with these extensions are translated into modules,
+/// others such as `.inc` and `.def` are fragments placed at the #include
+const HEADER_EXTENSIONS: [&str; 5] = ["h", "hh", "hpp", "hxx", "h++"];
+
+/// Options of the preprocessor, given with -D and -I on the command line
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// NAME or NAME=VALUE, a NAME alone is defined as 1
+    pub defines: Vec<String>,
+    /// searched for #include <..>, and for #include ".." after the
+    /// directory of the including file
+    pub include_dirs: Vec<PathBuf>,
+    /// configuration macros and the cfg predicate they map to, #if groups
+    /// testing them keep every branch. `None` evaluates every condition
+    pub cfg: Option<Vec<(String, String)>>,
+}
+
+/// configuration macros known to --cfg, `DEBUG` -> `feature = "debug"`
+const CFG_MACROS: [(&str, &str); 21] = [
+    ("_WIN32", "windows"),
+    ("_WIN64", "windows"),
+    ("WIN32", "windows"),
+    ("__unix__", "unix"),
+    ("__unix", "unix"),
+    ("__linux__", "target_os = \"linux\""),
+    ("__linux", "target_os = \"linux\""),
+    ("__APPLE__", "target_vendor = \"apple\""),
+    ("__MACH__", "target_os = \"macos\""),
+    ("__FreeBSD__", "target_os = \"freebsd\""),
+    ("__ANDROID__", "target_os = \"android\""),
+    ("__x86_64__", "target_arch = \"x86_64\""),
+    ("_M_X64", "target_arch = \"x86_64\""),
+    ("__i386__", "target_arch = \"x86\""),
+    ("_M_IX86", "target_arch = \"x86\""),
+    ("__aarch64__", "target_arch = \"aarch64\""),
+    ("__arm__", "target_arch = \"arm\""),
+    ("__wasm__", "target_family = \"wasm\""),
+    ("NDEBUG", "not(debug_assertions)"),
+    ("_DEBUG", "debug_assertions"),
+    ("DEBUG", "feature = \"debug\""),
+];
+
+/// cfg names rust sets itself, a --cfg-map predicate naming one is kept as is
+const BUILTIN_CFGS: [&str; 8] = [
+    "unix",
+    "windows",
+    "test",
+    "debug_assertions",
+    "doc",
+    "doctest",
+    "miri",
+    "proc_macro",
+];
+
+/**
+ * cfg_table:
+ * the configuration macros known to --cfg, with the NAME=PREDICATE
+ * entries of --cfg-map added or replacing the ones of the same name.
+ * A predicate which is a plain name rust doesn't set is taken as a feature,
+ * `USE_SSL=ssl` -> `feature = "ssl"`, `ON_UNIX=unix` is kept
+ */
+pub fn cfg_table(entries: &[String]) -> Vec<(String, String)> {
+    let mut table: Vec<(String, String)> = CFG_MACROS
+        .iter()
+        .map(|(name, predicate)| (name.to_string(), predicate.to_string()))
+        .collect();
+    for entry in entries {
+        let (name, predicate) = entry.split_once('=').unwrap_or((entry, ""));
+        let predicate = match predicate.trim() {
+            "" => format!("feature = \"{}\"", name.to_lowercase()),
+            plain
+                if !BUILTIN_CFGS.contains(&plain)
+                    && plain
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || c == '_' || c == '-') =>
+            {
+                format!("feature = \"{}\"", plain)
+            }
+            predicate => predicate.to_string(),
+        };
+        table.retain(|(known, _)| known != name);
+        table.push((name.to_string(), predicate));
+    }
+    table
+}
+
+/// A #define, `params` is None for object-like macros
+#[derive(Debug, Clone)]
+pub struct Macro {
+    pub name: String,
+    pub params: Option<Vec<String>>,
+    /// the parameter list ends with `...`, the extra arguments are __VA_ARGS__
+    pub variadic: bool,
+    pub body: Vec<Token>,
+    /// translated to a Rust constant, function or macro, expanded only
+    /// in #if and #include
+    pub translated: bool,
+}
+
+/// state of an #if group
+struct Group {
+    // one of the branches has been taken
+    taken: bool,
+    // the branch being read is taken
+    active: bool,
+    // the group is inside a taken branch
+    parent_active: bool,
+    else_seen: bool,
+    span: Span,
+    // the branches may be kept as cfg, they hold whole declarations
+    // or statements
+    fits_cfg: bool,
+    // cfg predicates of the branches kept so far
+    cfgs: Vec<String>,
+    // the branch being read is marked with its cfg predicate
+    marked: bool,
+}
+
+/// what decides whether a branch is kept
+enum Test {
+    /// kept as `#[cfg(..)]`
+    Cfg(String),
+    /// evaluated
+    Value(bool),
+}
+
+struct Preprocessor<'a> {
+    config: &'a Config,
+    macros: HashMap<String, Macro>,
+    // files which asked with #pragma once to be included once
+    once: HashSet<PathBuf>,
+    depth: usize,
+    errors: Vec<PreprocessError>,
+    // macros declared for the translation, a redefinition is expanded instead
+    declared: HashSet<String>,
+    // translated macros are expanded while reading #if and #include
+    expand_translated: bool,
+    // headers translated into modules, included once
+    modules: HashSet<PathBuf>,
+    // number of #if branches kept as cfg being read
+    cfg_depth: usize,
+    // the branches of the #if group read next hold whole declarations
+    // or statements
+    next_fits_cfg: bool,
+    // the file being read, `__FILE__`
+    file: Option<String>,
+}
+
+/// reads a condition made of configuration macros into a cfg predicate
+struct CfgReader<'t, 'l> {
+    tokens: &'t [Token],
+    head: usize,
+    lookup: &'l dyn Fn(&Token) -> Option<String>,
+}
+
+impl<'t, 'l> CfgReader<'t, 'l> {
+    fn eat(&mut self, typ: TokenType) -> bool {
+        let found = self
+            .tokens
+            .get(self.head)
+            .is_some_and(|t| t.get_token_type() == typ);
+        if found {
+            self.head += 1;
+        }
+        found
+    }
+
+    /// a || b
+    fn any(&mut self) -> Option<String> {
+        let mut predicates = vec![self.all()?];
+        while self.eat(TokenType::LogicalOr) {
+            predicates.push(self.all()?);
+        }
+        Some(combine("any", predicates))
+    }
+
+    /// a && b
+    fn all(&mut self) -> Option<String> {
+        let mut predicates = vec![self.unary()?];
+        while self.eat(TokenType::LogicalAnd) {
+            predicates.push(self.unary()?);
+        }
+        Some(combine("all", predicates))
+    }
+
+    /// !a, (a), defined(NAME), defined NAME and NAME
+    fn unary(&mut self) -> Option<String> {
+        if self.eat(TokenType::LogicalNot) {
+            return Some(negate(&self.unary()?));
+        }
+        if self.eat(TokenType::LeftBracket) {
+            let predicate = self.any()?;
+            return if self.eat(TokenType::RightBracket) {
+                Some(predicate)
+            } else {
+                None
+            };
+        }
+        let token = self.tokens.get(self.head)?;
+        self.head += 1;
+        if token.get_token_value() != "defined" {
+            return (self.lookup)(token);
+        }
+        let bracketed = self.eat(TokenType::LeftBracket);
+        let name = self.tokens.get(self.head)?;
+        self.head += 1;
+        if bracketed && !self.eat(TokenType::RightBracket) {
+            return None;
+        }
+        (self.lookup)(name)
+    }
+}
+
+/// not(a), `not(not(a))` is a
+fn negate(predicate: &str) -> String {
+    match arguments("not", predicate) {
+        Some(inner) => inner.to_string(),
+        None => format!("not({})", predicate),
+    }
+}
+
+/// arguments of the predicate `operator(..)`
+fn arguments<'p>(operator: &str, predicate: &'p str) -> Option<&'p str> {
+    let inner = predicate
+        .strip_prefix(operator)?
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+    let mut depth = 0;
+    let whole = inner.chars().all(|c| {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        depth >= 0
+    });
+    if whole {
+        Some(inner)
+    } else {
+        None
+    }
+}
+
+/// any(a, b), or the predicate alone
+fn combine(operator: &str, mut predicates: Vec<String>) -> String {
+    if predicates.len() == 1 {
+        predicates.remove(0)
+    } else {
+        format!("{}({})", operator, predicates.join(", "))
+    }
+}
+
+/**
+ * preprocess:
+ * carries out the directives of the tokenized `text` and expands its macros,
+ * `path` is the file the text was read from, included files are searched
+ * next to it. Tokens of macro expansions and included files are placed at
+ * the invocation and the #include. Directives left for the translation,
+ * #include of headers which are not found and #pragma, become one
+ * `Directive` token each
+ */
+pub fn preprocess(
+    tokens: Vec<Token>,
+    text: &str,
+    path: Option<&Path>,
+    config: &Config,
+) -> Result<Vec<Token>, PreprocessErrors> {
+    let mut preprocessor = Preprocessor {
+        config,
+        macros: HashMap::new(),
+        once: HashSet::new(),
+        depth: 0,
+        errors: Vec::new(),
+        declared: HashSet::new(),
+        expand_translated: false,
+        modules: HashSet::new(),
+        cfg_depth: 0,
+        next_fits_cfg: false,
+        file: None,
+    };
+    for define in &config.defines {
+        preprocessor.define_option(define);
+    }
+    let tokens = preprocessor.run(tokens, text, path);
+    if preprocessor.errors.is_empty() {
+        Ok(tokens)
+    } else {
+        Err(PreprocessErrors {
+            errors: preprocessor.errors,
+            tokens,
+        })
+    }
+}
+
+impl<'a> Preprocessor<'a> {
+    fn error(&mut self, message: String, span: Span) {
+        self.errors.push(PreprocessError::new(message, span));
+    }
+
+    /// tokens of one file, directives are read from `text`
+    fn run(&mut self, tokens: Vec<Token>, text: &str, path: Option<&Path>) -> Vec<Token> {
+        let file = path.map(|path| path.to_string_lossy().to_string());
+        let outer = std::mem::replace(&mut self.file, file);
+        let mut out = Vec::new();
+        // tokens between two directives, expanded together
+        let mut chunk = Vec::new();
+        let mut groups: Vec<Group> = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            if token.get_token_type() == TokenType::HeaderInclude && begins_line(text, token) {
+                let mut end = i + 1;
+                while end < tokens.len() && !begins_line(text, &tokens[end]) {
+                    end += 1;
+                }
+                let line: Vec<Token> = tokens[i + 1..end]
+                    .iter()
+                    .filter(|t| t.get_token_kind() != TokenKind::Comments)
+                    .cloned()
+                    .collect();
+                let expanded = self.expand(std::mem::take(&mut chunk));
+                out.extend(expanded);
+                self.next_fits_cfg =
+                    self.config.cfg.is_some() && fits_cfg(&out, &tokens[end..], text);
+                self.directive(token, &line, &mut groups, &mut out, path);
+                i = end;
+                continue;
+            }
+            if groups.last().is_none_or(|group| group.active) {
+                chunk.push(token.clone());
+            }
+            i += 1;
+        }
+        let expanded = self.expand(chunk);
+        out.extend(expanded);
+        for group in groups {
+            self.error("unterminated conditional directive".to_string(), group.span);
+        }
+        self.file = outer;
+        out
+    }
+
+    /// carries out the directive `# line`
+    fn directive(
+        &mut self,
+        hash: &Token,
+        line: &[Token],
+        groups: &mut Vec<Group>,
+        out: &mut Vec<Token>,
+        path: Option<&Path>,
+    ) {
+        // the null directive, a # alone
+        let name = match line.first() {
+            Some(name) => name.get_token_value(),
+            None => return,
+        };
+        let span = line
+            .iter()
+            .fold(hash.get_span(), |span, t| span.to(t.get_span()));
+        let rest = &line[1..];
+        let active = groups.last().is_none_or(|group| group.active);
+        match name.as_str() {
+            "if" | "ifdef" | "ifndef" => {
+                let mut group = Group {
+                    taken: false,
+                    active: false,
+                    parent_active: active,
+                    else_seen: false,
+                    span,
+                    fits_cfg: self.next_fits_cfg,
+                    cfgs: Vec::new(),
+                    marked: false,
+                };
+                self.branch(&mut group, &name, rest, hash, span, out);
+                groups.push(group);
+            }
+            "elif" | "else" => {
+                let mut group = match groups.pop() {
+                    Some(group) => group,
+                    None => return self.error(format!("#{} without #if", name), span),
+                };
+                if group.else_seen {
+                    self.error(format!("#{} after #else", name), span);
+                }
+                group.else_seen |= name == "else";
+                self.branch(&mut group, &name, rest, hash, span, out);
+                groups.push(group);
+            }
+            "endif" => match groups.pop() {
+                Some(group) if group.marked => {
+                    self.cfg_depth -= 1;
+                    out.push(marker(hash, TokenType::HeaderIfDefineEnd, "#endif", span));
+                }
+                Some(_) => {}
+                None => self.error("#endif without #if".to_string(), span),
+            },
+            // directives in groups which are not taken are not carried out
+            _ if !active => {}
+            "define" => {
+                self.define(rest, span);
+                self.declare(hash, rest, span, out);
+            }
+            "undef" => match rest.first() {
+                Some(name) if is_word(name) => {
+                    self.macros.remove(&name.get_token_value());
+                }
+                _ => self.error("macro names must be identifiers".to_string(), span),
+            },
+            "include" => self.include(hash, rest, span, out, path),
+            "pragma" if rest.first().is_some_and(|t| t.get_token_value() == "once") => {
+                if let Some(path) = path {
+                    self.once.insert(canonical(path));
+                }
+            }
+            // the branch may not be compiled
+            "error" if self.cfg_depth > 0 => out.push(directive_token(hash, &name, rest, span)),
+            "error" => self.error(format!("#error {}", spell(rest)), span),
+            "pragma" | "line" | "warning" | "ident" => {
+                out.push(directive_token(hash, &name, rest, span))
+            }
+            _ => self.error(format!("invalid preprocessing directive `#{}`", name), span),
+        }
+    }
+
+    /**
+     * branch:
+     * starts the branch of `group` beginning with the directive `name`.
+     * A branch whose condition tests configuration macros only is kept
+     * between markers holding its cfg predicate, which excludes the cfg
+     * branches before it. Other conditions are evaluated
+     */
+    fn branch(
+        &mut self,
+        group: &mut Group,
+        name: &str,
+        rest: &[Token],
+        hash: &Token,
+        span: Span,
+        out: &mut Vec<Token>,
+    ) {
+        if group.marked {
+            group.marked = false;
+            self.cfg_depth -= 1;
+            out.push(marker(hash, TokenType::HeaderIfDefineEnd, "#endif", span));
+        }
+        group.active = false;
+        if !group.parent_active || group.taken {
+            return;
+        }
+        let predicate = if group.fits_cfg {
+            self.cfg_predicate(name, rest)
+        } else {
+            None
+        };
+        let test = match predicate {
+            Some(predicate) => Test::Cfg(predicate),
+            None => Test::Value(match name {
+                "if" | "elif" => self.condition(rest, span),
+                "ifdef" => self.is_defined(rest, span),
+                "ifndef" => !self.is_defined(rest, span),
+                _ => true,
+            }),
+        };
+        let mut predicates: Vec<String> = group.cfgs.iter().map(|p| negate(p)).collect();
+        match test {
+            Test::Value(false) => return,
+            Test::Value(true) => group.taken = true,
+            Test::Cfg(predicate) => {
+                // all(not(a), b, c) rather than all(not(a), all(b, c))
+                let parts = match arguments("all", &predicate) {
+                    Some(parts) if !predicates.is_empty() => parts,
+                    _ => &predicate,
+                };
+                predicates.push(parts.to_string());
+                group.cfgs.push(predicate);
+            }
+        }
+        group.active = true;
+        if !predicates.is_empty() {
+            let predicate = if predicates.len() == 1 {
+                predicates.remove(0)
+            } else {
+                format!("all({})", predicates.join(", "))
+            };
+            group.marked = true;
+            self.cfg_depth += 1;
+            out.push(marker(
+                hash,
+                TokenType::HeaderIfDefineStart,
+                &predicate,
+                span,
+            ));
+        }
+    }
+
+    /**
+     * cfg_predicate:
+     * the condition of #if, #ifdef or #elif as a cfg predicate, when
+     * the --cfg mode is on and the condition only combines configuration
+     * macros of the table with `defined`, `!`, `&&` and `||`.
+     * `defined(_WIN32) && !defined(NDEBUG)` -> all(windows, debug_assertions)
+     */
+    fn cfg_predicate(&self, name: &str, rest: &[Token]) -> Option<String> {
+        let table = self.config.cfg.as_ref()?;
+        // macros defined in the source or with -D are evaluated
+        let lookup = |t: &Token| -> Option<String> {
+            let name = t.get_token_value();
+            if !is_word(t) || self.macros.contains_key(&name) {
+                return None;
+            }
+            table
+                .iter()
+                .find(|(macro_name, _)| *macro_name == name)
+                .map(|(_, predicate)| predicate.clone())
+        };
+        match (name, rest) {
+            ("ifdef", [macro_name]) => lookup(macro_name),
+            ("ifndef", [macro_name]) => lookup(macro_name).map(|p| negate(&p)),
+            ("if", _) | ("elif", _) => {
+                let mut reader = CfgReader {
+                    tokens: rest,
+                    head: 0,
+                    lookup: &lookup,
+                };
+                let predicate = reader.any()?;
+                if reader.head == rest.len() {
+                    Some(predicate)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// #ifdef NAME and #ifndef NAME
+    fn is_defined(&mut self, rest: &[Token], span: Span) -> bool {
+        match rest.first() {
+            Some(name) if is_word(name) => self.macros.contains_key(&name.get_token_value()),
+            _ => {
+                self.error("macro names must be identifiers".to_string(), span);
+                false
+            }
+        }
+    }
+
+    /// value of the condition of #if and #elif, `defined` is replaced before expansion
+    fn condition(&mut self, rest: &[Token], span: Span) -> bool {
+        let mut tokens = Vec::new();
+        let mut k = 0;
+        while k < rest.len() {
+            if rest[k].get_token_value() != "defined" {
+                tokens.push(rest[k].clone());
+                k += 1;
+                continue;
+            }
+            // defined NAME and defined(NAME)
+            let bracketed = rest
+                .get(k + 1)
+                .is_some_and(|t| t.get_token_type() == TokenType::LeftBracket);
+            let name = if bracketed {
+                rest.get(k + 2)
+            } else {
+                rest.get(k + 1)
+            };
+            let closed = !bracketed
+                || rest
+                    .get(k + 3)
+                    .is_some_and(|t| t.get_token_type() == TokenType::RightBracket);
+            match name {
+                Some(name) if is_word(name) && closed => {
+                    let defined = self.macros.contains_key(&name.get_token_value());
+                    tokens.extend(lex(if defined { "1" } else { "0" }, name.get_span()));
+                }
+                _ => {
+                    self.error(
+                        "operator `defined` requires an identifier".to_string(),
+                        span,
+                    );
+                    return false;
+                }
+            }
+            k += if bracketed { 4 } else { 2 };
+        }
+        let tokens = self.expand_all(tokens);
+        match evaluate(&tokens) {
+            Ok(value) => value != 0,
+            Err(message) => {
+                self.error(message, span);
+                false
+            }
+        }
+    }
+
+    /// -D NAME=VALUE, read as #define NAME VALUE
+    fn define_option(&mut self, define: &str) {
+        let (name, value) = define.split_once('=').unwrap_or((define, "1"));
+        let tokens = lex(&format!("{} {}", name, value), Span::default());
+        self.define(&tokens, Span::default());
+    }
+
+    /// #define NAME body, #define NAME(params) body
+    fn define(&mut self, rest: &[Token], span: Span) {
+        let name = match rest.first() {
+            Some(name) if is_word(name) => name,
+            _ => return self.error("macro names must be identifiers".to_string(), span),
+        };
+        // a bracket right after the name begins the parameters
+        let function_like = rest.get(1).is_some_and(|t| {
+            t.get_token_type() == TokenType::LeftBracket
+                && t.get_span().start.offset == name.get_span().end.offset
+        });
+        let mut params = Vec::new();
+        let mut variadic = false;
+        let mut body_start = 1;
+        if function_like {
+            let mut k = 2;
+            loop {
+                match rest.get(k) {
+                    Some(t) if t.get_token_type() == TokenType::RightBracket => break,
+                    Some(t) if t.get_token_type() == TokenType::Ellipsis => variadic = true,
+                    Some(t) if is_word(t) && !variadic => params.push(t.get_token_value()),
+                    _ => {
+                        return self.error(
+                            "expected parameter name or `)` in macro parameter list".to_string(),
+                            span,
+                        )
+                    }
+                }
+                k += 1;
+                match rest.get(k) {
+                    Some(t) if t.get_token_type() == TokenType::Comma => k += 1,
+                    Some(t) if t.get_token_type() == TokenType::RightBracket => break,
+                    _ => {
+                        return self.error(
+                            "expected `,` or `)` in macro parameter list".to_string(),
+                            span,
+                        )
+                    }
+                }
+            }
+            body_start = k + 1;
+        }
+        let body = rest[body_start..].to_vec();
+        let is_paste =
+            |t: Option<&Token>| t.is_some_and(|t| t.get_token_type() == TokenType::TokenPaste);
+        if is_paste(body.first()) || is_paste(body.last()) {
+            return self.error(
+                "`##` cannot appear at either end of a macro expansion".to_string(),
+                span,
+            );
+        }
+        if function_like {
+            let is_param = |t: &Token| {
+                let value = t.get_token_value();
+                params.contains(&value) || (variadic && value == "__VA_ARGS__")
+            };
+            let stringized = body.windows(2).all(|pair| {
+                pair[0].get_token_type() != TokenType::HeaderInclude || is_param(&pair[1])
+            });
+            let last_hash = body
+                .last()
+                .is_some_and(|t| t.get_token_type() == TokenType::HeaderInclude);
+            if !stringized || last_hash {
+                return self.error("`#` is not followed by a macro parameter".to_string(), span);
+            }
+        }
+        let name = name.get_token_value();
+        self.macros.insert(
+            name.clone(),
+            Macro {
+                name,
+                params: if function_like { Some(params) } else { None },
+                variadic,
+                body,
+                translated: false,
+            },
+        );
+    }
+
+    /**
+     * declare:
+     * an object-like macro whose body is a constant expression, and a
+     * function-like macro whose body is an expression or whole statements,
+     * are kept as a `#define NAME body` declaration ended by a
+     * `DirectiveEnd` for the parser instead of being expanded.
+     * Macros pasting tokens are declared, for the parser to report, but
+     * still expanded. The declaration is moved out of the function or
+     * record being read, as the macro is visible to the rest of the file
+     */
+    fn declare(&mut self, hash: &Token, rest: &[Token], span: Span, out: &mut Vec<Token>) {
+        let name = match rest.first() {
+            Some(name) => name.get_token_value(),
+            None => return,
+        };
+        let macros = &self.macros;
+        let is_constant = |t: &Token| match t.get_token_type() {
+            TokenType::NumberInteger
+            | TokenType::NumberFloat
+            | TokenType::CharValue
+            | TokenType::StringValue
+            | TokenType::True
+            | TokenType::False => true,
+            TokenType::LeftBracket
+            | TokenType::RightBracket
+            | TokenType::Plus
+            | TokenType::Minus
+            | TokenType::LogicalNot
+            | TokenType::BitwiseNegate
+            | TokenType::TernaryOpetator
+            | TokenType::Colon => true,
+            typ if BinaryOp::from_token(typ).is_some() => true,
+            _ => {
+                is_word(t)
+                    && macros
+                        .get(&t.get_token_value())
+                        .is_some_and(|m| m.translated && m.params.is_none())
+            }
+        };
+        let found = match self.macros.get(&name) {
+            Some(found) => found.clone(),
+            None => return,
+        };
+        let pasted = found
+            .body
+            .iter()
+            .any(|t| t.get_token_type() == TokenType::TokenPaste);
+        // a constant is one value, `1 + 1` stays expanded for `TWO * 2`
+        let declared = match found.params {
+            None => found.body.iter().all(is_constant) && is_operand(&found.body),
+            Some(_) => true,
+        };
+        // a macro redefined later is expanded from there on, unless
+        // the definitions are in branches kept as cfg
+        if (self.declared.contains(&name) && self.cfg_depth == 0)
+            || !declared
+            || !is_balanced(&found.body)
+        {
+            return;
+        }
+        let mut declaration = vec![Token::new(
+            "#define".to_string(),
+            TokenKind::Preprocessors,
+            TokenType::HeaderDefine,
+            span.start.line,
+            hash.get_token_id(),
+        )
+        .with_span(span)];
+        match found.params {
+            // other macros in the body are expanded, the parameters are not
+            Some(ref params) if !pasted => {
+                let head = rest.len() - found.body.len();
+                declaration.extend(rest[..head].iter().cloned());
+                let mut hidden = params.clone();
+                hidden.push("__VA_ARGS__".to_string());
+                let body = self.expand_hiding(found.body.clone(), hidden);
+                declaration.extend(body);
+            }
+            _ => declaration.extend(rest.iter().cloned()),
+        }
+        declaration.push(
+            Token::new(
+                "\n".to_string(),
+                TokenKind::Preprocessors,
+                TokenType::DirectiveEnd,
+                span.end.line,
+                hash.get_token_id(),
+            )
+            .with_span(span),
+        );
+        // a body the translation can not read is expanded instead
+        if !pasted && !is_translatable(&declaration) {
+            return;
+        }
+        if let Some(found) = self.macros.get_mut(&name) {
+            found.translated = !pasted;
+        }
+        self.declared.insert(name);
+        let at = top_level_end(out);
+        out.splice(at..at, declaration);
+    }
+
+    /// expansion with the translated macros replaced as well
+    fn expand_all(&mut self, tokens: Vec<Token>) -> Vec<Token> {
+        self.expand_translated = true;
+        let tokens = self.expand(tokens);
+        self.expand_translated = false;
+        tokens
+    }
+
+    /// #include "file" and #include <file>
+    fn include(
+        &mut self,
+        hash: &Token,
+        rest: &[Token],
+        span: Span,
+        out: &mut Vec<Token>,
+        path: Option<&Path>,
+    ) {
+        // #include MACRO
+        let rest = match rest.first() {
+            Some(first) if is_word(first) => self.expand_all(rest.to_vec()),
+            _ => rest.to_vec(),
+        };
+        let (name, angled) = match rest.first().map(|t| t.get_token_type()) {
+            Some(TokenType::StringValue) => {
+                let quoted = rest[0].get_token_value();
+                (quoted.trim_matches('"').to_string(), false)
+            }
+            Some(TokenType::LessThan) => {
+                let close = rest
+                    .iter()
+                    .position(|t| t.get_token_type() == TokenType::GreaterThan);
+                match close {
+                    Some(close) => (spell(&rest[1..close]), true),
+                    None => return self.error("missing `>` in #include".to_string(), span),
+                }
+            }
+            _ => {
+                return self.error(
+                    "#include expects \"FILENAME\" or <FILENAME>".to_string(),
+                    span,
+                )
+            }
+        };
+        let file = match self.find_include(&name, angled, path) {
+            Some(file) => file,
+            // system headers are left for the translation
+            None => {
+                let written = if angled {
+                    format!("<{}>", name)
+                } else {
+                    format!("\"{}\"", name)
+                };
+                out.push(directive_token(hash, "include", &lex(&written, span), span));
+                return;
+            }
+        };
+        if self.once.contains(&canonical(&file)) {
+            return;
+        }
+        if self.depth >= MAX_INCLUDE_DEPTH {
+            return self.error(format!("#include of `{}` nested too deeply", name), span);
+        }
+        let text = match fs::read_to_string(&file) {
+            Ok(text) => text,
+            Err(error) => {
+                return self.error(format!("`{}` could not be read: {}", name, error), span)
+            }
+        };
+        let tokens = match Tokenizer::new(&text).tokenize() {
+            Ok(tokens) => tokens,
+            Err(failure) => {
+                for error in &failure.errors {
+                    self.error(
+                        format!("{}:{}: {}", name, error.span.start, error.message),
+                        span,
+                    );
+                }
+                failure.tokens
+            }
+        };
+        let reported = self.errors.len();
+        self.depth += 1;
+        let tokens = self.run(tokens, &text, Some(&file));
+        self.depth -= 1;
+        let module = is_header(&file) && top_level_end(out) == out.len();
+        // errors in the header are reported at the #include
+        for error in &mut self.errors[reported..] {
+            if error.span != span {
+                error.message = format!("{}:{}: {}", name, error.span.start, error.message);
+                error.span = span;
+            }
+        }
+        if !module {
+            out.extend(tokens.into_iter().map(|t| t.with_location(span)));
+            return;
+        }
+        // the header is translated on its own, only its macros are kept
+        // along with the modules it includes, which are visible here too
+        out.extend(
+            tokens
+                .into_iter()
+                .filter(|t| t.get_token_type() == TokenType::HeaderModule)
+                .map(|t| t.with_location(span)),
+        );
+        if self.modules.insert(canonical(&file)) {
+            out.push(
+                Token::new(
+                    file.to_string_lossy().to_string(),
+                    TokenKind::Preprocessors,
+                    TokenType::HeaderModule,
+                    span.start.line,
+                    hash.get_token_id(),
+                )
+                .with_span(span),
+            );
+        }
+    }
+
+    /// directory of the including file first for #include "..", then the -I directories
+    fn find_include(&self, name: &str, angled: bool, path: Option<&Path>) -> Option<PathBuf> {
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        if !angled {
+            if let Some(dir) = path.and_then(Path::parent) {
+                dirs.push(dir.to_path_buf());
+            }
+        }
+        dirs.extend(self.config.include_dirs.iter().cloned());
+        dirs.into_iter()
+            .map(|dir| dir.join(name))
+            .find(|file| file.is_file())
+    }
+
+    /**
+     * expand:
+     * replaces the macro invocations in `tokens`, a name is not expanded
+     * again in the tokens of its own expansion
+     */
+    fn expand(&mut self, tokens: Vec<Token>) -> Vec<Token> {
+        self.expand_hiding(tokens, Vec::new())
+    }
+
+    /// expansion where the names `hidden` are not expanded
+    fn expand_hiding(&mut self, tokens: Vec<Token>, hidden: Vec<String>) -> Vec<Token> {
+        // tokens waiting to be read, with the macros they must not expand
+        let mut queue: VecDeque<(Token, Vec<String>)> =
+            tokens.into_iter().map(|t| (t, hidden.clone())).collect();
+        let mut out = Vec::new();
+        while let Some((token, mut hidden)) = queue.pop_front() {
+            let name = token.get_token_value();
+            if let Some(text) = self.predefined(&token) {
+                out.extend(lex(&text, token.get_span()));
+                continue;
+            }
+            let found = match self.macros.get(&name) {
+                Some(found)
+                    if is_word(&token)
+                        && !hidden.contains(&name)
+                        && (self.expand_translated || !found.translated) =>
+                {
+                    found.clone()
+                }
+                _ => {
+                    out.push(token);
+                    continue;
+                }
+            };
+            let mut span = token.get_span();
+            let args = if found.params.is_some() {
+                // a function-like macro name alone is not an invocation
+                let open = queue
+                    .iter()
+                    .position(|(t, _)| t.get_token_kind() != TokenKind::Comments);
+                if !open.is_some_and(|k| queue[k].0.get_token_type() == TokenType::LeftBracket) {
+                    out.push(token);
+                    continue;
+                }
+                queue.drain(..=open.unwrap_or(0));
+                match self.collect_args(&mut queue, &found, span) {
+                    Some((args, close)) => {
+                        span = span.to(close);
+                        args
+                    }
+                    None => continue,
+                }
+            } else {
+                Vec::new()
+            };
+            let body = self.substitute(&found, &args, span);
+            hidden.push(name);
+            for t in body.into_iter().rev() {
+                queue.push_front((t.with_location(span), hidden.clone()));
+            }
+        }
+        out
+    }
+
+    /**
+     * predefined:
+     * the text of `__LINE__`, the line of the invocation, and of `__FILE__`,
+     * the file being read, unless the source defines them
+     */
+    fn predefined(&self, token: &Token) -> Option<String> {
+        let name = token.get_token_value();
+        if !is_word(token) || self.macros.contains_key(&name) {
+            return None;
+        }
+        match name.as_str() {
+            "__LINE__" => Some(token.get_span().start.line.to_string()),
+            "__FILE__" => self.file.as_ref().map(|file| format!("{:?}", file)),
+            _ => None,
+        }
+    }
+
+    /// arguments of an invocation till the closing bracket, and its span
+    fn collect_args(
+        &mut self,
+        queue: &mut VecDeque<(Token, Vec<String>)>,
+        found: &Macro,
+        span: Span,
+    ) -> Option<(Vec<Vec<Token>>, Span)> {
+        let mut args: Vec<Vec<Token>> = vec![Vec::new()];
+        let mut depth = 0;
+        let close = loop {
+            let token = match queue.pop_front() {
+                Some((token, _)) => token,
+                None => {
+                    self.error(
+                        format!("unterminated argument list invoking macro `{}`", found.name),
+                        span,
+                    );
+                    return None;
+                }
+            };
+            match token.get_token_type() {
+                TokenType::RightBracket if depth == 0 => break token.get_span(),
+                TokenType::Comma if depth == 0 => {
+                    args.push(Vec::new());
+                    continue;
+                }
+                TokenType::LeftBracket => depth += 1,
+                TokenType::RightBracket => depth -= 1,
+                _ if token.get_token_kind() == TokenKind::Comments => continue,
+                _ => {}
+            }
+            if let Some(arg) = args.last_mut() {
+                arg.push(token);
+            }
+        };
+        let params = found.params.as_ref().map_or(0, Vec::len);
+        // F() passes no argument to a macro without parameters
+        if params == 0 && args.len() == 1 && args[0].is_empty() {
+            args.clear();
+        }
+        let enough = if found.variadic {
+            args.len() >= params
+        } else {
+            args.len() == params
+        };
+        if !enough {
+            self.error(
+                format!(
+                    "macro `{}` requires {} arguments, but {} given",
+                    found.name,
+                    params,
+                    args.len()
+                ),
+                span.to(close),
+            );
+            return None;
+        }
+        Some((args, close))
+    }
+
+    /// body of the macro with the arguments in place of the parameters, # and ## applied
+    fn substitute(&mut self, found: &Macro, args: &[Vec<Token>], span: Span) -> Vec<Token> {
+        let params = found.params.clone().unwrap_or_default();
+        let arg_of = |name: &str| -> Option<Vec<Token>> {
+            if let Some(k) = params.iter().position(|p| p == name) {
+                return args.get(k).cloned();
+            }
+            if found.variadic && name == "__VA_ARGS__" {
+                let extra = args.get(params.len()..).unwrap_or(&[]);
+                let comma = lex(",", span);
+                return Some(extra.join(&comma[..]));
+            }
+            None
+        };
+        let is_paste =
+            |t: Option<&Token>| t.is_some_and(|t| t.get_token_type() == TokenType::TokenPaste);
+        let body = &found.body;
+        let mut out: Vec<Token> = Vec::new();
+        let mut k = 0;
+        while k < body.len() {
+            let token = &body[k];
+            // #param
+            if found.params.is_some() && token.get_token_type() == TokenType::HeaderInclude {
+                if let Some(arg) = body.get(k + 1).and_then(|t| arg_of(&t.get_token_value())) {
+                    out.extend(lex(&stringize(&arg), span));
+                    k += 2;
+                    continue;
+                }
+            }
+            // lhs ## rhs, the operands are not expanded
+            if token.get_token_type() == TokenType::TokenPaste && k + 1 < body.len() {
+                let next = &body[k + 1];
+                let rhs = arg_of(&next.get_token_value()).unwrap_or_else(|| vec![next.clone()]);
+                match (out.pop(), rhs.split_first()) {
+                    (Some(lhs), Some((first, tail))) => {
+                        out.extend(self.paste(&lhs, first, span));
+                        out.extend(tail.iter().cloned());
+                    }
+                    (Some(lhs), None) => out.push(lhs),
+                    (None, _) => out.extend(rhs),
+                }
+                k += 2;
+                continue;
+            }
+            match arg_of(&token.get_token_value()) {
+                Some(arg) if is_paste(body.get(k + 1)) => out.extend(arg),
+                Some(arg) => out.extend(self.expand(arg)),
+                None => out.push(token.clone()),
+            }
+            k += 1;
+        }
+        out
+    }
+
+    /// the token spelled by `lhs` and `rhs` written together
+    fn paste(&mut self, lhs: &Token, rhs: &Token, span: Span) -> Vec<Token> {
+        let text = lhs.get_token_value() + &rhs.get_token_value();
+        let tokens = lex(&text, span);
+        if tokens.len() != 1 {
+            self.error(
+                format!(
+                    "pasting `{}` and `{}` does not give a valid preprocessing token",
+                    lhs.get_token_value(),
+                    rhs.get_token_value()
+                ),
+                span,
+            );
+        }
+        tokens
+    }
+}
+
+/**
+ * begins_line:
+ * true if only blanks are between the token and the start of its line,
+ * a line ending with a backslash continues on the next one
+ */
+fn begins_line(text: &str, token: &Token) -> bool {
+    let before = text.get(..token.get_span().start.offset).unwrap_or("");
+    let mut chars = before.chars().rev().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\r' | '\x0c' | '\x0b' => {}
+            '\n' => {
+                if chars.peek() == Some(&'\r') {
+                    chars.next();
+                }
+                if chars.peek() != Some(&'\\') {
+                    return true;
+                }
+                chars.next();
+            }
+            _ => return false,
+        }
+    }
+    true
+}
+
+/**
+ * fits_cfg:
+ * the #if group whose branches begin the tokens `following` starts after
+ * a whole declaration or statement of `out`, and each branch holds whole
+ * declarations or statements, so that they can be kept as cfg
+ */
+fn fits_cfg(out: &[Token], following: &[Token], text: &str) -> bool {
+    let is_end = |t: Option<&Token>| {
+        t.is_none_or(|t| {
+            matches!(
+                t.get_token_type(),
+                TokenType::Semicolon
+                    | TokenType::Colon
+                    | TokenType::LeftCurlyBrace
+                    | TokenType::RightCurlyBrace
+                    | TokenType::Directive
+                    | TokenType::DirectiveEnd
+                    | TokenType::HeaderIfDefineStart
+                    | TokenType::HeaderIfDefineEnd
+                    | TokenType::HeaderModule
+            )
+        })
+    };
+    let code = |t: &&Token| t.get_token_kind() != TokenKind::Comments;
+    if !is_end(out.iter().rev().find(code)) {
+        return false;
+    }
+    let mut depth = 0;
+    let mut branch: Vec<Token> = Vec::new();
+    let mut i = 0;
+    while i < following.len() {
+        let token = &following[i];
+        if !(token.get_token_type() == TokenType::HeaderInclude && begins_line(text, token)) {
+            if code(&token) {
+                branch.push(token.clone());
+            }
+            i += 1;
+            continue;
+        }
+        let name = following.get(i + 1).map(|t| t.get_token_value());
+        match name.as_deref() {
+            Some("if") | Some("ifdef") | Some("ifndef") => depth += 1,
+            Some("elif") | Some("else") | Some("endif") if depth == 0 => {
+                let whole = is_balanced(&branch)
+                    && is_end(branch.last())
+                    && branch
+                        .first()
+                        .is_none_or(|t| t.get_token_type() != TokenType::KeywordElse);
+                if !whole || name.as_deref() == Some("endif") {
+                    return whole;
+                }
+                branch.clear();
+            }
+            Some("endif") => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+        while i < following.len() && !begins_line(text, &following[i]) {
+            i += 1;
+        }
+    }
+    false
+}
+
+/// brackets and braces of the macro body are balanced, `#define BEGIN {` is not
+fn is_balanced(tokens: &[Token]) -> bool {
+    let mut open = Vec::new();
+    for token in tokens {
+        match token.get_token_type() {
+            TokenType::LeftBracket => open.push(TokenType::RightBracket),
+            TokenType::LeftSquareBracket => open.push(TokenType::RightSquareBracket),
+            TokenType::LeftCurlyBrace => open.push(TokenType::RightCurlyBrace),
+            typ @ (TokenType::RightBracket
+            | TokenType::RightSquareBracket
+            | TokenType::RightCurlyBrace)
+                if open.pop() != Some(typ) =>
+            {
+                return false
+            }
+            _ => {}
+        }
+    }
+    open.is_empty()
+}
+
+/// position of the bracket closing the one at `open`
+fn closing(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (k, token) in tokens.iter().enumerate().skip(open) {
+        match token.get_token_type() {
+            TokenType::LeftBracket => depth += 1,
+            TokenType::RightBracket => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(k);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/**
+ * is_operand:
+ * the macro body is one operand, a value or a name, a bracketed
+ * expression, a call or a stringized parameter, signed or not, so that
+ * the operators around an invocation can not take a part of it.
+ * `#define TWO 1 + 1` makes `TWO * 2` three
+ */
+fn is_operand(body: &[Token]) -> bool {
+    let body = match body.first().map(Token::get_token_type) {
+        Some(TokenType::Plus) | Some(TokenType::Minus) => &body[1..],
+        _ => body,
+    };
+    let is_value = |t: &Token| {
+        is_word(t)
+            || matches!(
+                t.get_token_type(),
+                TokenType::NumberInteger
+                    | TokenType::NumberFloat
+                    | TokenType::CharValue
+                    | TokenType::StringValue
+                    | TokenType::True
+                    | TokenType::False
+            )
+    };
+    match body {
+        [value] => is_value(value),
+        [hash, name] => hash.get_token_type() == TokenType::HeaderInclude && is_word(name),
+        [first, ..] if first.get_token_type() == TokenType::LeftBracket => {
+            closing(body, 0) == Some(body.len() - 1)
+        }
+        [name, open, ..] if is_word(name) && open.get_token_type() == TokenType::LeftBracket => {
+            closing(body, 1) == Some(body.len() - 1)
+        }
+        _ => false,
+    }
+}
+
+/**
+ * are_params_delimited:
+ * every use of a parameter in the macro body is a whole operand, in
+ * brackets, an argument, a subscript or a side of an assignment, or
+ * stringized. `#define DBL(x) x * 2` makes `DBL(a + 1)` `a + 1 * 2`.
+ * The start and the end of the body only delimit the statements of one
+ */
+fn are_params_delimited(body: &[Token], params: &[String], statements: bool) -> bool {
+    let opens = |t: Option<&Token>| match t {
+        None => statements,
+        Some(t) => {
+            t.get_token_kind() == TokenKind::AssignmentOperators
+                || matches!(
+                    t.get_token_type(),
+                    TokenType::LeftBracket
+                        | TokenType::LeftSquareBracket
+                        | TokenType::LeftCurlyBrace
+                        | TokenType::Comma
+                        | TokenType::Semicolon
+                        | TokenType::KeywordReturn
+                )
+        }
+    };
+    let closes = |t: Option<&Token>| match t {
+        None => statements,
+        Some(t) => {
+            t.get_token_kind() == TokenKind::AssignmentOperators
+                || matches!(
+                    t.get_token_type(),
+                    TokenType::RightBracket
+                        | TokenType::RightSquareBracket
+                        | TokenType::RightCurlyBrace
+                        | TokenType::Comma
+                        | TokenType::Semicolon
+                )
+        }
+    };
+    body.iter().enumerate().all(|(k, token)| {
+        if !(is_word(token) && params.contains(&token.get_token_value())) {
+            return true;
+        }
+        let before = k.checked_sub(1).map(|k| &body[k]);
+        before.is_some_and(|t| t.get_token_type() == TokenType::HeaderInclude)
+            || (opens(before) && closes(body.get(k + 1)))
+    })
+}
+
+/**
+ * is_translatable:
+ * the #define `declaration` parses into a constant or a macro whose
+ * parameters are only used as values. `for (int i = 0; i < (n); i++)`
+ * is not a whole statement, and `t v = 0` uses its parameters as a
+ * type and a name, such macros are expanded where they are used, as
+ * are those whose body or parameters are not delimited by brackets
+ */
+fn is_translatable(declaration: &[Token]) -> bool {
+    let found = match init_parser(declaration) {
+        Ok(unit) => match unit.items.as_slice() {
+            [Item::Constant(_)] => return true,
+            [Item::Macro(found)] => found.clone(),
+            _ => return false,
+        },
+        Err(_) => return false,
+    };
+    let mut params = found.params.clone();
+    params.push("__VA_ARGS__".to_string());
+    let body = declaration
+        .iter()
+        .skip_while(|t| t.get_token_type() != TokenType::RightBracket)
+        .filter(|t| params.contains(&t.get_token_value()))
+        .count();
+    let mut values = 0;
+    let mut count = |expr: &Expr| match expr {
+        Expr::Ident(name) | Expr::Stringize(name) if params.contains(name) => values += 1,
+        _ => {}
+    };
+    match found.body {
+        MacroBody::Expr(ref body) => body.visit(&mut count),
+        MacroBody::Stmts(ref stmts) => stmts.iter().for_each(|stmt| stmt.visit(&mut count)),
+    }
+    let open = declaration
+        .iter()
+        .position(|t| t.get_token_type() == TokenType::RightBracket)
+        .map_or(declaration.len(), |k| k + 1);
+    let tokens = &declaration[open..declaration.len() - 1];
+    let delimited = match found.body {
+        MacroBody::Expr(_) => is_operand(tokens) && are_params_delimited(tokens, &params, false),
+        MacroBody::Stmts(_) => are_params_delimited(tokens, &params, true),
+    };
+    values == body && delimited
+}
+
+/// position after the last complete top level declaration of `out`
+fn top_level_end(out: &[Token]) -> usize {
+    let mut depth = 0;
+    let mut end = 0;
+    for (k, token) in out.iter().enumerate() {
+        match token.get_token_type() {
+            TokenType::LeftCurlyBrace => depth += 1,
+            TokenType::RightCurlyBrace => {
+                depth -= 1;
+                if depth == 0 {
+                    end = k + 1;
+                }
+            }
+            TokenType::Semicolon
+            | TokenType::Directive
+            | TokenType::DirectiveEnd
+            | TokenType::HeaderIfDefineStart
+            | TokenType::HeaderIfDefineEnd
+            | TokenType::HeaderModule
+                if depth == 0 =>
+            {
+                end = k + 1
+            }
+            _ => {}
+        }
+    }
+    if depth == 0 {
+        out.len()
+    } else {
+        end
+    }
+}
+
+/// tokens of `text`, placed at `span`
+fn lex(text: &str, span: Span) -> Vec<Token> {
+    let tokens = match Tokenizer::new(text).tokenize() {
+        Ok(tokens) => tokens,
+        Err(failure) => failure.tokens,
+    };
+    tokens.into_iter().map(|t| t.with_location(span)).collect()
+}
+
+/// tokens as written in the source, separated where they were
+fn spell(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    for (k, token) in tokens.iter().enumerate() {
+        if k > 0 {
+            let previous = &tokens[k - 1];
+            let apart = previous.get_span().end.offset < token.get_span().start.offset;
+            // tokens of expansions share their span
+            let merged = is_word(previous) && is_word(token);
+            if apart || merged {
+                text.push(' ');
+            }
+        }
+        text.push_str(&token.get_token_value());
+    }
+    text
+}
+
+/// #arg, the argument as a string literal
+fn stringize(arg: &[Token]) -> String {
+    let mut text = String::from("\"");
+    for c in spell(arg).chars() {
+        if c == '"' || c == '\\' {
+            text.push('\\');
+        }
+        text.push(c);
+    }
+    text.push('"');
+    text
+}
+
+/// start or end of an #if branch kept as cfg, the start holds the predicate
+fn marker(hash: &Token, typ: TokenType, value: &str, span: Span) -> Token {
+    Token::new(
+        value.to_string(),
+        TokenKind::Preprocessors,
+        typ,
+        span.start.line,
+        hash.get_token_id(),
+    )
+    .with_span(span)
+}
+
+/// directive left for the translation, as one token
+fn directive_token(hash: &Token, name: &str, rest: &[Token], span: Span) -> Token {
+    let text = format!("#{} {}", name, spell(rest));
+    Token::new(
+        text.trim_end().to_string(),
+        TokenKind::Preprocessors,
+        TokenType::Directive,
+        span.start.line,
+        hash.get_token_id(),
+    )
+    .with_span(span)
+}
+
+/// included file translated into a module, rather than placed at the #include
+pub fn is_header(file: &Path) -> bool {
+    file.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| HEADER_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{cfg_table, preprocess, Config};
+    use crate::library::lexeme::definition::TokenType;
+    use crate::library::lexer::tokenizer::Tokenizer;
+    use std::fs;
+
+    /// token values of the preprocessed text, joined with spaces
+    fn expand(text: &str, config: &Config) -> String {
+        let tokens = Tokenizer::new(text).tokenize().unwrap();
+        let tokens = preprocess(tokens, text, None, config).unwrap();
+        tokens
+            .iter()
+            .map(|t| t.get_token_value())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn errors(text: &str) -> Vec<String> {
+        let tokens = Tokenizer::new(text).tokenize().unwrap();
+        preprocess(tokens, text, None, &Config::default())
+            .unwrap_err()
+            .errors
+            .iter()
+            .map(|e| e.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_that_macros_are_expanded() {
+        // macros given on the command line are expanded, not translated
+        let config = Config {
+            defines: vec![
+                "SQUARE(x)=((x) * (x))".to_string(),
+                "STR(x)=#x".to_string(),
+                "CAT(a, b)=a ## b".to_string(),
+                "LOG(fmt, ...)=printf(fmt, __VA_ARGS__)".to_string(),
+            ],
+            include_dirs: Vec::new(),
+            cfg: None,
+        };
+        let text = "#define N size\n\
+                    #define SELF SELF + 1\n\
+                    int a = SQUARE(N + 1); STR(a \"b\"); CAT(x, 1); LOG(\"%d\", 1, 2); SELF; SQUARE;";
+        assert_eq!(
+            expand(text, &config),
+            "int a = ( ( size + 1 ) * ( size + 1 ) ) ; \"a \\\"b\\\"\" ; x1 ; \
+             printf ( \"%d\" , 1 , 2 ) ; SELF + 1 ; SQUARE ;"
+        );
+    }
+
+    #[test]
+    fn test_that_conditional_groups_are_selected() {
+        let text = "#define A 2\n\
+                    #if A > 1 && defined(A)\nyes1\n#else\nno1\n#endif\n\
+                    #ifdef B\nno2\n#elif !defined B\nyes2\n#endif\n\
+                    #ifndef A\nno3\n#if 1\nno4\n#endif\n#else\nyes3\n#endif\n\
+                    #undef A\n#if A\nno5\n#endif";
+        assert_eq!(
+            expand(text, &Config::default()),
+            "#define A 2 \n yes1 yes2 yes3"
+        );
+    }
+
+    #[test]
+    fn test_that_constants_are_declared_at_top_level() {
+        let text = "#define MAX 64\n\
+                    #define NAME \"crust\"\n\
+                    #define CALL f()\n\
+                    #define HDR \"h.h\"\n\
+                    int main() {\n\
+                    #define LIMIT (MAX * 2)\n\
+                    int a[LIMIT]; CALL;\n\
+                    #if LIMIT > 100\nyes;\n#endif\n\
+                    }\n\
+                    #define MAX 32\n\
+                    int b = MAX;";
+        assert_eq!(
+            expand(text, &Config::default()),
+            "#define MAX 64 \n #define NAME \"crust\" \n #define HDR \"h.h\" \n \
+             #define LIMIT ( MAX * 2 ) \n int main ( ) { int a [ LIMIT ] ; f ( ) ; yes ; } \
+             int b = 32 ;"
+        );
+    }
+
+    #[test]
+    fn test_that_function_like_macros_are_declared() {
+        let text = "#define SQ(x) ((x) * (x))\n\
+                    #define CAT(a, b) a ## b\n\
+                    #define ID y\n\
+                    int f() {\n\
+                    #define TWICE(x) (2 * SQ(x) + ID)\n\
+                    return SQ(2) + CAT(x, 1);\n\
+                    }\n\
+                    #if SQ(2) == 4\nyes;\n#endif";
+        assert_eq!(
+            expand(text, &Config::default()),
+            "#define SQ ( x ) ( ( x ) * ( x ) ) \n #define CAT ( a , b ) a ## b \n \
+             #define TWICE ( x ) ( 2 * SQ ( x ) + y ) \n \
+             int f ( ) { return SQ ( 2 ) + x1 ; } yes ;"
+        );
+    }
+
+    #[test]
+    fn test_that_macros_the_translation_can_not_read_are_expanded() {
+        let text = "#define FOREACH(i, n) for (int i = 0; i < (n); i++)\n\
+                    #define DECL(t, v) t v = 0\n\
+                    #define F(x) x +\n\
+                    #define G(x) int x\n\
+                    #define ADD(x, y) x + y\n\
+                    #define DBL(x) (x * 2)\n\
+                    void f() { FOREACH(k, 3) { g(k); } DECL(long, z); int w = F(1) 2; G(q); \
+                    int s = ADD(w, 2) * 3; int d = DBL(w + 1); }";
+        assert_eq!(
+            expand(text, &Config::default()),
+            "void f ( ) { for ( int k = 0 ; k < ( 3 ) ; k ++ ) { g ( k ) ; } long z = 0 ; \
+             int w = 1 + 2 ; int q ; int s = w + 2 * 3 ; int d = ( w + 1 * 2 ) ; }"
+        );
+    }
+
+    #[test]
+    fn test_that_constants_the_translation_can_not_read_are_expanded() {
+        let text = "#define X 1 2\n#define Y 3\n#define TWO 1 + 1\n#define NEG -1\n\
+                    int a = f(X); int b = Y * TWO * NEG;";
+        assert_eq!(
+            expand(text, &Config::default()),
+            "#define Y 3 \n #define NEG - 1 \n int a = f ( 1 2 ) ; int b = Y * 1 + 1 * NEG ;"
+        );
+    }
+
+    #[test]
+    fn test_that_options_define_macros_and_continuations_join_lines() {
+        let config = Config {
+            defines: vec!["DEBUG".to_string(), "LEVEL=3".to_string()],
+            include_dirs: Vec::new(),
+            cfg: None,
+        };
+        let text = "#if defined(DEBUG) && \\\n    LEVEL == 3\nx = LEVEL;\n#endif";
+        assert_eq!(expand(text, &config), "x = 3 ;");
+    }
+
+    #[test]
+    fn test_that_line_and_file_are_predefined() {
+        let text =
+            "#define WHERE __LINE__\nint a = __LINE__;\n\nint b = WHERE;\nchar *f = __FILE__;";
+        let tokens = Tokenizer::new(text).tokenize().unwrap();
+        let main = std::path::Path::new("src").join("main.c");
+        let tokens = preprocess(tokens, text, Some(&main), &Config::default()).unwrap();
+        let values: Vec<String> = tokens.iter().map(|t| t.get_token_value()).collect();
+        let file = format!("{:?}", main.to_string_lossy());
+        let start = values.iter().position(|value| value == "int").unwrap();
+        assert_eq!(
+            values[start..],
+            [
+                "int", "a", "=", "2", ";", "int", "b", "=", "4", ";", "char", "*", "f", "=", &file,
+                ";"
+            ]
+        );
+        // the file of a text which was not read from one is not known
+        assert_eq!(
+            expand("int f = __FILE__;", &Config::default()),
+            "int f = __FILE__ ;"
+        );
+    }
+
+    #[test]
+    fn test_that_headers_are_translated_into_modules() {
+        let dir = std::env::temp_dir().join(format!("crust-pp-{}", std::process::id()));
+        fs::create_dir_all(dir.join("inc")).unwrap();
+        fs::write(
+            dir.join("a.h"),
+            "#pragma once\nint a;\n#include <b.h>\n#define A_MAX 3\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("inc/b.h"),
+            "#ifndef B_H\n#define B_H\nint b;\n#endif\n",
+        )
+        .unwrap();
+        fs::write(dir.join("table.inc"), "int t;\n").unwrap();
+        let text = "#include \"a.h\"\n#include \"a.h\"\n#include <b.h>\n#include <stdio.h>\n\
+                    #include \"table.inc\"\nint c = A_MAX;";
+        let config = Config {
+            defines: Vec::new(),
+            include_dirs: vec![dir.join("inc")],
+            cfg: None,
+        };
+        let tokens = Tokenizer::new(text).tokenize().unwrap();
+        let main = dir.join("main.c");
+        let tokens = preprocess(tokens, text, Some(&main), &config).unwrap();
+        let values: Vec<String> = tokens.iter().map(|t| t.get_token_value()).collect();
+        fs::remove_dir_all(&dir).unwrap();
+        // the modules a header includes come first, each once
+        let b = dir.join("inc").join("b.h").to_string_lossy().to_string();
+        let a = dir.join("a.h").to_string_lossy().to_string();
+        assert_eq!(
+            values,
+            vec![
+                b.as_str(),
+                a.as_str(),
+                "#include <stdio.h>",
+                "int",
+                "t",
+                ";",
+                "int",
+                "c",
+                "=",
+                "A_MAX",
+                ";"
+            ]
+        );
+        assert_eq!(tokens[0].get_token_type(), TokenType::HeaderModule);
+        assert_eq!(tokens[2].get_token_type(), TokenType::Directive);
+        // fragments which are not headers are placed at their #include
+        assert_eq!(tokens[4].get_span().start.line, 5);
+    }
+
+    #[test]
+    fn test_that_bad_directives_are_reported() {
+        assert_eq!(
+            errors(
+                "#if 1\n#else\n#else\n#endif\n#endif\n#foo\n#error stop here\n#if\n#endif\n#if 1"
+            ),
+            vec![
+                "#else after #else",
+                "#endif without #if",
+                "invalid preprocessing directive `#foo`",
+                "#error stop here",
+                "#if with no expression",
+                "unterminated conditional directive",
+            ]
+        );
+        assert_eq!(
+            errors("#define F(a, b) a ## b\nF(1);\n#define G(x) #y\n#define H ## x"),
+            vec![
+                "macro `F` requires 2 arguments, but 1 given",
+                "`#` is not followed by a macro parameter",
+                "`##` cannot appear at either end of a macro expansion",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_that_configuration_conditionals_are_kept_as_cfg() {
+        let config = Config {
+            defines: vec!["FAST".to_string()],
+            include_dirs: Vec::new(),
+            cfg: Some(cfg_table(&["USE_SSL=ssl".to_string()])),
+        };
+        let text = "#ifdef _WIN32\nwin;\n#elif defined(__linux__) && !defined(NDEBUG)\nlinux;\n\
+                    #else\nother;\n#endif\n\
+                    #ifndef USE_SSL\nplain;\n#endif\n\
+                    #if defined(FAST) || defined(_WIN32)\nfast;\n#endif\n\
+                    int v = f(\n#ifdef _WIN32\n1\n#else\n2\n#endif\n);";
+        assert_eq!(
+            expand(text, &config),
+            "windows win ; #endif \
+             all(not(windows), target_os = \"linux\", debug_assertions) linux ; #endif \
+             all(not(windows), not(all(target_os = \"linux\", debug_assertions))) other ; #endif \
+             not(feature = \"ssl\") plain ; #endif \
+             fast ; int v = f ( 2 ) ;"
+        );
+        // the cfg names rust sets are not features
+        let table = cfg_table(&[
+            "ON_UNIX=unix".to_string(),
+            "TESTING=test".to_string(),
+            "LINUX=target_os = \"linux\"".to_string(),
+            "USE_SSL=ssl".to_string(),
+            "FAST".to_string(),
+        ]);
+        assert_eq!(
+            table[table.len() - 5..].to_vec(),
+            [
+                ("ON_UNIX", "unix"),
+                ("TESTING", "test"),
+                ("LINUX", "target_os = \"linux\""),
+                ("USE_SSL", "feature = \"ssl\""),
+                ("FAST", "feature = \"fast\""),
+            ]
+            .iter()
+            .map(|(name, predicate)| (name.to_string(), predicate.to_string()))
+            .collect::<Vec<(String, String)>>()
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Layout of the crate a whole project is translated into, one module per file
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::library::codegen::helper::module_name;
+use crate::library::doc::CRUST_BANNER;
+
+/// extensions of the sources and headers translated in project mode
+const SOURCE_EXTENSIONS: [&str; 10] = [
+    "c", "cc", "cpp", "cxx", "c++", "h", "hh", "hpp", "hxx", "h++",
+];
+
+/// module path of a file in the crate, `net/socket.c` -> ["net", "socket"]
+pub type ModulePath = Vec<String>;
+
+pub fn is_source(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| SOURCE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/**
+ * collect_sources:
+ * the files of the project, directories are searched for sources and
+ * headers. The root, which module paths start from, is the directory
+ * holding all of them
+ */
+pub fn collect_sources(inputs: &[String]) -> io::Result<(PathBuf, Vec<PathBuf>)> {
+    let mut files = Vec::new();
+    for input in inputs {
+        let path = PathBuf::from(input.trim());
+        if path.is_dir() {
+            search(&path, &mut files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files.dedup();
+    let root = inputs
+        .iter()
+        .map(|input| PathBuf::from(input.trim()))
+        .map(|path| {
+            if path.is_dir() {
+                path
+            } else {
+                path.parent().map(Path::to_path_buf).unwrap_or_default()
+            }
+        })
+        .reduce(|common, path| common_ancestor(&common, &path))
+        .unwrap_or_default();
+    Ok((root, files))
+}
+
+fn search(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            search(&path, files)?;
+        } else if is_source(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn common_ancestor(a: &Path, b: &Path) -> PathBuf {
+    a.components()
+        .zip(b.components())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a)
+        .collect()
+}
+
+/// module path of `file` from its directories below `root`, a file out
+/// of the root is a module of the crate root. Cargo builds `src/main.rs`,
+/// `src/lib.rs` and the files of `src/bin/` as crate roots, such modules
+/// get a trailing `_`, `main.c` -> main_
+pub fn module_path(root: &Path, file: &Path) -> ModulePath {
+    let relative = file
+        .strip_prefix(root)
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|_| PathBuf::from(file.file_name().unwrap_or_default()));
+    let mut path: ModulePath = relative
+        .iter()
+        .map(|component| module_name(&component.to_string_lossy()))
+        .collect();
+    let reserved = match path.as_slice() {
+        [name] => name == "main" || name == "lib",
+        [dir, _, ..] => dir == "bin",
+        [] => false,
+    };
+    if reserved {
+        path[0].push('_');
+    }
+    path
+}
+
+/**
+ * crate_files:
+ * the files below src/ with their content. The binary `main` is main.rs,
+ * a crate without one is a library. Each module is declared by its parent,
+ * a directory holding modules and no source of its own is a `mod.rs`.
+ * Modules are declared in the order of `modules`, the ones defining
+ * macro_rules! first
+ */
+pub fn crate_files(modules: &[(ModulePath, String)], main: Option<&str>) -> Vec<(PathBuf, String)> {
+    let mut nodes: Vec<ModulePath> = Vec::new();
+    for (path, _) in modules {
+        for len in 1..=path.len() {
+            if !nodes.contains(&path[..len].to_vec()) {
+                nodes.push(path[..len].to_vec());
+            }
+        }
+    }
+    let declarations = |parent: &[String], public: bool| -> String {
+        nodes
+            .iter()
+            .filter(|node| node.len() == parent.len() + 1 && node.starts_with(parent))
+            .map(|node| {
+                let visibility = if public { "pub " } else { "" };
+                // macro_rules! of the module are visible after the declaration
+                format!("#[macro_use]\n{}mod {};\n", visibility, node[parent.len()])
+            })
+            .collect()
+    };
+    let mut files = Vec::new();
+    for node in nodes.iter().collect::<BTreeSet<_>>() {
+        let file = PathBuf::from(node.join("/"));
+        match modules.iter().find(|(path, _)| path == node) {
+            Some((_, source)) => files.push((
+                file.with_extension("rs"),
+                after_banner(source, &declarations(node, true)),
+            )),
+            None => files.push((file.join("mod.rs"), declarations(node, true))),
+        }
+    }
+    files.push(match main {
+        Some(source) => (
+            PathBuf::from("main.rs"),
+            after_banner(source, &declarations(&[], false)),
+        ),
+        None => (PathBuf::from("lib.rs"), declarations(&[], true)),
+    });
+    files
+}
+
+/// `text` placed after the banner of the translated `source`
+fn after_banner(source: &str, text: &str) -> String {
+    match source.strip_prefix(CRUST_BANNER) {
+        Some(rest) => format!("{}\n{}{}", CRUST_BANNER, text, rest),
+        None => format!("{}{}", text, source),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crate_files, module_path};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_that_modules_follow_the_directories() {
+        let root = Path::new("proj");
+        assert_eq!(module_path(root, Path::new("proj/util.c")), vec!["util"]);
+        assert_eq!(
+            module_path(root, Path::new("proj/net/tcp-socket.h")),
+            vec!["net", "tcp_socket"]
+        );
+        assert_eq!(module_path(root, Path::new("/usr/include/x.h")), vec!["x"]);
+        // rust keywords can not name a module
+        assert_eq!(
+            module_path(root, Path::new("proj/mod/type.h")),
+            vec!["mod_", "type_"]
+        );
+        // nor be a crate root of cargo
+        assert_eq!(module_path(root, Path::new("proj/main.c")), vec!["main_"]);
+        assert_eq!(module_path(root, Path::new("proj/lib.h")), vec!["lib_"]);
+        assert_eq!(
+            module_path(root, Path::new("proj/bin/tool.c")),
+            vec!["bin_", "tool"]
+        );
+        assert_eq!(
+            module_path(root, Path::new("proj/net/main.c")),
+            vec!["net", "main"]
+        );
+    }
+
+    #[test]
+    fn test_that_modules_are_declared_by_their_parent() {
+        let modules = vec![
+            (vec!["util".to_string()], "pub fn f() {}\n".to_string()),
+            (
+                vec!["net".to_string(), "socket".to_string()],
+                "pub struct S {}\n".to_string(),
+            ),
+        ];
+        let files = crate_files(&modules, Some("fn main() {}\n"));
+        let names: Vec<PathBuf> = files.iter().map(|(name, _)| name.clone()).collect();
+        assert_eq!(
+            names,
+            vec![
+                PathBuf::from("net/mod.rs"),
+                PathBuf::from("net/socket.rs"),
+                PathBuf::from("util.rs"),
+                PathBuf::from("main.rs"),
+            ]
+        );
+        assert_eq!(files[0].1, "#[macro_use]\npub mod socket;\n");
+        assert_eq!(
+            files[3].1,
+            "#[macro_use]\nmod util;\n#[macro_use]\nmod net;\nfn main() {}\n"
+        );
+        let files = crate_files(&modules, None);
+        assert_eq!(files[3].0, PathBuf::from("lib.rs"));
+        assert!(files[3].1.contains("pub mod util;"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Files of the cargo project the crate is written into, written without cargo
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// editions a manifest can be written for
+pub const EDITIONS: [&str; 4] = ["2015", "2018", "2021", "2024"];
+
+pub const DEFAULT_EDITION: &str = "2021";
+
+/// the .gitignore written by `cargo new`
+const GITIGNORE: &str = "/target\n";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// name of the package, a valid crate name
+    pub name: String,
+    pub edition: String,
+    /// cargo features tested by the cfg attributes of the crate
+    pub features: BTreeSet<String>,
+}
+
+impl Manifest {
+    /// manifest of the package in `dir`, named after the directory
+    pub fn new(dir: &Path, edition: &str) -> Manifest {
+        let name = dir
+            .file_name()
+            .map(|name| package_name(&name.to_string_lossy()))
+            .unwrap_or_else(|| "crate".to_string());
+        Manifest {
+            name,
+            edition: edition.to_string(),
+            features: BTreeSet::new(),
+        }
+    }
+
+    /// the content of Cargo.toml, as `cargo new` writes it, with the
+    /// features enabling nothing but the code tested for them
+    pub fn to_toml(&self) -> String {
+        let mut toml = format!(
+            "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"{}\"\n\n[dependencies]\n",
+            self.name, self.edition
+        );
+        if !self.features.is_empty() {
+            toml.push_str("\n[features]\n");
+            for feature in &self.features {
+                toml.push_str(&format!("{} = []\n", feature));
+            }
+        }
+        toml
+    }
+}
+
+/// names of the cargo features the `#[cfg]` attributes of `source` test,
+/// `#[cfg(all(unix, feature = "debug"))]` -> debug
+pub fn features(source: &str) -> BTreeSet<String> {
+    let mut features = BTreeSet::new();
+    for (at, _) in source.match_indices("#[cfg(") {
+        let attribute = &source[at..];
+        let attribute = &attribute[..attribute.find(")]").unwrap_or(attribute.len())];
+        for (at, _) in attribute.match_indices("feature = \"") {
+            let name = &attribute[at + "feature = \"".len()..];
+            if let Some(end) = name.find('"') {
+                features.insert(name[..end].to_string());
+            }
+        }
+    }
+    features
+}
+
+/// `name` made a valid package name, letters, digits, `-` and `_` not
+/// starting with a digit
+pub fn package_name(name: &str) -> String {
+    let mut package: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if package.is_empty() || package.starts_with(|c: char| c.is_ascii_digit()) {
+        package.insert(0, '_');
+    }
+    package
+}
+
+/**
+ * write_project:
+ * writes Cargo.toml, .gitignore and the `sources` below src/ of the
+ * project in `dir`. None of them is written when one exists already,
+ * unless `force`. A file of src/ left from an earlier layout, lib.rs
+ * when main.rs is written, is removed with `force`
+ */
+pub fn write_project(
+    dir: &Path,
+    manifest: &Manifest,
+    sources: &[(PathBuf, String)],
+    force: bool,
+) -> io::Result<Vec<PathBuf>> {
+    let src = dir.join("src");
+    let mut files: Vec<(PathBuf, &str)> = Vec::new();
+    let toml = manifest.to_toml();
+    files.push((dir.join("Cargo.toml"), &toml));
+    files.push((dir.join(".gitignore"), GITIGNORE));
+    for (file, source) in sources {
+        files.push((src.join(file), source));
+    }
+    if !force {
+        if let Some((file, _)) = files.iter().find(|(file, _)| file.exists()) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "`{}` already exists, use --force to overwrite it",
+                    file.display()
+                ),
+            ));
+        }
+    }
+    for stale in ["main.rs", "lib.rs"] {
+        let stale = src.join(stale);
+        if force && stale.exists() && !files.iter().any(|(file, _)| *file == stale) {
+            fs::remove_file(stale)?;
+        }
+    }
+    // modules are written before their parents
+    files.sort_by_key(|(file, _)| std::cmp::Reverse(file.components().count()));
+    let mut written = Vec::new();
+    for (file, content) in files {
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&file, content)?;
+        written.push(file);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{features, package_name, write_project, Manifest};
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_that_manifests_are_written_as_by_cargo() {
+        let manifest = Manifest::new(Path::new("out/my demo"), "2018");
+        assert_eq!(manifest.name, "my_demo");
+        assert_eq!(
+            manifest.to_toml(),
+            "[package]\nname = \"my_demo\"\nversion = \"0.1.0\"\nedition = \"2018\"\n\n[dependencies]\n"
+        );
+        assert_eq!(package_name("2d-engine"), "_2d-engine");
+    }
+
+    #[test]
+    fn test_that_features_tested_by_cfg_are_declared() {
+        let mut manifest = Manifest::new(Path::new("demo"), "2021");
+        manifest.features = features(
+            "#[cfg(feature = \"debug\")]\nfn f() {}\n\
+             #[cfg(all(unix, not(feature = \"ssl\")))] let a = \"feature = \\\"x\\\"\";\n\
+             #[cfg(feature = \"debug\")]\nfn g() {}\n",
+        );
+        assert!(manifest
+            .to_toml()
+            .ends_with("[dependencies]\n\n[features]\ndebug = []\nssl = []\n"));
+    }
+
+    #[test]
+    fn test_that_projects_are_not_overwritten_without_force() {
+        let dir = std::env::temp_dir().join(format!("crust-manifest-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let manifest = Manifest::new(&dir, "2021");
+        let sources = vec![(PathBuf::from("main.rs"), "fn main() {}\n".to_string())];
+        write_project(&dir, &manifest, &sources, false).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join(".gitignore")).unwrap(),
+            "/target\n"
+        );
+        assert!(fs::read_to_string(dir.join("Cargo.toml"))
+            .unwrap()
+            .contains("edition = \"2021\""));
+
+        let err = write_project(&dir, &manifest, &sources, false).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+        let sources = vec![(PathBuf::from("lib.rs"), "pub fn f() {}\n".to_string())];
+        write_project(&dir, &manifest, &sources, true).unwrap();
+        assert!(dir.join("src/lib.rs").exists());
+        assert!(!dir.join("src/main.rs").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
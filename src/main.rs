@@ -1,25 +1,81 @@
 #![allow(dead_code)]
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
+use std::fs;
 use std::fs::File;
 use std::io;
 use std::io::BufReader;
+use std::io::IsTerminal;
 use std::io::Read;
 use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
 
 use getopts::Options;
 
+use library::ast::TranslationUnit;
+use library::codegen::emitter::{self, Placement};
+use library::codegen::helper::module_name;
+use library::codegen::mutability::Mutability;
+use library::codegen::printer;
+use library::diagnostics::json;
+use library::diagnostics::render::Renderer;
+use library::diagnostics::sarif;
+use library::diagnostics::Diagnostics;
+use library::doc::CRUST_BANNER;
 use library::lexer::tokenizer::Tokenizer;
 use library::parser::parser;
+use library::preprocessor::preprocessor::{self, Config};
+use library::project::layout::{self, ModulePath};
+use library::project::manifest::{self, Manifest};
 
 mod library;
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MessageFormat {
+    // rendered for the terminal on stderr
+    Human,
+    // one json object per line on stdout
+    Json,
+}
+
 struct Settings {
-    strict: bool,
+    // mut where written, unless --strict or --loose
+    mutability: Mutability,
     project_name: Option<String>,
     files: Vec<String>,
+    message_format: MessageFormat,
+    // SARIF log of the diagnostics of all files
+    sarif: Option<String>,
+    // macros and include directories given with -D and -I, and the
+    // configuration macros kept as cfg
+    preprocessor: Config,
+    // the cargo project of --project-name
+    cargo: CargoSettings,
+    // rustfmt run over the laid out translation, when it is installed
+    rustfmt: bool,
+}
+
+#[derive(Clone, Default)]
+struct CargoSettings {
+    // package name, the name of the project directory by default
+    package: Option<String>,
+    edition: String,
+    // crate type given with --crate-type, a binary when a file defines main by default
+    binary: Option<bool>,
+    // overwrite the files of an existing project
+    force: bool,
+}
+
+impl Settings {
+    /// progress messages, kept out of stdout when it carries json
+    fn status(&self, message: &str) {
+        if self.message_format == MessageFormat::Human {
+            println!("{}", message);
+        }
+    }
 }
 
 fn print_usage(program: &str, opts: Options) {
@@ -32,8 +88,80 @@ fn main() {
     let program = args[0].clone();
 
     let mut opts = Options::new();
-    opts.optflag("s", "strict", "Strict mode (immutable)");
+    opts.optflag(
+        "s",
+        "strict",
+        "Strict mode, every variable immutable instead of mutable where written",
+    );
+    opts.optflag(
+        "l",
+        "loose",
+        "Loose mode, every variable mutable instead of mutable where written",
+    );
     opts.optopt("p", "project-name", "Cargo project name", "NAME");
+    opts.optopt(
+        "",
+        "message-format",
+        "Format of the diagnostics, human (default) or json",
+        "FORMAT",
+    );
+    opts.optopt(
+        "",
+        "sarif",
+        "Write the diagnostics as a SARIF log to FILE",
+        "FILE",
+    );
+    opts.optmulti(
+        "D",
+        "define",
+        "Define a macro for the preprocessor, NAME alone is defined as 1",
+        "NAME[=VALUE]",
+    );
+    opts.optmulti(
+        "I",
+        "include",
+        "Add DIR to the directories searched for included headers",
+        "DIR",
+    );
+    opts.optflag(
+        "",
+        "cfg",
+        "Keep #if groups testing configuration macros as #[cfg] attributes",
+    );
+    opts.optmulti(
+        "",
+        "cfg-map",
+        "Map a configuration macro to a cfg predicate, implies --cfg",
+        "NAME=PREDICATE",
+    );
+    opts.optopt(
+        "",
+        "package",
+        "Package name of the cargo project, its directory name by default",
+        "NAME",
+    );
+    opts.optopt(
+        "",
+        "edition",
+        "Rust edition of the cargo project, 2021 by default",
+        "EDITION",
+    );
+    opts.optopt(
+        "",
+        "crate-type",
+        "Write the project as a bin or lib crate, a binary when a file defines main by default",
+        "TYPE",
+    );
+    opts.optflag(
+        "",
+        "force",
+        "Overwrite the files of an existing cargo project",
+    );
+    opts.optflag(
+        "",
+        "no-rustfmt",
+        "Keep the layout of CRUST instead of formatting the output with rustfmt",
+    );
     opts.optflag("h", "help", "show this help message");
 
     let matches = match opts.parse(&args[1..]) {
@@ -44,15 +172,88 @@ fn main() {
         print_usage(&program, opts);
         return;
     }
-    let settings = if matches.free.is_empty() {
+    let message_format = match matches.opt_str("message-format").as_deref() {
+        None | Some("human") => MessageFormat::Human,
+        Some("json") => MessageFormat::Json,
+        Some(other) => {
+            eprintln!(
+                "Unknown message format '{}', expected `human` or `json`.",
+                other
+            );
+            std::process::exit(1);
+        }
+    };
+    let sarif = matches.opt_str("sarif");
+    let edition = matches
+        .opt_str("edition")
+        .unwrap_or_else(|| manifest::DEFAULT_EDITION.to_string());
+    if !manifest::EDITIONS.contains(&edition.as_str()) {
+        eprintln!(
+            "Unknown edition '{}', expected one of {}.",
+            edition,
+            manifest::EDITIONS.join(", ")
+        );
+        std::process::exit(1);
+    }
+    let binary = match matches.opt_str("crate-type").as_deref() {
+        None => None,
+        Some("bin") => Some(true),
+        Some("lib") => Some(false),
+        Some(other) => {
+            eprintln!("Unknown crate type '{}', expected `bin` or `lib`.", other);
+            std::process::exit(1);
+        }
+    };
+    let mutability = match (matches.opt_present("s"), matches.opt_present("l")) {
+        (true, true) => {
+            eprintln!("The options --strict and --loose can not be used together.");
+            std::process::exit(1);
+        }
+        (true, false) => Mutability::Strict,
+        (false, true) => Mutability::Loose,
+        (false, false) => Mutability::Inferred,
+    };
+    let rustfmt = !matches.opt_present("no-rustfmt");
+    let cargo = CargoSettings {
+        package: matches
+            .opt_str("package")
+            .map(|name| manifest::package_name(&name)),
+        edition,
+        binary,
+        force: matches.opt_present("force"),
+    };
+    let preprocessor = Config {
+        defines: matches.opt_strs("D"),
+        include_dirs: matches
+            .opt_strs("I")
+            .into_iter()
+            .map(PathBuf::from)
+            .collect(),
+        cfg: if matches.opt_present("cfg") || matches.opt_present("cfg-map") {
+            Some(preprocessor::cfg_table(&matches.opt_strs("cfg-map")))
+        } else {
+            None
+        },
+    };
+    let mut settings = if matches.free.is_empty() {
         get_settings_interactively()
     } else {
         Settings {
-            strict: matches.opt_present("s"),
+            mutability,
             project_name: matches.opt_str("p"),
             files: matches.free,
+            message_format,
+            sarif: None,
+            preprocessor: Config::default(),
+            cargo: CargoSettings::default(),
+            rustfmt: true,
         }
     };
+    settings.message_format = message_format;
+    settings.sarif = sarif;
+    settings.preprocessor = preprocessor;
+    settings.cargo = cargo;
+    settings.rustfmt = rustfmt;
 
     invoke(&settings);
 }
@@ -64,13 +265,16 @@ fn get_settings_interactively() -> Settings {
     io::stdout().flush().expect("FATAL : Buffer flush failed");
     io::stdin().read_line(&mut input).expect("Unable to read");
 
-    let mut strict = String::new();
+    let mut mode = String::new();
 
-    print!("Enter the translation mode [(S/s)trict/(L/l)oose] : ");
+    print!("Enter the translation mode [(I/i)nferred/(S/s)trict/(L/l)oose] : ");
     io::stdout().flush().expect("FATAL : Buffer flush failed");
-    io::stdin().read_line(&mut strict).expect("Unable to read");
-    let strict = strict.trim();
-    let strict = matches!(strict, "S" | "Strict" | "s");
+    io::stdin().read_line(&mut mode).expect("Unable to read");
+    let mutability = match mode.trim() {
+        "S" | "Strict" | "s" => Mutability::Strict,
+        "L" | "Loose" | "l" => Mutability::Loose,
+        _ => Mutability::Inferred,
+    };
 
     let mut cargo = String::new();
     print!("Do you want to create a cargo project :[Y/N]");
@@ -93,97 +297,523 @@ fn get_settings_interactively() -> Settings {
     }
 
     Settings {
-        strict,
+        mutability,
         project_name,
         files: vec![input.trim().to_owned()],
+        message_format: MessageFormat::Human,
+        sarif: None,
+        preprocessor: Config::default(),
+        cargo: CargoSettings::default(),
+        rustfmt: true,
+    }
+}
+
+/// a source read into its syntax tree, kept till the crate it is part of is generated
+struct Parsed {
+    input: String,
+    text: String,
+    unit: TranslationUnit,
+    diagnostics: Diagnostics,
+}
+
+/**
+ * parse:
+ * tokenizes, preprocesses and parses `input`
+ */
+fn parse(input: &str, settings: &Settings) -> Parsed {
+    let file = match File::open(input) {
+        Ok(f) => f,
+        Err(err) => {
+            eprintln!("Unable to open input source file '{}': {}.", input, err);
+            std::process::exit(1);
+        }
+    };
+    // get the reader
+    let mut reader = BufReader::new(&file);
+    let mut text: String = String::new();
+    let size = reader
+        .read_to_string(&mut text)
+        .expect("unable to read file.");
+
+    settings.status(&format!("Input file size : {}bytes ", size));
+
+    let tok = Tokenizer::new(&text);
+    settings.status("Tokenizing");
+
+    let mut diagnostics = Diagnostics::new();
+    let tokens = match tok.tokenize() {
+        Ok(tokens) => tokens,
+        Err(failure) => {
+            for error in &failure.errors {
+                diagnostics.push(error.to_diagnostic());
+            }
+            failure.tokens
+        }
+    };
+
+    settings.status("Preprocessing");
+    let path = PathBuf::from(input);
+    let tokens = match preprocessor::preprocess(tokens, &text, Some(&path), &settings.preprocessor)
+    {
+        Ok(tokens) => tokens,
+        Err(failure) => {
+            for error in &failure.errors {
+                diagnostics.push(error.to_diagnostic());
+            }
+            failure.tokens
+        }
+    };
+    //let mut out: Vec<String> = Vec::new();
+    // tok.tokenize();
+    // let mut ln = 0;
+    // for token in &tokens {
+    //     let mut token_value = token.get_token_value();
+    //     print!("{:?}\n",token);
+    //     if token.get_token_line_num() != ln {
+    //         token_value = "\n".to_string() + &token_value[..];
+    //     }
+    //     ln = token.get_token_line_num();
+    //     out.push(token_value);
+    // }
+
+    settings.status("Invoking Parser....");
+
+    let unit = match parser::init_parser(&tokens) {
+        Ok(unit) => unit,
+        Err(failure) => {
+            for error in &failure.errors {
+                diagnostics.push(error.to_diagnostic());
+            }
+            failure.unit
+        }
+    };
+    Parsed {
+        input: input.to_string(),
+        text,
+        unit,
+        diagnostics,
+    }
+}
+
+/// rust source of the parsed file, its diagnostics are rendered as asked
+/// with --message-format and kept for the SARIF log
+fn generate(
+    parsed: &Parsed,
+    placement: Placement,
+    settings: &Settings,
+    reports: &mut Vec<(String, Diagnostics)>,
+) -> String {
+    let mut diagnostics = parsed.diagnostics.clone();
+    //regenerate the code from the syntax tree
+    let rust = emitter::generate_in(
+        &parsed.unit,
+        settings.mutability,
+        placement,
+        &mut diagnostics,
+    );
+    let rust = printer::pretty(&rust);
+    diagnostics.sort();
+    match settings.message_format {
+        MessageFormat::Human => {
+            let renderer =
+                Renderer::new(&parsed.input, &parsed.text).with_color(io::stderr().is_terminal());
+            eprint!("{}", renderer.render_all(&diagnostics));
+        }
+        MessageFormat::Json => {
+            for diagnostic in &diagnostics {
+                println!("{}", json::to_json(diagnostic, &parsed.input));
+            }
+        }
+    }
+    reports.push((parsed.input.clone(), diagnostics));
+    rust
+}
+
+/// writes the rust source to `fname` and formats it
+fn write_rust(fname: &Path, rust: &str, settings: &Settings) {
+    let mut file = File::create(fname).expect("Unable to open file to write");
+    file.write_all(rust.as_bytes())
+        .expect("Unable to write to file");
+    format_rust(fname, settings);
+}
+
+/// rustfmt over the written file, which is kept as laid out by the
+/// printer when rustfmt is missing or rejects it
+fn format_rust(fname: &Path, settings: &Settings) {
+    if !settings.rustfmt {
+        return;
+    }
+    match Command::new("rustfmt").arg("--").arg(fname).output() {
+        Ok(output) if !output.status.success() => settings.status(&format!(
+            "rustfmt could not format `{}`, it is kept as laid out by CRUST",
+            fname.display()
+        )),
+        _ => {}
     }
 }
 
 fn invoke(settings: &Settings) {
-    for input in settings.files.iter() {
-        let file = match File::open(input) {
-            Ok(f) => f,
-            Err(err) => {
-                println!("Unable to open input source file '{}': {}.", input, err);
-                std::process::exit(1);
+    let mut reports: Vec<(String, Diagnostics)> = Vec::new();
+    match settings.project_name {
+        Some(ref project_name) => translate_project(settings, project_name, &mut reports),
+        None => {
+            // headers translated into modules, once for all the sources,
+            // with the file of their module
+            let mut modules: HashMap<PathBuf, PathBuf> = HashMap::new();
+            for input in settings.files.iter() {
+                translate_file(input.trim(), settings, &mut modules, &mut reports);
+            }
+        }
+    }
+
+    if let Some(ref path) = settings.sarif {
+        let mut file = File::create(path).expect("Unable to open SARIF file to write");
+        file.write_all(sarif::to_sarif(&reports).as_bytes())
+            .expect("Unable to write SARIF file");
+    }
+}
+
+/**
+ * translate_file:
+ * translates `input` next to it, along with the headers it includes
+ * which are translated into modules, once
+ */
+fn translate_file(
+    input: &str,
+    settings: &Settings,
+    modules: &mut HashMap<PathBuf, PathBuf>,
+    reports: &mut Vec<(String, Diagnostics)>,
+) {
+    let mode = settings.mutability.name();
+    let parsed = parse(input, settings);
+    let headers: Vec<Parsed> = parsed
+        .unit
+        .headers()
+        .iter()
+        .map(|header| parse(header, settings))
+        .collect();
+    let units: Vec<&TranslationUnit> = headers.iter().map(|h| &h.unit).collect();
+
+    let mut fname = PathBuf::from(input);
+    fname.set_extension("rs");
+
+    // the modules are declared by the source, next to it, and
+    // written first for rustfmt to find them
+    let dir = fname.parent().unwrap_or_else(|| Path::new(""));
+    // a module taking the name of a translated source or of another
+    // header is renamed, `util_h` or `b_x`
+    let sources: HashSet<PathBuf> = settings
+        .files
+        .iter()
+        .map(|source| canonical_file(&Path::new(source.trim()).with_extension("rs")))
+        .collect();
+    let mut taken: HashSet<PathBuf> = modules.values().cloned().collect();
+    let mut files: Vec<Option<PathBuf>> = Vec::new();
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    for header in &headers {
+        let (name, file) = match modules.get(&canonical(Path::new(&header.input))) {
+            Some(module) => (module_stem(module), None),
+            None => {
+                let name = free_module_name(dir, &header.input, &sources, &taken);
+                let module = dir.join(format!("{}.rs", name));
+                taken.insert(canonical_file(&module));
+                (name, Some(module))
             }
         };
-        // get the reader
-        let mut reader = BufReader::new(&file);
-        let mut text: String = String::new();
-        let size = reader
-            .read_to_string(&mut text)
-            .expect("unable to read file.");
-
-        println!("Input file size : {}bytes ", size);
-
-        let tok = Tokenizer::new(&text);
-        print!("Tokenizing");
-
-        let tokens = tok.tokenize();
-        //let mut out: Vec<String> = Vec::new();
-        // tok.tokenize();
-        // let mut ln = 0;
-        // for token in &tokens {
-        //     let mut token_value = token.get_token_value();
-        //     print!("{:?}\n",token);
-        //     if token.get_token_line_num() != ln {
-        //         token_value = "\n".to_string() + &token_value[..];
-        //     }
-        //     ln = token.get_token_line_num();
-        //     out.push(token_value);
-        // }
-
-        println!("Invoking Parser....");
-
-        let mode = if settings.strict { "Strict" } else { "Loose" };
-        let rust_lexeme = parser::init_parser(&tokens, settings.strict);
-        //regenerate the code from lexemes
-        let mut o: String = String::new();
-        for i in rust_lexeme {
-            o += " ";
-            o += &i[..];
-        }
-
-        let mut fname = PathBuf::from(input);
-
-        fname.set_extension("rs");
-
-        if let Some(ref project_name) = settings.project_name {
-            let child = Command::new("cargo")
-                .args(&["new", "--bin"])
-                .arg(&project_name[..])
-                .status()
-                .expect("Failed to create project");
-            if child.code().unwrap() == 101 {
-                println!(
-                    "Project already exist with the name : {}, it will be overwritten by \
-                          the `crust`.",
-                    project_name
-                );
-
-                fname = PathBuf::from(project_name.clone() + "/src/main.rs");
+        if name != module_name(&header.input) {
+            renamed.insert(header.input.clone(), name);
+        }
+        files.push(file);
+    }
+    for (header, file) in headers.iter().zip(files) {
+        let module = match file {
+            Some(module) => module,
+            None => continue,
+        };
+        modules.insert(canonical(Path::new(&header.input)), canonical_file(&module));
+        let placement = Placement {
+            module: true,
+            modules: None,
+            renamed: Some(&renamed),
+            defined: None,
+            headers: &units,
+        };
+        let rust = generate(header, placement, settings, reports);
+        write_rust(&module, &rust, settings);
+        settings.status(&format!(
+            "Header `{}` is translated into the module `{}`",
+            header.input,
+            module.display()
+        ));
+    }
+
+    let placement = Placement {
+        module: false,
+        modules: None,
+        renamed: Some(&renamed),
+        defined: None,
+        headers: &units,
+    };
+    let rust = generate(&parsed, placement, settings, reports);
+    write_rust(&fname, &rust, settings);
+    settings.status(&format!(
+        "Rust equivalent of source of `{}` in [{} mode ], is generated successfully, \n\
+		View the rust code in file : `{}`",
+        input,
+        mode,
+        fname.display()
+    ));
+}
+
+/**
+ * translate_project:
+ * translates the sources and headers of the project into one crate,
+ * each file into a module laid out as the directories of the project.
+ * The file defining main is the binary, without one the crate is a library
+ */
+fn translate_project(
+    settings: &Settings,
+    project_name: &str,
+    reports: &mut Vec<(String, Diagnostics)>,
+) {
+    let (root, files) = match layout::collect_sources(&settings.files) {
+        Ok(found) => found,
+        Err(err) => {
+            eprintln!("Unable to read the project sources: {}.", err);
+            std::process::exit(1);
+        }
+    };
+    let mut parsed: Vec<Parsed> = files
+        .iter()
+        .map(|file| parse(&file.to_string_lossy(), settings))
+        .collect();
+    // headers included from out of the project join it
+    let mut known: HashSet<PathBuf> = files.iter().map(|file| canonical(file)).collect();
+    let mut next = 0;
+    while next < parsed.len() {
+        for header in parsed[next].unit.headers() {
+            if known.insert(canonical(Path::new(&header))) {
+                parsed.push(parse(&header, settings));
             }
-            if child.success() {
-                fname = PathBuf::from(project_name.clone() + "/src/main.rs");
+        }
+        next += 1;
+    }
+
+    let paths: Vec<ModulePath> = parsed
+        .iter()
+        .map(|p| layout::module_path(&root, Path::new(&p.input)))
+        .collect();
+    let main = match settings.cargo.binary {
+        Some(false) => None,
+        _ => parsed.iter().position(|p| p.unit.has_main()),
+    };
+    if settings.cargo.binary == Some(true) && main.is_none() {
+        eprintln!("No file of the project defines main, the crate can not be a binary.");
+        std::process::exit(1);
+    }
+    let by_file: HashMap<PathBuf, &ModulePath> = parsed
+        .iter()
+        .zip(&paths)
+        .map(|(p, path)| (canonical(Path::new(&p.input)), path))
+        .collect();
+    // the headers of the project give their structures and macros to all units
+    let units: Vec<&TranslationUnit> = parsed
+        .iter()
+        .filter(|p| preprocessor::is_header(Path::new(&p.input)))
+        .map(|p| &p.unit)
+        .collect();
+
+    // modules defining the functions, the binary aside as it is not a module
+    let mut definitions: HashMap<String, &ModulePath> = HashMap::new();
+    for (index, (p, path)) in parsed.iter().zip(&paths).enumerate() {
+        if Some(index) != main {
+            for function in p.unit.functions() {
+                definitions.entry(function).or_insert(path);
             }
-            println!("child code {} ", child.code().unwrap());
-        }
-
-        let mut file = File::create(&fname).expect("Unable to open file to write");
-        file.write_all(o.as_bytes())
-            .expect("Unable to write to file");
-        Command::new("rustfmt")
-            .arg("--")
-            .arg(&fname)
-            .output()
-            .expect("Failed to format the translated code");
-        println!(
-            "Rust equivalent of source of `{}` in [{} mode ], is generated successfully, \n\
-		View the rust code in file : `{}`",
-            input.trim(),
-            mode,
-            fname.display()
-        );
+        }
+    }
+
+    // translations of the files of each module, a header before its source
+    let mut parts: BTreeMap<&ModulePath, Vec<String>> = BTreeMap::new();
+    // modules included by each module
+    let mut graph: BTreeMap<&ModulePath, Vec<&ModulePath>> = BTreeMap::new();
+    for (p, path) in parsed.iter().zip(&paths) {
+        let mut includes: HashMap<String, String> = HashMap::new();
+        for header in p.unit.headers() {
+            let included = by_file
+                .get(&canonical(Path::new(&header)))
+                .copied()
+                .filter(|included| *included != path);
+            graph.entry(path).or_default().extend(included);
+            includes.insert(
+                header,
+                included
+                    .map(|included| included.join("::"))
+                    .unwrap_or_default(),
+            );
+        }
+        // the prototypes of the functions other modules define import them
+        let defined: HashMap<String, String> = definitions
+            .iter()
+            .filter(|(_, defining)| **defining != path)
+            .map(|(function, defining)| (function.clone(), defining.join("::")))
+            .collect();
+        let placement = Placement {
+            module: main.is_none_or(|main| paths[main] != *path),
+            modules: Some(&includes),
+            renamed: None,
+            defined: Some(&defined),
+            headers: &units,
+        };
+        let rust = generate(p, placement, settings, reports);
+        let module = parts.entry(path).or_default();
+        if preprocessor::is_header(Path::new(&p.input)) {
+            module.insert(0, rust);
+        } else {
+            module.push(rust);
+        }
+    }
+    let binary = main
+        .and_then(|main| parts.remove(&paths[main]))
+        .map(join_parts);
+    // included modules are declared first, for their macros
+    let (mut visited, mut order) = (HashSet::new(), Vec::new());
+    for path in parts.keys() {
+        depend(path, &graph, &mut visited, &mut order);
+    }
+    let modules: Vec<(ModulePath, String)> = order
+        .into_iter()
+        .filter_map(|path| {
+            parts
+                .remove(path)
+                .map(|rust| (path.clone(), join_parts(rust)))
+        })
+        .collect();
+
+    let dir = PathBuf::from(project_name);
+    let mut manifest = Manifest::new(&dir, &settings.cargo.edition);
+    if let Some(ref package) = settings.cargo.package {
+        manifest.name = package.clone();
+    }
+    let files = layout::crate_files(&modules, binary.as_deref());
+    manifest.features = files
+        .iter()
+        .flat_map(|(_, source)| manifest::features(source))
+        .collect();
+    let written = match manifest::write_project(&dir, &manifest, &files, settings.cargo.force) {
+        Ok(written) => written,
+        Err(err) => {
+            eprintln!(
+                "Unable to write the cargo project `{}`: {}.",
+                project_name, err
+            );
+            std::process::exit(1);
+        }
+    };
+    for fname in written {
+        if fname.extension().is_some_and(|extension| extension == "rs") {
+            format_rust(&fname, settings);
+        }
+        settings.status(&format!("Written `{}`", fname.display()));
+    }
+    let mode = settings.mutability.name();
+    settings.status(&format!(
+        "Rust equivalent of the project in [{} mode ], is generated successfully \
+		in the cargo project `{}`",
+        mode, project_name
+    ));
+}
+
+/**
+ * free_module_name:
+ * name of the module a header is translated into next to the sources in
+ * `dir`, renamed when a translated source has its name, `util_h` next to
+ * `util.c`, or when the module of another header is `taken`, after the
+ * directory of the header, `b_x` for `b/x.h` after `a/x.h`
+ */
+fn free_module_name(
+    dir: &Path,
+    header: &str,
+    sources: &HashSet<PathBuf>,
+    taken: &HashSet<PathBuf>,
+) -> String {
+    let file = |name: &str| canonical_file(&dir.join(format!("{}.rs", name)));
+    let mut name = module_name(header);
+    if sources.contains(&file(&name)) {
+        name = format!("{}_h", name);
+    }
+    let parent = Path::new(header)
+        .parent()
+        .and_then(Path::file_name)
+        .map(|parent| module_name(&parent.to_string_lossy()));
+    if let (true, Some(parent)) = (taken.contains(&file(&name)), parent) {
+        name = format!("{}_{}", parent.trim_end_matches('_'), name);
+    }
+    let base = name.clone();
+    let mut next = 2;
+    while sources.contains(&file(&name)) || taken.contains(&file(&name)) {
+        name = format!("{}_{}", base, next);
+        next += 1;
+    }
+    name
+}
+
+/// name of the module written to `file`
+fn module_stem(file: &Path) -> String {
+    file.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// `file` in its canonical directory, the file itself may not be written yet
+fn canonical_file(file: &Path) -> PathBuf {
+    let dir = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    canonical(dir).join(file.file_name().unwrap_or_default())
+}
+
+/// `path` after the modules it includes
+fn depend<'a>(
+    path: &'a ModulePath,
+    graph: &BTreeMap<&'a ModulePath, Vec<&'a ModulePath>>,
+    visited: &mut HashSet<&'a ModulePath>,
+    order: &mut Vec<&'a ModulePath>,
+) {
+    if !visited.insert(path) {
+        return;
+    }
+    for included in graph.get(path).into_iter().flatten() {
+        depend(included, graph, visited, order);
+    }
+    order.push(path);
+}
+
+/// translations of the files of one module, the banner and the imports once
+fn join_parts(parts: Vec<String>) -> String {
+    let mut joined = String::new();
+    for part in parts {
+        match part.strip_prefix(CRUST_BANNER) {
+            Some(rest) if !joined.is_empty() => {
+                let imports: Vec<String> = joined
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| line.starts_with("use "))
+                    .map(str::to_string)
+                    .collect();
+                for line in rest.split_inclusive('\n') {
+                    if !imports.contains(&line.trim().to_string()) {
+                        joined.push_str(line);
+                    }
+                }
+            }
+            _ => joined.push_str(&part),
+        }
     }
+    joined
 }